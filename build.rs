@@ -0,0 +1,34 @@
+//! Embed the git commit and build date for `mo --version-json`
+
+use std::path::Path;
+use std::process::Command;
+
+/// Trimmed stdout of a command, if it ran successfully
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn main() {
+    let commit = output("git", &["rev-parse", "--short=12", "HEAD"]);
+
+    // Reproducible builds pin the date through SOURCE_DATE_EPOCH
+    let date = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => output("date", &["-u", "-d", &format!("@{}", epoch), "+%Y-%m-%d"]),
+        Err(_) => output("date", &["-u", "+%Y-%m-%d"]),
+    };
+
+    println!("cargo:rustc-env=MOLE_GIT_COMMIT={}", commit.as_deref().unwrap_or("unknown"));
+    println!("cargo:rustc-env=MOLE_BUILD_DATE={}", date.as_deref().unwrap_or("unknown"));
+
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    for path in [".git/HEAD", ".git/refs/heads"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+}
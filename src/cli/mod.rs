@@ -2,6 +2,18 @@
 
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::commands::clean::CleanPreset;
+use crate::commands::purge::DEFAULT_MAX_DEPTH;
+use crate::commands::uninstall::AppType;
+use crate::core::config::validate_profile_name;
+use crate::core::security::ConfirmPolicy;
+use crate::core::filesystem::parse_size;
+use crate::core::output::OutputFormat;
+use crate::core::paths::CategoryGroup;
+use crate::core::state::State;
+use crate::core::time::parse_duration;
 
 /// Mole-RS: Deep clean and optimize your Ubuntu system
 #[derive(Parser, Debug)]
@@ -13,6 +25,46 @@ pub struct Args {
     #[arg(long, global = true)]
     pub debug: bool,
 
+    /// Show more diagnostics (-v for debug, -vv for trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Only print warnings and errors
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Report logical file sizes (default)
+    #[arg(long, global = true, conflicts_with = "disk_usage")]
+    pub apparent_size: bool,
+
+    /// Report space actually allocated on disk (accounts for compression and sparse files)
+    #[arg(long, global = true)]
+    pub disk_usage: bool,
+
+    /// Stop scanning after this many seconds and show partial results (0 = no limit)
+    #[arg(long, global = true, value_name = "SECS")]
+    pub timeout: Option<u64>,
+
+    /// Use ~/.config/mole-rs/profiles/NAME.toml instead of config.toml
+    #[arg(long, global = true, value_name = "NAME", value_parser = validate_profile_name)]
+    pub profile: Option<String>,
+
+    /// Also append an uncolored copy of the output to this file
+    #[arg(long, global = true, value_name = "PATH", alias = "output-file")]
+    pub log_file: Option<PathBuf>,
+
+    /// If another mole-rs run is deleting, wait for it instead of exiting
+    #[arg(long, global = true)]
+    pub wait: bool,
+
+    /// Print name, version, commit, build date and detected distro as JSON
+    #[arg(long)]
+    pub version_json: bool,
+
+    /// Write the clean/purge/optimize/uninstall plan to a file (.json or .toml)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -21,24 +73,163 @@ pub struct Args {
 pub enum Command {
     /// Deep system cleanup - free up disk space
     Clean {
-        /// Preview changes without deleting
+        /// Delete files (without this, only a preview is shown)
         #[arg(long)]
+        apply: bool,
+
+        /// Deprecated: previewing is the default now
+        #[arg(long, hide = true, conflicts_with = "apply")]
         dry_run: bool,
 
         /// Show detailed debug information
         #[arg(long)]
         debug: bool,
+
+        /// List cleanup categories and their paths without calculating sizes
+        #[arg(long)]
+        categories: bool,
+
+        /// Show system categories (and their sizes) even when not running as root
+        #[arg(long)]
+        include_system: bool,
+
+        /// Only clean this group of categories (repeatable)
+        #[arg(long = "group", value_enum)]
+        groups: Vec<CategoryGroup>,
+
+        /// Check the freed total against the disk's free space before and after
+        #[arg(long, requires = "apply")]
+        verify: bool,
+
+        /// Clean largest categories first, stopping once this much is free (e.g. 10G)
+        #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+        keep_free: Option<u64>,
+
+        /// Archive everything about to be deleted into DIR/mole-backup-<time>.tar.zst first
+        #[arg(long, value_name = "DIR", requires = "apply")]
+        backup: Option<PathBuf>,
+
+        /// Clean up to N categories at once (default: number of CPUs)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+        parallel: Option<u16>,
+
+        /// Skip categories cleaned less than this long ago (e.g. 30m, 6h, 1d)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        skip_if_cleaned_within: Option<Duration>,
+
+        /// In the preview, list the largest entries each category would delete
+        #[arg(long, conflicts_with = "apply")]
+        list_files: bool,
+
+        /// Also select caches written to in the last hour, which are normally spared
+        #[arg(long)]
+        no_recent_protection: bool,
+
+        /// Cleanup intensity: safe keeps browser caches, in-use caches and /var;
+        /// aggressive cleans everything, system caches included
+        #[arg(long, value_enum, conflicts_with = "no_recent_protection")]
+        preset: Option<CleanPreset>,
+
+        /// Ask before deleting each category: y(es), N(o), a(ll remaining), q(uit)
+        #[arg(long, requires = "apply")]
+        confirm_each: bool,
+
+        /// When to ask before deleting: all, large (over 1 GiB or large_file_count files), caution (paths like /tmp), none
+        #[arg(long, value_enum, value_name = "POLICY", default_value_t = ConfirmPolicy::Large)]
+        confirm_policy: ConfirmPolicy,
+
+        /// Instead of caches, list large old files in Downloads (see stale_download_days
+        /// and stale_download_min_size); with --apply, ask about each and move it to the Trash
+        #[arg(long, conflicts_with_all = ["categories", "confirm_each", "backup", "keep_free"])]
+        stale_downloads: bool,
+
+        /// Delete only files and keep every cache subdirectory, for apps that
+        /// fail when their cache layout is missing
+        #[arg(long, conflicts_with = "stale_downloads")]
+        files_only: bool,
     },
 
     /// Analyze disk usage with visual breakdown
     Analyze {
-        /// Path to analyze (defaults to home directory)
-        #[arg(default_value_t = default_analyze_path())]
-        path: String,
+        /// Paths to analyze; several are listed side by side with a grand total
+        /// (defaults to the last analyzed path, then home directory)
+        #[arg(default_values_t = [default_analyze_path()])]
+        paths: Vec<String>,
+
+        /// Show how much of the data was modified today, this week, month, quarter or earlier
+        #[arg(long)]
+        age_histogram: bool,
+
+        /// Show how much space each kind of file (videos, archives, logs, ...) takes
+        #[arg(long)]
+        by_type: bool,
+
+        /// List large text-like files (logs, CSV, JSON, ...) worth compressing, with estimated savings
+        #[arg(long)]
+        suggest_compress: bool,
+
+        /// List files with identical content and the space the extra copies take
+        #[arg(long)]
+        duplicates: bool,
+
+        /// Files the duplicate finder hashes at once (default 4)
+        #[arg(long, value_name = "N", requires = "duplicates", value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: Option<u16>,
+
+        /// Skip files smaller than this when looking for duplicates (default 1M)
+        #[arg(long, value_name = "SIZE", requires = "duplicates", value_parser = parse_size)]
+        min_size: Option<u64>,
+
+        /// Only count files whose name matches this glob, e.g. '*.iso' (repeatable)
+        #[arg(long = "filter", value_name = "GLOB")]
+        filters: Vec<String>,
+
+        /// Skip directories/files whose name or path matches this glob, e.g. '.cache' (repeatable)
+        #[arg(long = "exclude", value_name = "GLOB")]
+        excludes: Vec<String>,
+
+        /// Print only the `Total: X (N items)` line, for scripts
+        #[arg(long)]
+        summary_only: bool,
+
+        /// Follow symlinks and size what they point to; overcounts if links point within the tree
+        #[arg(short = 'L', long)]
+        dereference: bool,
+
+        /// Label entries on another filesystem than the analyzed path and total each filesystem
+        #[arg(long)]
+        group_by_fs: bool,
+
+        /// Only list entries taking at least this % of the total; the rest are summed in one row
+        #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=100))]
+        min_percent: Option<u8>,
+
+        /// Print the entries as text, JSON or CSV (`path,size_bytes,is_dir,percent`)
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text, conflicts_with = "summary_only")]
+        format: OutputFormat,
+    },
+
+    /// Estimate how much clean, purge, optimize and uninstall could reclaim, without deleting anything
+    Scan {
+        /// Print the estimates as text, JSON or CSV
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Same as `--format json`
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
     },
 
     /// Monitor live system status
-    Status,
+    Status {
+        /// Print a single snapshot instead of the live view
+        #[arg(long)]
+        once: bool,
+
+        /// Print the snapshot as JSON (implies --once)
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Clean development project artifacts
     Purge {
@@ -46,9 +237,41 @@ pub enum Command {
         #[arg(long, value_delimiter = ',')]
         paths: Option<Vec<PathBuf>>,
 
-        /// Preview changes without deleting
+        /// How many directory levels below each path to search for projects
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_DEPTH as u16, value_parser = clap::value_parser!(u16).range(1..))]
+        max_depth: u16,
+
+        /// Delete artifacts (without this, only a preview is shown)
         #[arg(long)]
+        apply: bool,
+
+        /// Deprecated: previewing is the default now
+        #[arg(long, hide = true, conflicts_with = "apply")]
         dry_run: bool,
+
+        /// Check the freed total against the disk's free space before and after
+        #[arg(long, requires = "apply")]
+        verify: bool,
+
+        /// Archive the artifacts into DIR/mole-backup-<time>.tar.zst before deleting them
+        #[arg(long, value_name = "DIR", requires = "apply")]
+        backup: Option<PathBuf>,
+
+        /// Also clear package caches shared by all projects (~/.cargo/registry, npm, pip, ...)
+        #[arg(long)]
+        include_global_caches: bool,
+
+        /// Also select artifacts built in the last week, which are normally spared
+        #[arg(long)]
+        no_recent_protection: bool,
+
+        /// Ask before deleting each artifact: y(es), N(o), a(ll remaining), q(uit)
+        #[arg(long, requires = "apply")]
+        confirm_each: bool,
+
+        /// When to ask before deleting: all, large (over 1 GiB or large_file_count files), caution (paths like /tmp), none
+        #[arg(long, value_enum, value_name = "POLICY", default_value_t = ConfirmPolicy::Large)]
+        confirm_policy: ConfirmPolicy,
     },
 
     /// System optimization and maintenance
@@ -56,11 +279,43 @@ pub enum Command {
         /// Preview changes without executing
         #[arg(long)]
         dry_run: bool,
+
+        /// Clean obsolete packages only (e.g. apt-get autoclean) instead of clearing the package cache
+        #[arg(long)]
+        autoclean: bool,
+
+        /// Also clear the recently used files lists (GTK and KDE)
+        #[arg(long)]
+        clear_recent: bool,
+
+        /// Run only this task, by name, e.g. 'Vacuum journal logs' or vacuum-journal-logs (repeatable)
+        #[arg(long, value_name = "TASK")]
+        only: Vec<String>,
+
+        /// Leave out this task, by name (repeatable)
+        #[arg(long, value_name = "TASK")]
+        skip: Vec<String>,
+    },
+
+    /// Inspect the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Restore items from the trash
+    Restore {
+        /// Item number from the listing (omit to list recently trashed items)
+        index: Option<usize>,
+
+        /// Restore under a suffixed name if the original path is taken
+        #[arg(long)]
+        rename: bool,
     },
 
     /// Remove applications and their leftover files
     Uninstall {
-        /// Application name to uninstall
+        /// Application name to uninstall (omit to pick from a searchable list)
         app_name: Option<String>,
 
         /// Preview changes without uninstalling
@@ -70,11 +325,61 @@ pub enum Command {
         /// List installed applications
         #[arg(long)]
         list: bool,
+
+        /// How many directory levels to search for leftovers (1-3)
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=3))]
+        depth: u8,
+
+        /// Search these directories for leftovers instead of the defaults
+        #[arg(long = "path", value_name = "DIR")]
+        paths: Vec<PathBuf>,
+
+        /// Uninstall every package listed in this file (one name per line)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["app_name", "list"])]
+        from: Option<PathBuf>,
+
+        /// With --from, uninstall every app a name matches instead of skipping ambiguous names
+        #[arg(long, requires = "from")]
+        all_matches: bool,
+
+        /// Only consider apps of this type, e.g. to remove the snap but keep the deb
+        #[arg(long = "type", alias = "assume-type", value_enum)]
+        app_type: Option<AppType>,
+
+        /// Only match an app whose name is exactly APP_NAME (case-insensitive)
+        #[arg(long, conflicts_with = "list")]
+        exact: bool,
     },
 }
 
+impl Command {
+    /// Whether this run deletes or changes anything, and so must not
+    /// overlap with another one
+    pub fn is_destructive(&self) -> bool {
+        match self {
+            Command::Clean { apply, .. } | Command::Purge { apply, .. } => *apply,
+            Command::Optimize { dry_run, .. } => !dry_run,
+            Command::Restore { index, .. } => index.is_some(),
+            Command::Uninstall { dry_run, list, .. } => !dry_run && !list,
+            Command::Analyze { .. }
+            | Command::Scan { .. }
+            | Command::Status { .. }
+            | Command::Config { .. } => false,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Report syntax errors, unknown keys, bad sizes and missing paths in the config file
+    Check,
+}
+
 fn default_analyze_path() -> String {
-    dirs::home_dir()
+    State::load()
+        .last_analyze_path
+        .filter(|p| p.is_dir())
+        .or_else(dirs::home_dir)
         .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|| ".".to_string())
 }
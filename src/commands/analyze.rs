@@ -2,10 +2,65 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hasher;
+use std::io::{IsTerminal, Read, Write};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+use serde::Serialize;
 
-use crate::core::filesystem::format_size;
+use crate::core::filesystem::{
+    begin_scan, file_size, filtered_files, format_size, measure_dir, DirSize, ScanFilter,
+};
+use crate::core::csv;
+use crate::core::json;
+use crate::core::output::{is_quiet, warn_if_timed_out, OutputFormat};
+use crate::core::state::State;
+use crate::core::security::{find_mount, mountpoints};
+use crate::{say, sayln, warnln};
+
+/// How often the progress line is redrawn while a large subtree is walked
+const PROGRESS_TICK: Duration = Duration::from_millis(100);
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Options for the analyze command
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzeOptions {
+    /// Also show size by file modification age
+    pub age_histogram: bool,
+    /// Also show size by file type
+    pub by_type: bool,
+    /// Also list large files that would shrink a lot if compressed
+    pub suggest_compress: bool,
+    /// Only count files whose name matches one of these globs
+    pub filters: Vec<String>,
+    /// Skip files and directories whose name or path matches one of these globs
+    pub excludes: Vec<String>,
+    /// Print only the total line, without the banner or per-entry listing
+    pub summary_only: bool,
+    /// Follow symlinks when sizing entries
+    pub dereference: bool,
+    /// Label entries by filesystem and total each one
+    pub group_by_fs: bool,
+    /// Also list files with identical content
+    pub duplicates: bool,
+    /// Files hashed at once by the duplicate finder (`DEFAULT_HASH_JOBS` when unset)
+    pub jobs: Option<usize>,
+    /// Smallest file the duplicate finder considers (`DUPLICATE_MIN_SIZE` when unset)
+    pub min_size: Option<u64>,
+    /// Only list entries of at least this percentage of the total
+    pub min_percent: Option<u8>,
+    /// Print the entries as text, JSON or CSV
+    pub format: OutputFormat,
+}
+
+/// Kernel pseudo-filesystems skipped when analyzing `/`
+const PSEUDO_FS: &[&str] = &["/proc", "/sys", "/dev", "/run"];
 
 /// Directory entry with size info
 #[derive(Debug)]
@@ -14,70 +69,603 @@ pub struct DirEntry {
     pub name: String,
     pub size: u64,
     pub is_dir: bool,
+    /// Entries under this one that could not be read
+    pub denied: usize,
 }
 
 /// Scan a directory and get sorted entries by size
-pub fn scan_directory(path: &Path, _depth: u32) -> Result<Vec<DirEntry>> {
-    let mut entries = Vec::new();
+///
+/// Child sizes are computed in parallel. `on_progress` is called on the
+/// calling thread every time a child finishes (with that entry) and
+/// periodically while the walk is still running (with `None`), along with
+/// the number of finished children and the total.
+///
+/// Children excluded by `filter` are skipped entirely. With an include
+/// filter, entries left with nothing matching are dropped.
+pub fn scan_directory<F>(
+    path: &Path,
+    filter: &ScanFilter,
+    on_progress: F,
+) -> Result<Vec<DirEntry>>
+where
+    F: FnMut(Option<&DirEntry>, usize, usize),
+{
+    let mut children = Vec::new();
+    let _scan = begin_scan();
 
     if !path.exists() {
-        return Ok(entries);
+        return Ok(Vec::new());
     }
 
     for entry in std::fs::read_dir(path)? {
         let entry = entry?;
-        let path = entry.path();
-        let metadata = entry.metadata()?;
-
-        let size = if metadata.is_dir() {
-            calculate_dir_size(&path)
-        } else {
-            metadata.len()
+        if filter.excludes(&entry.path()) {
+            continue;
+        }
+        let metadata = match filter.follow_links {
+            // Dangling links are still listed, as the link itself
+            true => std::fs::metadata(entry.path()).or_else(|_| entry.metadata())?,
+            false => entry.metadata()?,
         };
 
-        entries.push(DirEntry {
-            name: entry.file_name().to_string_lossy().to_string(),
-            path: path.clone(),
-            size,
-            is_dir: metadata.is_dir(),
+        children.push((
+            entry.path(),
+            entry.file_name().to_string_lossy().to_string(),
+            metadata,
+        ));
+    }
+
+    Ok(measure_entries(children, filter, on_progress))
+}
+
+/// Size several paths as rows of one listing, named as given
+///
+/// Works like `scan_directory` with `paths` standing in for the children of
+/// a directory. Paths that don't exist are returned separately.
+pub fn scan_paths<F>(
+    paths: &[PathBuf],
+    filter: &ScanFilter,
+    on_progress: F,
+) -> (Vec<DirEntry>, Vec<PathBuf>)
+where
+    F: FnMut(Option<&DirEntry>, usize, usize),
+{
+    let _scan = begin_scan();
+
+    let mut children = Vec::new();
+    let mut missing = Vec::new();
+    for path in paths {
+        match std::fs::metadata(path) {
+            Ok(metadata) => children.push((path.clone(), path.display().to_string(), metadata)),
+            Err(_) => missing.push(path.clone()),
+        }
+    }
+
+    (measure_entries(children, filter, on_progress), missing)
+}
+
+/// Size `children` in parallel, reporting progress on the calling thread
+fn measure_entries<F>(
+    children: Vec<(PathBuf, String, std::fs::Metadata)>,
+    filter: &ScanFilter,
+    mut on_progress: F,
+) -> Vec<DirEntry>
+where
+    F: FnMut(Option<&DirEntry>, usize, usize),
+{
+    let total = children.len();
+    let mut entries = Vec::with_capacity(total);
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            children
+                .into_par_iter()
+                .for_each_with(tx, |tx, (path, name, metadata)| {
+                    let size = if metadata.is_dir() {
+                        measure_dir(&path, filter)
+                    } else if filter.includes(&name) {
+                        DirSize {
+                            bytes: file_size(&metadata),
+                            denied: 0,
+                        }
+                    } else {
+                        DirSize::default()
+                    };
+
+                    let _ = tx.send(DirEntry {
+                        name,
+                        path,
+                        size: size.bytes,
+                        is_dir: metadata.is_dir(),
+                        denied: size.denied,
+                    });
+                });
         });
+
+        loop {
+            match rx.recv_timeout(PROGRESS_TICK) {
+                Ok(entry) => {
+                    on_progress(Some(&entry), entries.len() + 1, total);
+                    entries.push(entry);
+                }
+                Err(RecvTimeoutError::Timeout) => on_progress(None, entries.len(), total),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    if !filter.include.is_empty() {
+        entries.retain(|e| e.size > 0 || e.denied > 0);
     }
 
     // Sort by size descending
     entries.sort_by(|a, b| b.size.cmp(&a.size));
 
-    Ok(entries)
+    entries
 }
 
-fn calculate_dir_size(path: &Path) -> u64 {
-    WalkDir::new(path)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.metadata().map(|m| m.len()).unwrap_or(0))
-        .sum()
+/// Modification-age buckets used by `--age-histogram`
+const AGE_BUCKETS: [(&str, u64); 5] = [
+    ("today", 1),
+    ("< 7 days", 7),
+    ("< 30 days", 30),
+    ("< 90 days", 90),
+    ("older", u64::MAX),
+];
+
+/// Size and file count per age bucket, in `AGE_BUCKETS` order
+#[derive(Debug, Default)]
+pub struct AgeHistogram {
+    pub sizes: [u64; AGE_BUCKETS.len()],
+    pub counts: [usize; AGE_BUCKETS.len()],
+}
+
+impl AgeHistogram {
+    /// Walk `roots` as `filter` allows and bucket every file by how long
+    /// ago it was modified
+    ///
+    /// Files with an mtime in the future count as modified today.
+    pub fn scan<P: AsRef<Path>>(roots: &[P], filter: &ScanFilter) -> Self {
+        let _scan = begin_scan();
+        let now = SystemTime::now();
+        let mut histogram = Self::default();
+
+        for entry in roots.iter().flat_map(|root| filtered_files(root.as_ref(), filter)) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            let age_days = metadata
+                .modified()
+                .ok()
+                .and_then(|t| now.duration_since(t).ok())
+                .map(|d| d.as_secs() / 86400)
+                .unwrap_or(0);
+
+            let bucket = AGE_BUCKETS
+                .iter()
+                .position(|(_, limit)| age_days < *limit)
+                .unwrap_or(AGE_BUCKETS.len() - 1);
+
+            histogram.sizes[bucket] += file_size(&metadata);
+            histogram.counts[bucket] += 1;
+        }
+
+        histogram
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.sizes.iter().sum()
+    }
+}
+
+fn print_age_histogram(histogram: &AgeHistogram) {
+    let total = histogram.total_size();
+
+    sayln!("{}", "Age of files (by modification time):".bold());
+
+    for (i, (label, _)) in AGE_BUCKETS.iter().enumerate() {
+        let percent = if total > 0 {
+            (histogram.sizes[i] as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let bar_width: usize = 20;
+        let filled = ((percent / 100.0) * bar_width as f64) as usize;
+        let bar = format!(
+            "{}{}",
+            "█".repeat(filled),
+            "░".repeat(bar_width.saturating_sub(filled))
+        );
+
+        sayln!(
+            "  {:<10} {} {:>5.1}% {:>10} {}",
+            label,
+            bar.cyan(),
+            percent,
+            format_size(histogram.sizes[i]).yellow(),
+            format!("({} files)", histogram.counts[i]).dimmed()
+        );
+    }
+
+    sayln!();
+}
+
+/// Broad kind of file, decided by extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCategory {
+    Images,
+    Videos,
+    Audio,
+    Archives,
+    Documents,
+    Code,
+    Logs,
+    Other,
+}
+
+impl std::fmt::Display for FileCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileCategory::Images => write!(f, "images"),
+            FileCategory::Videos => write!(f, "videos"),
+            FileCategory::Audio => write!(f, "audio"),
+            FileCategory::Archives => write!(f, "archives"),
+            FileCategory::Documents => write!(f, "documents"),
+            FileCategory::Code => write!(f, "code"),
+            FileCategory::Logs => write!(f, "logs"),
+            FileCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl FileCategory {
+    /// Classify a (lowercase) file extension
+    pub fn from_extension(ext: &str) -> Self {
+        match ext {
+            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp" | "svg" | "tif" | "tiff" | "heic"
+            | "raw" | "cr2" | "nef" | "ico" | "psd" | "xcf" => FileCategory::Images,
+            // `ts` is TypeScript far more often than an MPEG transport stream
+            "mp4" | "mkv" | "avi" | "mov" | "webm" | "wmv" | "flv" | "m4v" | "mpg" | "mpeg"
+            | "m2ts" => FileCategory::Videos,
+            "mp3" | "flac" | "wav" | "ogg" | "opus" | "m4a" | "aac" | "wma" => FileCategory::Audio,
+            "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "7z" | "rar" | "iso" | "deb"
+            | "rpm" | "appimage" | "snap" | "jar" => FileCategory::Archives,
+            "pdf" | "doc" | "docx" | "odt" | "xls" | "xlsx" | "ods" | "ppt" | "pptx" | "odp"
+            | "txt" | "md" | "epub" | "csv" => FileCategory::Documents,
+            "rs" | "c" | "h" | "cpp" | "hpp" | "cc" | "py" | "js" | "jsx" | "ts" | "tsx" | "go"
+            | "java" | "kt" | "rb" | "php" | "sh" | "html" | "css" | "scss" | "json" | "toml"
+            | "yaml" | "yml" | "xml" | "sql" | "lua" | "swift" => FileCategory::Code,
+            "log" | "journal" | "old" => FileCategory::Logs,
+            _ => FileCategory::Other,
+        }
+    }
+
+    /// Classify a path by its extension; `app.log.1` style rotations count as logs
+    pub fn from_path(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if name.contains(".log.") {
+            return FileCategory::Logs;
+        }
+
+        path.extension()
+            .map(|e| Self::from_extension(&e.to_string_lossy().to_lowercase()))
+            .unwrap_or(FileCategory::Other)
+    }
+}
+
+/// Total size per file category, plus per extension within each category
+#[derive(Debug, Default)]
+pub struct TypeBreakdown {
+    pub categories: HashMap<FileCategory, u64>,
+    pub extensions: HashMap<(FileCategory, String), u64>,
+}
+
+impl TypeBreakdown {
+    /// Walk `roots` as `filter` allows and add every file's size to its
+    /// category
+    pub fn scan<P: AsRef<Path>>(roots: &[P], filter: &ScanFilter) -> Self {
+        let _scan = begin_scan();
+        let mut breakdown = Self::default();
+
+        for entry in roots.iter().flat_map(|root| filtered_files(root.as_ref(), filter)) {
+            let size = entry.metadata().map(|m| file_size(&m)).unwrap_or(0);
+            let category = FileCategory::from_path(entry.path());
+            *breakdown.categories.entry(category).or_default() += size;
+
+            let ext = entry
+                .path()
+                .extension()
+                .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()))
+                .unwrap_or_else(|| "(none)".to_string());
+            *breakdown.extensions.entry((category, ext)).or_default() += size;
+        }
+
+        breakdown
+    }
+
+    /// Categories sorted by size, largest first
+    pub fn sorted(&self) -> Vec<(FileCategory, u64)> {
+        let mut sorted: Vec<_> = self.categories.iter().map(|(c, s)| (*c, *s)).collect();
+        sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+        sorted
+    }
+}
+
+fn print_type_breakdown(breakdown: &TypeBreakdown) {
+    let total: u64 = breakdown.categories.values().sum();
+
+    sayln!("{}", "Size by file type:".bold());
+
+    for (category, size) in breakdown.sorted() {
+        let percent = if total > 0 {
+            (size as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let bar_width: usize = 20;
+        let filled = ((percent / 100.0) * bar_width as f64) as usize;
+        let bar = format!(
+            "{}{}",
+            "█".repeat(filled),
+            "░".repeat(bar_width.saturating_sub(filled))
+        );
+
+        // Largest extensions in this category
+        let mut exts: Vec<_> = breakdown
+            .extensions
+            .iter()
+            .filter(|((c, _), _)| *c == category)
+            .collect();
+        exts.sort_by(|a, b| b.1.cmp(a.1));
+        let top = exts
+            .iter()
+            .take(3)
+            .map(|((_, ext), _)| ext.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        sayln!(
+            "  {:<10} {} {:>5.1}% {:>10} {}",
+            category.to_string(),
+            bar.cyan(),
+            percent,
+            format_size(size).yellow(),
+            top.dimmed()
+        );
+    }
+
+    sayln!();
 }
 
 /// Run the analyze command
-pub fn run(path: String) -> Result<()> {
-    let path = PathBuf::from(&path);
+///
+/// A single path lists its contents; several paths are listed as one row
+/// each, with a grand total. Breakdowns requested in `options` are printed
+/// after the usual listing.
+pub fn run(paths: Vec<String>, options: AnalyzeOptions) -> Result<()> {
+    // Every walk of this run, reports included, shares one deadline
+    let _scan = begin_scan();
+    let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    let shown = paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if !options.summary_only && options.format == OutputFormat::Text {
+        sayln!("{}", "Mole-RS Disk Analyzer".bold().cyan());
+        sayln!("{}", "═".repeat(60));
+        sayln!();
+        sayln!("Analyzing: {}", shown.yellow());
+        if !options.filters.is_empty() {
+            sayln!("Counting only: {}", options.filters.join(", ").yellow());
+        }
+        if !options.excludes.is_empty() {
+            sayln!("Excluding: {}", options.excludes.join(", ").yellow());
+        }
+        sayln!();
+    }
+
+    let mut exclude = options.excludes.clone();
+    if paths.iter().any(|p| p == Path::new("/")) {
+        exclude.extend(PSEUDO_FS.iter().map(|p| p.to_string()));
+    }
+    let filter = ScanFilter {
+        include: options.filters.clone(),
+        exclude,
+        follow_links: options.dereference,
+    };
 
-    println!("{}", "Mole-RS Disk Analyzer".bold().cyan());
-    println!("{}", "═".repeat(60));
-    println!();
-    println!("Analyzing: {}", path.display().to_string().yellow());
-    println!();
+    let entries = scan_with_progress(&paths, &filter)?;
+    if let [path] = paths.as_slice() {
+        remember_path(path);
+    }
 
-    let entries = scan_directory(&path, 0)?;
+    if options.format != OutputFormat::Text {
+        let rows = entry_rows(&entries);
+        match options.format {
+            OutputFormat::Json => sayln!("{}", json::to_string_pretty(&rows)?),
+            _ => say!("{}", entry_rows_csv(&rows)),
+        }
+        warn_if_timed_out();
+        return Ok(());
+    }
+
+    if entries.is_empty() && options.summary_only {
+        sayln!("Total: {} (0 items)", format_size(0));
+        return Ok(());
+    }
 
     if entries.is_empty() {
-        println!("{}", "No files found.".dimmed());
+        let message = if options.filters.is_empty() {
+            "No files found."
+        } else {
+            "No matching files found."
+        };
+        sayln!("{}", message.dimmed());
         return Ok(());
     }
 
     let total_size: u64 = entries.iter().map(|e| e.size).sum();
+    let filesystems = options.group_by_fs.then(|| EntryFilesystems::detect(&paths, &entries));
+
+    if options.summary_only {
+        sayln!("Total: {} ({} items)", format_size(total_size), entries.len());
+    } else {
+        let shown = options
+            .min_percent
+            .map_or(entries.len(), |min| entries_above_percent(&entries, total_size, min));
+        print_entries(&entries[..shown], total_size, filesystems.as_ref());
+        print_other_row(&entries[shown..]);
+
+        sayln!();
+        sayln!("{}", "═".repeat(60));
+        sayln!(
+            "Total: {} ({} items)",
+            format_size(total_size).green().bold(),
+            entries.len()
+        );
+    }
+
+    if let Some(filesystems) = &filesystems {
+        print_filesystem_totals(&totals_by_filesystem(&entries, &filesystems.mounts));
+    }
 
+    let denied: usize = entries.iter().map(|e| e.denied).sum();
+    if denied > 0 {
+        warnln!(
+            "{} {} entries could not be read (permission denied); totals are incomplete. Run with sudo for full sizes.",
+            "!".yellow(),
+            denied
+        );
+    }
+
+    if options.age_histogram {
+        sayln!();
+        print_age_histogram(&AgeHistogram::scan(&paths, &filter));
+    }
+
+    if options.by_type {
+        sayln!();
+        print_type_breakdown(&TypeBreakdown::scan(&paths, &filter));
+    }
+
+    if options.suggest_compress {
+        sayln!();
+        print_compression_report(&CompressionReport::scan(&paths, &filter, COMPRESS_MIN_SIZE));
+    }
+
+    if options.duplicates {
+        sayln!();
+        let groups = duplicates_with_progress(
+            &paths,
+            &filter,
+            options.min_size.unwrap_or(DUPLICATE_MIN_SIZE),
+            options.jobs.unwrap_or(DEFAULT_HASH_JOBS),
+        )?;
+        print_duplicates(&groups);
+    }
+
+    warn_if_timed_out();
+
+    Ok(())
+}
+
+/// Mount point of each entry, and of the analyzed path itself
+pub struct EntryFilesystems {
+    /// Filesystem of the analyzed path (the first one, when several are given)
+    pub root: String,
+    /// One per entry, in the same order: a filesystem mounted at or below
+    /// the entry (its size counts in the entry's), else the one holding it
+    pub mounts: Vec<String>,
+}
+
+impl EntryFilesystems {
+    fn detect(paths: &[PathBuf], entries: &[DirEntry]) -> Self {
+        Self::detect_in(paths, entries, &mountpoints())
+    }
+
+    /// `detect` against the given mount table
+    fn detect_in(paths: &[PathBuf], entries: &[DirEntry], mounts: &[PathBuf]) -> Self {
+        let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let holding = |path: &Path| {
+            mounts
+                .iter()
+                .filter(|mount| path.starts_with(mount))
+                .max_by_key(|mount| mount.components().count())
+                .map(|mount| mount.display().to_string())
+                .unwrap_or_else(|| "?".to_string())
+        };
+
+        Self {
+            root: paths.first().map(|p| holding(&canonical(p))).unwrap_or_default(),
+            mounts: entries
+                .iter()
+                .map(|e| {
+                    let path = canonical(&e.path);
+                    match find_mount(&path, mounts) {
+                        Some(mount) => mount.display().to_string(),
+                        None => holding(&path),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Label for entry `index` if it lives on another filesystem than the root
+    fn label(&self, index: usize) -> Option<&str> {
+        self.mounts
+            .get(index)
+            .filter(|mount| **mount != self.root)
+            .map(String::as_str)
+    }
+}
+
+/// Size and entry count per filesystem, largest first
+///
+/// `mounts` gives the mount point of each entry, in the same order.
+pub fn totals_by_filesystem(entries: &[DirEntry], mounts: &[String]) -> Vec<(String, u64, usize)> {
+    let mut totals: HashMap<&str, (u64, usize)> = HashMap::new();
+    for (entry, mount) in entries.iter().zip(mounts) {
+        let total = totals.entry(mount).or_default();
+        total.0 += entry.size;
+        total.1 += 1;
+    }
+
+    let mut totals: Vec<_> = totals
+        .into_iter()
+        .map(|(mount, (size, count))| (mount.to_string(), size, count))
+        .collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+fn print_filesystem_totals(totals: &[(String, u64, usize)]) {
+    sayln!();
+    sayln!("{}", "By filesystem:".bold());
+    for (mount, size, count) in totals {
+        sayln!(
+            "  {:<30} {:>10}  ({} items)",
+            mount.cyan(),
+            format_size(*size).yellow(),
+            count
+        );
+    }
+}
+
+/// Print the largest entries with a bar for their share of `total_size`
+///
+/// With `filesystems`, entries on another filesystem than the analyzed path
+/// are marked with their mount point.
+fn print_entries(entries: &[DirEntry], total_size: u64, filesystems: Option<&EntryFilesystems>) {
     // Display entries with bar visualization
     for (i, entry) in entries.iter().take(20).enumerate() {
         let percent = if total_size > 0 {
@@ -111,33 +699,707 @@ pub fn run(path: String) -> Result<()> {
             bar.green()
         };
 
-        println!(
-            " {:2}. {} {:>5.1}% {} {:<30} {:>10}",
+        let partial = if entry.denied > 0 { " (partial)" } else { "" };
+        let mount = filesystems
+            .and_then(|fs| fs.label(i))
+            .map(|mount| format!(" [on {}]", mount))
+            .unwrap_or_default();
+
+        sayln!(
+            " {:2}. {} {:>5.1}% {} {:<30} {:>10}{}{}",
             i + 1,
             bar_colored,
             percent,
             icon,
             name,
-            size_str.yellow()
+            size_str.yellow(),
+            partial.red(),
+            mount.cyan()
         );
     }
 
     if entries.len() > 20 {
-        println!();
-        println!(
+        sayln!();
+        sayln!(
             "  {} {} more items...",
             "...".dimmed(),
             entries.len() - 20
         );
     }
+}
+
+/// Number of leading entries (sorted largest first) that take at least
+/// `min_percent` of `total_size`
+pub fn entries_above_percent(entries: &[DirEntry], total_size: u64, min_percent: u8) -> usize {
+    entries
+        .iter()
+        .take_while(|e| e.size as f64 * 100.0 >= total_size as f64 * f64::from(min_percent))
+        .count()
+}
+
+/// One analyzed entry, serialized for `--format json` and `--format csv`
+#[derive(Debug, Serialize)]
+pub struct EntryRow {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+    /// Share of the total of all entries
+    pub percent: f64,
+}
+
+pub fn entry_rows(entries: &[DirEntry]) -> Vec<EntryRow> {
+    let total_size: u64 = entries.iter().map(|e| e.size).sum();
+    entries
+        .iter()
+        .map(|e| EntryRow {
+            path: e.path.clone(),
+            size_bytes: e.size,
+            is_dir: e.is_dir,
+            percent: if total_size > 0 {
+                (e.size as f64 * 10000.0 / total_size as f64).round() / 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect()
+}
+
+/// `path,size_bytes,is_dir,percent`, one row per entry
+pub fn entry_rows_csv(rows: &[EntryRow]) -> String {
+    let mut out = csv::row(&["path", "size_bytes", "is_dir", "percent"]);
+    for row in rows {
+        out.push_str(&csv::row(&[
+            row.path.display().to_string(),
+            row.size_bytes.to_string(),
+            row.is_dir.to_string(),
+            format!("{:.2}", row.percent),
+        ]));
+    }
+    out
+}
+
+/// Sum the entries hidden by `--min-percent` into one row
+fn print_other_row(rest: &[DirEntry]) {
+    if rest.is_empty() {
+        return;
+    }
+    let size: u64 = rest.iter().map(|e| e.size).sum();
+    sayln!(
+        "     {} {}",
+        format!("(other: {})", format_size(size)).dimmed(),
+        format!("{} smaller items", rest.len()).dimmed()
+    );
+}
+
+/// Make `path` the default for the next `mo analyze` without arguments
+fn remember_path(path: &Path) {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut state = State::load();
+    state.last_analyze_path = Some(path);
+
+    if let Err(e) = state.save() {
+        tracing::debug!("Could not save state: {}", e);
+    }
+}
+
+/// Scan a single path's contents, or several paths side by side, drawing a
+/// live progress line on stderr when it is a terminal
+fn scan_with_progress(paths: &[PathBuf], filter: &ScanFilter) -> Result<Vec<DirEntry>> {
+    let interactive = std::io::stderr().is_terminal() && !is_quiet();
+    let mut tick = 0usize;
+    let mut largest: Option<(String, u64)> = None;
+
+    let progress = |entry: Option<&DirEntry>, done: usize, total: usize| {
+        if let Some(entry) = entry {
+            if largest.as_ref().is_none_or(|(_, size)| entry.size > *size) {
+                largest = Some((entry.name.clone(), entry.size));
+            }
+        }
+
+        if !interactive {
+            return;
+        }
+
+        tick += 1;
+        let largest_str = largest
+            .as_ref()
+            .map(|(name, size)| format!(" · largest so far: {} ({})", name, format_size(*size)))
+            .unwrap_or_default();
+
+        eprint!(
+            "\r\x1B[K {} Scanning {}/{}{}",
+            SPINNER[tick % SPINNER.len()].to_string().cyan(),
+            done,
+            total,
+            largest_str.dimmed()
+        );
+        std::io::stderr().flush().ok();
+    };
+
+    let entries = match paths {
+        [path] => scan_directory(path, filter, progress)?,
+        _ => {
+            let (entries, missing) = scan_paths(paths, filter, progress);
+            for path in missing {
+                warnln!("{} {} not found, skipped", "!".yellow(), path.display());
+            }
+            entries
+        }
+    };
+
+    if interactive {
+        eprint!("\r\x1B[K");
+        std::io::stderr().flush().ok();
+    }
+
+    Ok(entries)
+}
+
+/// Files smaller than this are left out of the duplicate search by default;
+/// small files dominate the count but hardly the wasted space
+pub const DUPLICATE_MIN_SIZE: u64 = 1024 * 1024;
+
+/// Files hashed at once by default; more mostly adds seeking on spinning disks
+pub const DEFAULT_HASH_JOBS: usize = 4;
+
+/// Read buffer each hashing thread streams files through
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Duplicate groups listed in full
+const DUPLICATE_GROUPS_SHOWN: usize = 10;
+
+/// Files with identical content
+#[derive(Debug)]
+pub struct DuplicateGroup {
+    /// Size of each copy
+    pub size: u64,
+    /// Sorted by path
+    pub paths: Vec<PathBuf>,
+}
 
-    println!();
-    println!("{}", "═".repeat(60));
-    println!(
-        "Total: {} ({} items)",
-        format_size(total_size).green().bold(),
-        entries.len()
+impl DuplicateGroup {
+    /// Space taken by all copies but one
+    pub fn wasted(&self) -> u64 {
+        self.size * (self.paths.len() as u64).saturating_sub(1)
+    }
+}
+
+/// Find files with identical content under `roots`, walked as `filter`
+/// allows
+///
+/// Files are grouped by size first, so only files sharing a size with
+/// another are read at all. Those are hashed by a pool of `jobs` threads,
+/// each streaming through one fixed buffer, so memory stays flat however
+/// large the files are. Hard links to the same inode count once.
+/// `on_progress` is called on the calling thread with the number of files
+/// hashed and the total.
+pub fn find_duplicates<F>(
+    roots: &[PathBuf],
+    filter: &ScanFilter,
+    min_size: u64,
+    jobs: usize,
+    mut on_progress: F,
+) -> Result<Vec<DuplicateGroup>>
+where
+    F: FnMut(usize, usize),
+{
+    let _scan = begin_scan();
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut inodes = HashSet::new();
+
+    for root in roots {
+        for entry in filtered_files(root, filter) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.len() < min_size.max(1) || !inodes.insert((metadata.dev(), metadata.ino())) {
+                continue;
+            }
+            by_size.entry(metadata.len()).or_default().push(entry.into_path());
+        }
+    }
+
+    let candidates: Vec<(u64, PathBuf)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (size, path)))
+        .collect();
+    let total = candidates.len();
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let mut by_hash: HashMap<(u64, u64), Vec<PathBuf>> = HashMap::new();
+    let mut done = 0;
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            pool.install(|| {
+                candidates.into_par_iter().for_each_with(tx, |tx, (size, path)| {
+                    let hash = hash_file(&path).ok();
+                    let _ = tx.send((size, hash, path));
+                });
+            });
+        });
+
+        loop {
+            match rx.recv_timeout(PROGRESS_TICK) {
+                Ok((size, hash, path)) => {
+                    done += 1;
+                    if let Some(hash) = hash {
+                        by_hash.entry((size, hash)).or_default().push(path);
+                    }
+                    on_progress(done, total);
+                }
+                Err(RecvTimeoutError::Timeout) => on_progress(done, total),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, _), mut paths)| {
+            paths.sort();
+            DuplicateGroup { size, paths }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.wasted().cmp(&a.wasted()).then_with(|| a.paths.cmp(&b.paths)));
+
+    Ok(groups)
+}
+
+/// Hash a file's content, reading it in `HASH_BUFFER_SIZE` chunks
+fn hash_file(path: &Path) -> std::io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
+
+fn duplicates_with_progress(
+    paths: &[PathBuf],
+    filter: &ScanFilter,
+    min_size: u64,
+    jobs: usize,
+) -> Result<Vec<DuplicateGroup>> {
+    let interactive = std::io::stderr().is_terminal() && !is_quiet();
+    let mut tick = 0usize;
+
+    let groups = find_duplicates(paths, filter, min_size, jobs, |done, total| {
+        if !interactive {
+            return;
+        }
+        tick += 1;
+        eprint!(
+            "\r\x1B[K {} Hashing {}/{} files",
+            SPINNER[tick % SPINNER.len()].to_string().cyan(),
+            done,
+            total
+        );
+        std::io::stderr().flush().ok();
+    })?;
+
+    if interactive {
+        eprint!("\r\x1B[K");
+        std::io::stderr().flush().ok();
+    }
+
+    Ok(groups)
+}
+
+fn print_duplicates(groups: &[DuplicateGroup]) {
+    if groups.is_empty() {
+        sayln!("{}", "No duplicate files found.".dimmed());
+        return;
+    }
+
+    let wasted: u64 = groups.iter().map(|g| g.wasted()).sum();
+    sayln!(
+        "{} {} groups, {} wasted",
+        "Duplicates:".bold(),
+        groups.len(),
+        format_size(wasted).yellow().bold()
     );
 
-    Ok(())
+    for group in groups.iter().take(DUPLICATE_GROUPS_SHOWN) {
+        sayln!(
+            "  {} × {}  {}",
+            format_size(group.size).yellow(),
+            group.paths.len(),
+            format!("({} wasted)", format_size(group.wasted())).dimmed()
+        );
+        for path in &group.paths {
+            sayln!("      {}", path.display().to_string().dimmed());
+        }
+    }
+    if groups.len() > DUPLICATE_GROUPS_SHOWN {
+        sayln!(
+            "  {} {} more groups",
+            "...".dimmed(),
+            groups.len() - DUPLICATE_GROUPS_SHOWN
+        );
+    }
+}
+
+/// Files smaller than this aren't worth compressing by hand
+const COMPRESS_MIN_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Typical fraction of size gzip saves on text-like formats
+fn compression_savings(ext: &str) -> Option<f64> {
+    match ext {
+        "log" => Some(0.90),
+        "json" | "xml" => Some(0.85),
+        "csv" | "tsv" | "sql" => Some(0.80),
+        "txt" => Some(0.65),
+        _ => None,
+    }
+}
+
+/// Formats that are already compressed, so gzip would gain nothing
+fn is_compressed_format(ext: &str) -> bool {
+    matches!(
+        ext,
+        "zip" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "7z" | "rar" | "mp4" | "mkv" | "webm"
+            | "mov" | "jpg" | "jpeg" | "png" | "webp" | "heic" | "mp3" | "ogg" | "opus" | "m4a"
+            | "flac"
+    )
+}
+
+/// A large file that would likely shrink a lot if compressed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionCandidate {
+    pub path: PathBuf,
+    pub ext: String,
+    pub size: u64,
+    /// Estimated bytes saved by gzip
+    pub savings: u64,
+}
+
+/// Compression opportunities found under a path (advisory only)
+#[derive(Debug, Default)]
+pub struct CompressionReport {
+    pub candidates: Vec<CompressionCandidate>,
+    /// Large files skipped because they are already compressed
+    pub compressed_files: usize,
+    pub compressed_bytes: u64,
+}
+
+impl CompressionReport {
+    /// Walk `roots` as `filter` allows, looking at files of at least
+    /// `min_size` bytes
+    pub fn scan<P: AsRef<Path>>(roots: &[P], filter: &ScanFilter, min_size: u64) -> Self {
+        let _scan = begin_scan();
+        let mut report = Self::default();
+
+        for entry in roots.iter().flat_map(|root| filtered_files(root.as_ref(), filter)) {
+            let size = entry.metadata().map(|m| file_size(&m)).unwrap_or(0);
+            if size < min_size {
+                continue;
+            }
+
+            let Some(ext) = entry.path().extension() else {
+                continue;
+            };
+            let ext = ext.to_string_lossy().to_lowercase();
+
+            if let Some(ratio) = compression_savings(&ext) {
+                report.candidates.push(CompressionCandidate {
+                    path: entry.path().to_path_buf(),
+                    savings: (size as f64 * ratio) as u64,
+                    ext,
+                    size,
+                });
+            } else if is_compressed_format(&ext) {
+                report.compressed_files += 1;
+                report.compressed_bytes += size;
+            }
+        }
+
+        report
+            .candidates
+            .sort_by(|a, b| b.savings.cmp(&a.savings).then_with(|| a.path.cmp(&b.path)));
+        report
+    }
+
+    /// Candidates grouped by extension, the group saving the most first
+    pub fn by_extension(&self) -> Vec<(&str, Vec<&CompressionCandidate>)> {
+        let mut groups: HashMap<&str, Vec<&CompressionCandidate>> = HashMap::new();
+        for candidate in &self.candidates {
+            groups.entry(candidate.ext.as_str()).or_default().push(candidate);
+        }
+
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by_key(|(ext, files)| {
+            (std::cmp::Reverse(files.iter().map(|c| c.savings).sum::<u64>()), *ext)
+        });
+        groups
+    }
+}
+
+fn print_compression_report(report: &CompressionReport) {
+    sayln!(
+        "{}",
+        format!("Compression candidates (files over {}):", format_size(COMPRESS_MIN_SIZE)).bold()
+    );
+
+    if report.candidates.is_empty() {
+        sayln!("  {}", "No large compressible files found.".dimmed());
+    }
+
+    for (ext, files) in report.by_extension() {
+        let size: u64 = files.iter().map(|c| c.size).sum();
+        let savings: u64 = files.iter().map(|c| c.savings).sum();
+
+        sayln!(
+            "  .{:<6} {} files, {} → ~{} saved",
+            ext,
+            files.len(),
+            format_size(size).yellow(),
+            format_size(savings).green()
+        );
+        for candidate in files.iter().take(5) {
+            sayln!(
+                "      {:>10}  {}",
+                format_size(candidate.size),
+                candidate.path.display().to_string().dimmed()
+            );
+        }
+        if files.len() > 5 {
+            sayln!("      {} {} more", "...".dimmed(), files.len() - 5);
+        }
+    }
+
+    if report.compressed_files > 0 {
+        sayln!(
+            "  {}",
+            format!(
+                "{} large files ({}) are already compressed and were skipped.",
+                report.compressed_files,
+                format_size(report.compressed_bytes)
+            )
+            .dimmed()
+        );
+    }
+    sayln!(
+        "  {}",
+        "Savings are rough gzip estimates; nothing was changed.".dimmed()
+    );
+    sayln!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_duplicates() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::write(root.join("one.bin"), vec![7u8; 200_000]).unwrap();
+        std::fs::write(root.join("a/b/copy.bin"), vec![7u8; 200_000]).unwrap();
+        std::fs::hard_link(root.join("one.bin"), root.join("a/link.bin")).unwrap();
+        std::fs::write(root.join("a/same-size.bin"), vec![8u8; 200_000]).unwrap();
+        std::fs::write(root.join("tiny1"), "x").unwrap();
+        std::fs::write(root.join("tiny2"), "x").unwrap();
+
+        let paths = [root.to_path_buf()];
+        let mut calls = 0;
+        let groups = find_duplicates(&paths, &ScanFilter::default(), 1024, 2, |_, _| calls += 1).unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].size, 200_000);
+        assert_eq!(groups[0].paths.len(), 2);
+        assert!(groups[0].paths.contains(&root.join("a/b/copy.bin")));
+        assert_eq!(groups[0].wasted(), 200_000);
+        assert!(calls >= 3);
+
+        let small = find_duplicates(&paths, &ScanFilter::default(), 1, 1, |_, _| {}).unwrap();
+        assert_eq!(small.len(), 2);
+        assert_eq!(small[1].paths, [root.join("tiny1"), root.join("tiny2")]);
+    }
+
+    #[test]
+    fn test_entries_above_percent() {
+        let entries: Vec<DirEntry> = [700, 200, 50, 30, 20]
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| DirEntry {
+                path: PathBuf::from(format!("/d/{}", i)),
+                name: i.to_string(),
+                size,
+                is_dir: true,
+                denied: 0,
+            })
+            .collect();
+
+        assert_eq!(entries_above_percent(&entries, 1000, 5), 3);
+        assert_eq!(entries_above_percent(&entries, 1000, 10), 2);
+        assert_eq!(entries_above_percent(&entries, 1000, 100), 0);
+        assert_eq!(entries_above_percent(&entries, 1000, 1), 5);
+    }
+
+    #[test]
+    fn test_entry_rows_csv() {
+        let entries = vec![
+            DirEntry {
+                path: PathBuf::from("/d/videos, 2024"),
+                name: "videos, 2024".to_string(),
+                size: 750,
+                is_dir: true,
+                denied: 0,
+            },
+            DirEntry {
+                path: PathBuf::from("/d/notes.txt"),
+                name: "notes.txt".to_string(),
+                size: 250,
+                is_dir: false,
+                denied: 0,
+            },
+        ];
+
+        let rows = entry_rows(&entries);
+        assert_eq!(rows[0].percent, 75.0);
+        assert_eq!(
+            entry_rows_csv(&rows),
+            "path,size_bytes,is_dir,percent\n\
+             \"/d/videos, 2024\",750,true,75.00\n\
+             /d/notes.txt,250,false,25.00\n"
+        );
+
+        let json = json::to_string(&rows).unwrap();
+        assert!(json.contains("\"size_bytes\":750"));
+        assert!(json.contains("\"is_dir\":false"));
+    }
+
+    #[test]
+    fn test_totals_by_filesystem() {
+        // A fake mount table over a temp tree: the tree is on `/`, `home` is
+        // its own filesystem and `mnt` only holds one further down
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        for dir in ["mnt/data", "usr", "home"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let table = [PathBuf::from("/"), root.join("home"), root.join("mnt/data")];
+        let entry = |name: &str, size: u64| DirEntry {
+            path: root.join(name),
+            name: name.to_string(),
+            size,
+            is_dir: true,
+            denied: 0,
+        };
+        let entries = vec![entry("mnt", 2000), entry("usr", 300), entry("home", 500)];
+
+        let filesystems = EntryFilesystems::detect_in(std::slice::from_ref(&root), &entries, &table);
+        let (data, home) = (root.join("mnt/data").display().to_string(), root.join("home").display().to_string());
+        assert_eq!(filesystems.root, "/");
+        assert_eq!(filesystems.label(0), Some(data.as_str()));
+        assert_eq!(filesystems.label(1), None);
+        assert_eq!(filesystems.label(2), Some(home.as_str()));
+        assert_eq!(filesystems.label(7), None);
+
+        assert_eq!(
+            totals_by_filesystem(&entries, &filesystems.mounts),
+            [(data, 2000, 1), (home, 500, 1), ("/".to_string(), 300, 1)]
+        );
+    }
+
+    #[test]
+    fn test_file_category_from_path() {
+        assert_eq!(FileCategory::from_path(Path::new("clip.MP4")), FileCategory::Videos);
+        assert_eq!(FileCategory::from_path(Path::new("src/index.ts")), FileCategory::Code);
+        assert_eq!(FileCategory::from_path(Path::new("a/b/photo.jpeg")), FileCategory::Images);
+        assert_eq!(FileCategory::from_path(Path::new("backup.tar.gz")), FileCategory::Archives);
+        assert_eq!(FileCategory::from_path(Path::new("syslog.log.2")), FileCategory::Logs);
+        assert_eq!(FileCategory::from_path(Path::new("Makefile")), FileCategory::Other);
+        assert_eq!(FileCategory::from_path(Path::new("data.xyz")), FileCategory::Other);
+    }
+
+    #[test]
+    fn test_type_breakdown_sums_by_category() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("sub")).unwrap();
+        std::fs::write(temp.path().join("a.mp4"), vec![0u8; 300]).unwrap();
+        std::fs::write(temp.path().join("sub/b.mkv"), vec![0u8; 100]).unwrap();
+        std::fs::write(temp.path().join("notes.txt"), vec![0u8; 50]).unwrap();
+        std::fs::write(temp.path().join("README"), vec![0u8; 5]).unwrap();
+
+        let breakdown = TypeBreakdown::scan(&[temp.path()], &ScanFilter::default());
+        let sorted = breakdown.sorted();
+
+        assert_eq!(sorted[0], (FileCategory::Videos, 400));
+        assert_eq!(sorted[1], (FileCategory::Documents, 50));
+        assert_eq!(sorted[2], (FileCategory::Other, 5));
+        assert_eq!(breakdown.extensions[&(FileCategory::Videos, ".mp4".to_string())], 300);
+    }
+
+    #[test]
+    fn test_type_breakdown_skips_excluded() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("images")).unwrap();
+        std::fs::write(temp.path().join("images/disk.iso"), vec![0u8; 900]).unwrap();
+        std::fs::write(temp.path().join("top.iso"), vec![0u8; 200]).unwrap();
+        std::fs::write(temp.path().join("notes.txt"), vec![0u8; 50]).unwrap();
+
+        let filter = ScanFilter {
+            exclude: vec!["images".to_string()],
+            ..ScanFilter::default()
+        };
+        let breakdown = TypeBreakdown::scan(&[temp.path()], &filter);
+
+        assert_eq!(breakdown.categories[&FileCategory::Archives], 200);
+        assert_eq!(breakdown.categories[&FileCategory::Documents], 50);
+    }
+
+    #[test]
+    fn test_breakdowns_follow_include_filter() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("sub")).unwrap();
+        std::fs::write(temp.path().join("a.mp4"), vec![0u8; 300]).unwrap();
+        std::fs::write(temp.path().join("sub/b.mp4"), vec![0u8; 100]).unwrap();
+        std::fs::write(temp.path().join("notes.txt"), vec![0u8; 50]).unwrap();
+
+        let filter = ScanFilter {
+            include: vec!["*.mp4".to_string()],
+            ..ScanFilter::default()
+        };
+        let breakdown = TypeBreakdown::scan(&[temp.path()], &filter);
+        assert_eq!(breakdown.sorted(), [(FileCategory::Videos, 400)]);
+
+        let histogram = AgeHistogram::scan(&[temp.path()], &filter);
+        assert_eq!(histogram.total_size(), measure_dir(temp.path(), &filter).bytes);
+        assert_eq!(histogram.counts.iter().sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn test_compression_report_groups_by_extension() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("big.log"), vec![b'a'; 1000]).unwrap();
+        std::fs::write(temp.path().join("old.log"), vec![b'a'; 500]).unwrap();
+        std::fs::write(temp.path().join("dump.SQL"), vec![b'a'; 2000]).unwrap();
+        std::fs::write(temp.path().join("tiny.log"), vec![b'a'; 10]).unwrap();
+        std::fs::write(temp.path().join("movie.mp4"), vec![0u8; 3000]).unwrap();
+        std::fs::write(temp.path().join("data.bin"), vec![0u8; 3000]).unwrap();
+
+        let report = CompressionReport::scan(&[temp.path()], &ScanFilter::default(), 100);
+
+        assert_eq!(report.candidates.len(), 3);
+        assert_eq!(report.candidates[0].savings, 1600);
+        assert_eq!(report.compressed_files, 1);
+        assert_eq!(report.compressed_bytes, 3000);
+
+        let groups = report.by_extension();
+        assert_eq!(groups[0].0, "sql");
+        assert_eq!(groups[1].0, "log");
+        assert_eq!(groups[1].1.len(), 2);
+    }
 }
@@ -1,10 +1,34 @@
 //! Clean command - system cache cleanup
 
 use anyhow::Result;
+use chrono::Local;
 use colored::Colorize;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
 
-use crate::core::filesystem::{clean_directory, dir_size, format_size, is_root};
+use crate::core::config::Config;
+use crate::core::filesystem::{
+    begin_scan, clean_directory_keeping, count_files, dir_size, dir_size_dedup, format_size, is_root,
+    parse_size, size_mode, DeletionReport, SizeMode,
+};
+use crate::core::backup::backup_before_delete;
+use crate::core::output::{
+    confirm_deletion, mark_nothing_to_do, print_skipped, ItemConfirmer,
+    print_verification, warn_if_timed_out, warn_interrupted, warn_no_recent_protection,
+};
+use crate::core::interrupt::{install_handler, interrupted};
+use crate::core::report::{Report, ReportItem};
+use crate::core::security::{ConfirmPolicy, SecurityValidator};
+use crate::core::state::State;
+use crate::core::system::{FreeSpaceSnapshot, SystemInfo};
+use crate::core::time::format_duration;
+use crate::core::trash::move_to_trash;
+use crate::core::paths::CategoryGroup;
 use crate::core::CleanupPaths;
+use crate::{outln, warnln};
 
 /// Cleanup category with size information
 #[derive(Debug)]
@@ -14,34 +38,269 @@ pub struct CleanupCategory {
     pub size: u64,
     pub requires_sudo: bool,
     pub selected: bool,
+    pub group: CategoryGroup,
+    /// More directories emptied along with `path`, for a category spread
+    /// over several places
+    pub extra_paths: Vec<PathBuf>,
+}
+
+impl CleanupCategory {
+    /// Every directory this category empties
+    fn dirs(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.path).chain(&self.extra_paths)
+    }
+}
+
+/// Empty one category's directories, leaving `keep` in place, reporting
+/// the outcome as it finishes
+fn clean_category(cat: &CleanupCategory, files_only: bool, keep: &[PathBuf]) -> Option<DeletionReport> {
+    if interrupted() {
+        return None;
+    }
+
+    match clean_directories(cat.dirs(), false, files_only, keep) {
+        Ok(report) if report.interrupted => {
+            outln!("  {} Stopped {}", "○".dimmed(), cat.name);
+            Some(report)
+        }
+        Ok(report) => {
+            outln!("  {} Cleaned {}", "✓".green(), cat.name);
+            Some(report)
+        }
+        Err(e) => {
+            warnln!("  {} Failed {}: {}", "✗".red(), cat.name, e);
+            None
+        }
+    }
+}
+
+/// Caches written to within this window are assumed to be in use
+const IN_USE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Steam shader caches of games played within this window are kept, since
+/// rebuilding them means stutter on the next few launches
+const RECENTLY_PLAYED_WINDOW: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Options for the clean command
+#[derive(Debug, Clone, Default)]
+pub struct CleanOptions {
+    /// Preview changes without deleting
+    pub dry_run: bool,
+    /// Show paths next to each category
+    pub debug: bool,
+    /// Only list categories and their paths
+    pub list_only: bool,
+    /// Show root-only categories even when not running as root
+    pub include_system: bool,
+    /// Write the plan to this file
+    pub report: Option<PathBuf>,
+    /// Only clean these groups (all groups when empty)
+    pub groups: Vec<CategoryGroup>,
+    /// Compare the freed total with the disk's free space before and after
+    pub verify: bool,
+    /// Stop cleaning once a category's filesystem has this many bytes free
+    pub keep_free: Option<u64>,
+    /// Archive the targets into this directory before deleting them
+    pub backup: Option<PathBuf>,
+    /// Categories cleaned at once (number of CPUs when unset)
+    pub parallel: Option<usize>,
+    /// Leave out categories cleaned less than this long ago
+    pub skip_if_cleaned_within: Option<Duration>,
+    /// List the entries each category would delete in the preview
+    pub list_files: bool,
+    /// Select caches that look in use too
+    pub no_recent_protection: bool,
+    /// Cleanup intensity overriding the per-category defaults
+    pub preset: Option<CleanPreset>,
+    /// Ask before deleting each selected category
+    pub confirm_each: bool,
+    /// Which conditions ask for confirmation before deleting
+    pub confirm_policy: ConfirmPolicy,
+    /// Review large old files in Downloads instead of caches
+    pub stale_downloads: bool,
+    /// Delete only files, keeping each category's directory tree
+    pub files_only: bool,
+}
+
+/// Named cleanup intensity, a single dial instead of several flags
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum CleanPreset {
+    /// Keep browser caches, caches in use and anything under /var
+    Safe,
+    /// Clean every cache, including in-use ones and system caches (with sudo);
+    /// browser caches still wait for high_water_mark and keep_warm still holds
+    Aggressive,
+}
+
+impl CleanPreset {
+    fn name(self) -> &'static str {
+        match self {
+            CleanPreset::Safe => "safe",
+            CleanPreset::Aggressive => "aggressive",
+        }
+    }
+
+    /// Set the flags the preset stands for
+    fn configure(self, options: &mut CleanOptions) {
+        match self {
+            CleanPreset::Safe => options.no_recent_protection = false,
+            CleanPreset::Aggressive => {
+                options.include_system = true;
+                options.no_recent_protection = true;
+            }
+        }
+    }
+
+    /// Whether the preset cleans the category at `path`, given whether it
+    /// is a browser cache, whether `keep_warm` names it and whether it was
+    /// selected by default
+    pub fn selects(self, path: &Path, is_browser_cache: bool, kept_warm: bool, selected: bool) -> bool {
+        if kept_warm {
+            return false;
+        }
+        match self {
+            CleanPreset::Safe => selected && !is_browser_cache && !path.starts_with("/var"),
+            // An unselected browser cache is below high_water_mark
+            CleanPreset::Aggressive => selected || !is_browser_cache,
+        }
+    }
+}
+
+/// Apply `preset` to the scanned categories and say what it will and won't touch
+fn apply_preset(preset: CleanPreset, categories: &mut [CleanupCategory]) {
+    let paths = CleanupPaths::new();
+    let keep_warm = Config::load().keep_warm;
+    let home = dirs::home_dir();
+    for cat in categories.iter_mut() {
+        let kept_warm = home
+            .as_ref()
+            .is_some_and(|home| is_kept_warm(&keep_warm, home, &cat.name, &cat.path));
+        cat.selected = preset.selects(&cat.path, paths.is_browser_cache(&cat.path), kept_warm, cat.selected);
+    }
+
+    let names = |selected: bool| {
+        let names: Vec<_> = categories
+            .iter()
+            .filter(|c| c.selected == selected)
+            .map(|c| c.name.as_str())
+            .collect();
+        if names.is_empty() {
+            "nothing".to_string()
+        } else {
+            names.join(", ")
+        }
+    };
+
+    outln!("{}", format!("Preset {}:", preset.name()).bold());
+    outln!("  {} {}", "will clean:".green(), names(true));
+    outln!("  {} {}", "won't touch:".dimmed(), names(false));
+    outln!();
+}
+
+impl CleanOptions {
+    fn wants(&self, group: CategoryGroup) -> bool {
+        self.groups.is_empty() || self.groups.contains(&group)
+    }
+}
+
+/// A root-only category seen from an unprivileged run
+#[derive(Debug)]
+pub struct SystemProbe {
+    pub name: String,
+    pub path: PathBuf,
+    pub group: CategoryGroup,
+    /// Size from `sudo -n du`, if sudo didn't need a password
+    pub size: Option<u64>,
 }
 
 /// Scan all cleanup categories and calculate sizes
-pub fn scan_categories() -> Vec<CleanupCategory> {
+///
+/// With `recent_protection`, caches written to within `IN_USE_WINDOW` are
+/// listed but not selected. Categories named in `keep_warm` are never
+/// selected.
+pub fn scan_categories(recent_protection: bool) -> Vec<CleanupCategory> {
+    let _scan = begin_scan();
     let paths = CleanupPaths::new();
+    let config = Config::load();
+    let sysinfo = SystemInfo::new();
     let is_sudo = is_root();
 
     let mut categories = Vec::new();
 
     // User caches (no sudo needed)
-    for (name, path) in paths.user_caches() {
+    for (name, path, group) in paths.user_caches() {
         if path.exists() {
-            let size = dir_size(path).unwrap_or(0);
+            let size = if paths.is_hardlink_heavy(path) {
+                dir_size_dedup(path)
+            } else {
+                dir_size(path)
+            }
+            .unwrap_or(0);
             if size > 0 {
                 categories.push(CleanupCategory {
                     name: name.to_string(),
                     path: path.clone(),
                     size,
                     requires_sudo: false,
-                    selected: true,
+                    selected: auto_select(&paths, path, &sysinfo, config.high_water_mark),
+                    group,
+                    extra_paths: Vec::new(),
                 });
             }
         }
     }
 
+    // Electron app caches; one touched very recently likely belongs to a
+    // running app, so it is listed but not selected
+    for (name, path) in paths.electron_caches() {
+        let size = dir_size(&path).unwrap_or(0);
+        if size > 0 {
+            categories.push(CleanupCategory {
+                name,
+                selected: !(recent_protection && recently_modified(&path, IN_USE_WINDOW)),
+                path,
+                size,
+                requires_sudo: false,
+                group: CategoryGroup::Cache,
+                extra_paths: Vec::new(),
+            });
+        }
+    }
+
+    // Per-game Steam shader caches, kept for games played recently
+    for (name, path) in paths.steam_shader_caches() {
+        let size = dir_size(&path).unwrap_or(0);
+        if size > 0 {
+            categories.push(CleanupCategory {
+                name,
+                selected: !(recent_protection && recently_modified(&path, RECENTLY_PLAYED_WINDOW)),
+                path,
+                size,
+                requires_sudo: false,
+                group: CategoryGroup::Cache,
+                extra_paths: Vec::new(),
+            });
+        }
+    }
+
+    // Crash reports: apport's /var/crash (root only) and Crashpad databases
+    let crash_dirs = paths.crash_report_dirs(is_sudo);
+    let size: u64 = crash_dirs.iter().map(|dir| dir_size(dir).unwrap_or(0)).sum();
+    if let Some((path, extra_paths)) = crash_dirs.split_first().filter(|_| size > 0) {
+        categories.push(CleanupCategory {
+            name: "Crash Reports".to_string(),
+            path: path.clone(),
+            size,
+            requires_sudo: *path == paths.crash_reports,
+            selected: true,
+            group: CategoryGroup::Log,
+            extra_paths: extra_paths.to_vec(),
+        });
+    }
+
     // System caches (require sudo)
     if is_sudo {
-        for (name, path) in paths.system_caches() {
+        for (name, path, group) in paths.system_caches() {
             if path.exists() {
                 let size = dir_size(path).unwrap_or(0);
                 if size > 0 {
@@ -51,54 +310,602 @@ pub fn scan_categories() -> Vec<CleanupCategory> {
                         size,
                         requires_sudo: true,
                         selected: true,
+                        group,
+                        extra_paths: Vec::new(),
                     });
                 }
             }
         }
     }
 
+    if let Some(home) = dirs::home_dir() {
+        for cat in &mut categories {
+            if is_kept_warm(&config.keep_warm, &home, &cat.name, &cat.path) {
+                cat.selected = false;
+            }
+        }
+    }
+
     // Sort by size (largest first)
     categories.sort_by(|a, b| b.size.cmp(&a.size));
 
     categories
 }
 
+/// Whether one of `cat`'s directories lies inside a directory of another
+/// category in `categories`
+fn is_nested(cat: &CleanupCategory, categories: &[&CleanupCategory]) -> bool {
+    cat.dirs().any(|dir| {
+        categories
+            .iter()
+            .filter(|other| !std::ptr::eq(**other, cat))
+            .flat_map(|other| other.dirs())
+            .any(|outer| dir != outer && dir.starts_with(outer))
+    })
+}
+
+/// The path a `keep_warm` entry names, where `~/` stands for `home`; `None`
+/// for a category name
+fn keep_warm_path(entry: &str, home: &Path) -> Option<PathBuf> {
+    match entry.trim().strip_prefix("~/") {
+        Some(rest) => Some(home.join(rest)),
+        None => Some(PathBuf::from(entry.trim())).filter(|path| path.is_absolute()),
+    }
+}
+
+/// Whether a `keep_warm` entry names this category, either by its name
+/// (case-insensitive) or by its path
+fn is_kept_warm(keep_warm: &[String], home: &Path, name: &str, path: &Path) -> bool {
+    keep_warm.iter().any(|entry| {
+        entry.trim().eq_ignore_ascii_case(name) || keep_warm_path(entry, home).is_some_and(|p| p == path)
+    })
+}
+
+/// Everything `keep_warm` protects: the directories of the categories it
+/// names and the paths it lists. These stay in place when a selected
+/// category containing them (User Cache holds Pip Cache) is emptied.
+fn kept_warm_paths(keep_warm: &[String], home: &Path, categories: &[CleanupCategory]) -> Vec<PathBuf> {
+    let mut kept: Vec<PathBuf> = categories
+        .iter()
+        .filter(|cat| is_kept_warm(keep_warm, home, &cat.name, &cat.path))
+        .flat_map(|cat| cat.dirs().cloned())
+        .collect();
+    for path in keep_warm.iter().filter_map(|entry| keep_warm_path(entry, home)) {
+        if !kept.contains(&path) {
+            kept.push(path);
+        }
+    }
+    kept
+}
+
+fn build_report(categories: &[CleanupCategory], dry_run: bool) -> Report {
+    let items = categories
+        .iter()
+        .map(|c| ReportItem {
+            name: c.name.clone(),
+            kind: None,
+            path: Some(c.path.clone()),
+            size: c.size,
+            selected: c.selected,
+            requires_sudo: c.requires_sudo,
+        })
+        .collect();
+
+    Report::new("clean", dry_run, items)
+}
+
+/// Look up root-only categories without elevating
+///
+/// Sizes are probed through non-interactive sudo, so they are only known
+/// when credentials are cached or sudo is passwordless.
+pub fn probe_system_categories() -> Vec<SystemProbe> {
+    CleanupPaths::new()
+        .system_caches()
+        .into_iter()
+        .filter(|(_, path, _)| path.exists())
+        .map(|(name, path, group)| SystemProbe {
+            name: name.to_string(),
+            path: path.clone(),
+            group,
+            size: sudo_probe_size(path),
+        })
+        .collect()
+}
+
+fn sudo_probe_size(path: &Path) -> Option<u64> {
+    let unit = match size_mode() {
+        SizeMode::Apparent => "-b",
+        SizeMode::DiskUsage => "-B1",
+    };
+
+    // du exits non-zero when a subtree is unreadable but still prints a total
+    let output = Command::new("sudo")
+        .args(["-n", "du", "-s", unit])
+        .arg(path)
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn print_system_probe(options: &CleanOptions) {
+    let probes: Vec<_> = probe_system_categories()
+        .into_iter()
+        .filter(|p| options.wants(p.group))
+        .collect();
+    if probes.is_empty() {
+        return;
+    }
+
+    outln!("{}", "System caches (need sudo, not included):".bold());
+
+    for probe in probes {
+        let size_str = probe
+            .size
+            .map(format_size)
+            .unwrap_or_else(|| "size unknown".to_string());
+
+        let detail = if options.debug {
+            probe.path.display().to_string()
+        } else {
+            "[sudo]".to_string()
+        };
+
+        outln!(
+            "  {} {} {} {}",
+            "○".dimmed(),
+            probe.name.dimmed(),
+            size_str.dimmed(),
+            detail.dimmed()
+        );
+    }
+
+    outln!(
+        "{}",
+        "Rerun with `sudo mo clean` to include them.".dimmed()
+    );
+    outln!();
+}
+
+/// Check whether `path` or anything directly inside it changed within `window`
+fn recently_modified(path: &Path, window: Duration) -> bool {
+    let now = SystemTime::now();
+    let is_recent = |p: &Path| {
+        p.metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| now.duration_since(t).ok())
+            .is_some_and(|age| age < window)
+    };
+
+    is_recent(path)
+        || std::fs::read_dir(path)
+            .map(|entries| entries.filter_map(|e| e.ok()).any(|e| is_recent(&e.path())))
+            .unwrap_or(false)
+}
+
+/// Decide whether a category is selected by default given disk pressure
+///
+/// Browser caches are costly to rebuild, so they are only picked when the
+/// filesystem holding them is above the configured high-water mark.
+fn auto_select(paths: &CleanupPaths, path: &Path, sysinfo: &SystemInfo, high_water_mark: u8) -> bool {
+    if !paths.is_browser_cache(path) {
+        return true;
+    }
+
+    sysinfo
+        .disk_for_path(path)
+        .is_some_and(|disk| disk.usage_percent() >= f32::from(high_water_mark))
+}
+
+/// Decide which categories `--keep-free` cleans, given as `(size, mount point)`
+/// largest first
+///
+/// A category is spared once its filesystem is estimated to have `target`
+/// bytes free; each cleaned one adds its size to that estimate.
+pub fn plan_keep_free(
+    categories: &[(u64, String)],
+    mut available: HashMap<String, u64>,
+    target: u64,
+) -> Vec<bool> {
+    categories
+        .iter()
+        .map(|(size, mount)| {
+            let free = available.entry(mount.clone()).or_default();
+            if *free >= target {
+                false
+            } else {
+                *free += size;
+                true
+            }
+        })
+        .collect()
+}
+
+/// Entries shown per category by `--list-files`
+const LIST_FILES_LIMIT: usize = 15;
+
+/// The `limit` largest of `entries`, biggest first, and how many were left out
+pub fn largest_entries(mut entries: Vec<(PathBuf, u64)>, limit: usize) -> (Vec<(PathBuf, u64)>, usize) {
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let more = entries.len().saturating_sub(limit);
+    entries.truncate(limit);
+    (entries, more)
+}
+
+/// Empty each of `dirs` in turn, leaving `keep` in place, combining their
+/// reports
+///
+/// With `files_only`, subdirectories are kept and only files go.
+fn clean_directories<'a>(
+    dirs: impl Iterator<Item = &'a PathBuf>,
+    dry_run: bool,
+    files_only: bool,
+    keep: &[PathBuf],
+) -> crate::core::errors::Result<DeletionReport> {
+    let mut combined = DeletionReport::default();
+    for dir in dirs {
+        let report = clean_directory_keeping(dir, dry_run, files_only, keep)?;
+        combined.freed += report.freed;
+        combined.skipped.extend(report.skipped);
+        combined.entries.extend(report.entries);
+        if report.interrupted {
+            combined.interrupted = true;
+            break;
+        }
+    }
+    Ok(combined)
+}
+
+/// Show what cleaning each category would delete, walking it exactly as a
+/// real run does but without deleting
+fn print_file_list(categories: &[&CleanupCategory], files_only: bool, keep: &[PathBuf]) {
+    for cat in categories {
+        outln!("{}", cat.name.bold());
+
+        let report = match clean_directories(cat.dirs(), true, files_only, keep) {
+            Ok(report) => report,
+            Err(e) => {
+                warnln!("  {} {}", "✗".red(), e);
+                continue;
+            }
+        };
+        if report.entries.is_empty() {
+            outln!("  {}", "nothing to delete".dimmed());
+        }
+
+        let (shown, more) = largest_entries(report.entries, LIST_FILES_LIMIT);
+        for (path, size) in shown {
+            outln!("  {:>10}  {}", format_size(size), path.display());
+        }
+        if more > 0 {
+            outln!("  {}", format!("… and {} more", more).dimmed());
+        }
+    }
+    outln!();
+}
+
+/// Preview which selected categories `--keep-free` would clean and spare
+fn print_keep_free_plan(categories: &[&CleanupCategory], target: u64) {
+    let sysinfo = SystemInfo::new();
+    let mut available = HashMap::new();
+    let mounts: Vec<_> = categories
+        .iter()
+        .map(|c| {
+            let disk = sysinfo.disk_for_path(&c.path);
+            let mount = disk.as_ref().map(|d| d.mount_point.clone()).unwrap_or_default();
+            if let Some(disk) = disk {
+                available.insert(mount.clone(), disk.available_space);
+            }
+            (c.size, mount)
+        })
+        .collect();
+
+    let plan = plan_keep_free(&mounts, available, target);
+    let (cleaned, spared): (Vec<_>, Vec<_>) = categories.iter().zip(plan).partition(|(_, clean)| *clean);
+    let names = |list: Vec<(&&CleanupCategory, bool)>| {
+        list.iter().map(|(c, _)| c.name.as_str()).collect::<Vec<_>>().join(", ")
+    };
+
+    outln!("{}", format!("With --keep-free {}:", format_size(target)).bold());
+    if cleaned.is_empty() {
+        outln!("  {}", "Enough space is already free; nothing would be cleaned.".dimmed());
+    } else {
+        outln!("  {} {}", "would clean:".green(), names(cleaned));
+    }
+    if !spared.is_empty() {
+        outln!("  {} {}", "would spare:".dimmed(), names(spared));
+    }
+    outln!();
+}
+
+/// Describe `freed` bytes relative to the filesystem they came from,
+/// e.g. "4.1% of /, now 67% used"
+pub fn disk_context(freed: u64, total: u64, usage_percent: f32, mount: &str) -> String {
+    let share = if total > 0 {
+        freed as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    format!("{:.1}% of {}, now {:.0}% used", share, mount, usage_percent)
+}
+
+/// Print every known cleanup category and its path without sizing it
+fn list_categories(options: &CleanOptions) {
+    let paths = CleanupPaths::new();
+
+    let groups = [
+        ("User caches", paths.user_caches(), false),
+        ("System caches", paths.system_caches(), true),
+    ];
+
+    for (title, categories, requires_sudo) in groups {
+        outln!("{}", title.bold());
+        for (name, path, group) in categories {
+            if !options.wants(group) {
+                continue;
+            }
+            let status = if path.exists() {
+                "✓".green()
+            } else {
+                "✗".dimmed()
+            };
+            let sudo_marker = if requires_sudo { " [sudo]" } else { "" };
+
+            outln!(
+                "  {} {:<16} {:<16} {}{}",
+                status,
+                name,
+                group.label().dimmed(),
+                path.display().to_string().dimmed(),
+                sudo_marker.dimmed()
+            );
+        }
+        outln!();
+    }
+
+    outln!(
+        "{}",
+        "✓ = path exists, ✗ = not present on this system".dimmed()
+    );
+}
+
+/// A large file in Downloads nobody touched for a long time
+#[derive(Debug)]
+pub struct StaleFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Time since the file was last modified
+    pub age: Duration,
+}
+
+/// Files under `dir` of at least `min_size` bytes not modified within
+/// `min_age` of `now`, largest first
+///
+/// Symlinks are not followed, and whitelisted files are left out.
+pub fn find_stale_files(
+    dir: &Path,
+    min_age: Duration,
+    min_size: u64,
+    now: SystemTime,
+    whitelisted: impl Fn(&Path) -> bool,
+) -> Vec<StaleFile> {
+    let mut files: Vec<StaleFile> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let age = now.duration_since(metadata.modified().ok()?).ok()?;
+            (metadata.len() >= min_size && age >= min_age).then(|| StaleFile {
+                path: e.into_path(),
+                size: metadata.len(),
+                age,
+            })
+        })
+        .filter(|f| !whitelisted(&f.path))
+        .collect();
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    files
+}
+
+/// `--stale-downloads`: list large old files in Downloads and, with
+/// `--apply`, move the ones the user approves to the Trash
+///
+/// This is user data, so nothing is selected up front and nothing is
+/// deleted outright; every file needs its own yes.
+fn review_stale_downloads(options: &CleanOptions) -> Result<()> {
+    let config = Config::load();
+    let paths = CleanupPaths::new();
+    let min_size = parse_size(&config.stale_download_min_size).unwrap_or_else(|e| {
+        warnln!("Invalid stale_download_min_size: {}; using 100M", e);
+        100 * 1024 * 1024
+    });
+    let min_age = Duration::from_secs(u64::from(config.stale_download_days) * 24 * 60 * 60);
+
+    // Ask for a terminal before scanning, as --confirm-each does
+    let mut confirmer = (!options.dry_run)
+        .then(|| ItemConfirmer::asking("--stale-downloads", "Move {} to the Trash"))
+        .transpose()?;
+
+    outln!(
+        "{}",
+        format!(
+            "Looking for files in {} older than {} days and at least {}...",
+            paths.downloads.display(),
+            config.stale_download_days,
+            format_size(min_size)
+        )
+        .dimmed()
+    );
+    let validator = SecurityValidator::new();
+    let files = find_stale_files(&paths.downloads, min_age, min_size, SystemTime::now(), |p| {
+        validator.is_whitelisted(p)
+    });
+
+    if files.is_empty() {
+        outln!("{}", "No stale downloads found.".yellow());
+        mark_nothing_to_do();
+        return Ok(());
+    }
+
+    outln!();
+    outln!("{}", "Stale downloads:".bold());
+    outln!();
+    for file in &files {
+        outln!(
+            "  {} {} {} {}",
+            "○".dimmed(),
+            file.path.display().to_string().bold(),
+            format_size(file.size).yellow(),
+            format!("(modified {} ago)", format_duration(file.age)).dimmed()
+        );
+    }
+    outln!();
+    outln!(
+        "{}: {} in {} files",
+        "Total".bold(),
+        format_size(files.iter().map(|f| f.size).sum()).green().bold(),
+        files.len()
+    );
+    outln!();
+
+    let Some(confirmer) = &mut confirmer else {
+        outln!("{}", "[DRY RUN] No files were moved.".yellow().bold());
+        outln!(
+            "{}",
+            "Run again with --apply to go through them one by one; approved files are moved to the Trash."
+                .dimmed()
+        );
+        return Ok(());
+    };
+
+    let mut moved = 0u64;
+    let mut count = 0;
+    for file in &files {
+        if !confirmer.approve(&file.path.display().to_string(), file.size) {
+            continue;
+        }
+        match move_to_trash(&file.path, &paths.trash) {
+            Ok(_) => {
+                moved += file.size;
+                count += 1;
+            }
+            Err(e) => warnln!("  {} {}: {}", "✗".red(), file.path.display(), e),
+        }
+    }
+
+    outln!();
+    if count == 0 {
+        outln!("{}", "Nothing approved, nothing was moved.".yellow());
+    } else {
+        outln!(
+            "Moved {} files ({}) to the Trash; {} brings them back.",
+            count,
+            format_size(moved).green().bold(),
+            "mo restore".cyan()
+        );
+    }
+    Ok(())
+}
+
 /// Run the clean command
-pub fn run(dry_run: bool, debug: bool) -> Result<()> {
-    println!("{}", "Mole-RS Clean".bold().cyan());
-    println!("{}", "═".repeat(50));
-    println!();
+pub fn run(mut options: CleanOptions) -> Result<()> {
+    outln!("{}", "Mole-RS Clean".bold().cyan());
+    outln!("{}", "═".repeat(50));
+    outln!();
 
-    println!("{}", "Scanning cache directories...".dimmed());
-    let categories = scan_categories();
+    if options.list_only {
+        list_categories(&options);
+        return Ok(());
+    }
+
+    if options.stale_downloads {
+        return review_stale_downloads(&options);
+    }
+
+    let mut confirmer = options.confirm_each.then(ItemConfirmer::new).transpose()?;
+
+    if let Some(preset) = options.preset {
+        preset.configure(&mut options);
+    }
+
+    outln!("{}", "Scanning cache directories...".dimmed());
+    if options.no_recent_protection {
+        warn_no_recent_protection();
+    }
+    let mut categories = scan_categories(!options.no_recent_protection);
+    categories.retain(|c| options.wants(c.group));
+    warn_if_timed_out();
+
+    let mut state = State::load();
+    if let Some(window) = options.skip_if_cleaned_within {
+        let now = Local::now();
+        categories.retain(|c| match state.cleaned_ago(&c.name, now) {
+            Some(ago) if ago < window => {
+                outln!(
+                    "  {} Skipping {} (cleaned {} ago)",
+                    "○".dimmed(),
+                    c.name,
+                    format_duration(ago)
+                );
+                false
+            }
+            _ => true,
+        });
+    }
+
+    if let Some(preset) = options.preset {
+        apply_preset(preset, &mut categories);
+    }
+
+    if let Some(report_path) = &options.report {
+        build_report(&categories, options.dry_run).write(report_path)?;
+        outln!("Report written to {}", report_path.display().to_string().yellow());
+    }
 
     if categories.is_empty() {
-        println!("{}", "No caches found to clean.".yellow());
+        outln!("{}", "No caches found to clean.".yellow());
+        mark_nothing_to_do();
+        if options.include_system && !is_root() {
+            outln!();
+            print_system_probe(&options);
+        }
         return Ok(());
     }
 
-    let total_size: u64 = categories.iter().map(|c| c.size).sum();
+    let total_size: u64 = categories.iter().filter(|c| c.selected).map(|c| c.size).sum();
 
-    println!();
-    println!("{}", "Found cleanup targets:".bold());
-    println!();
+    outln!();
+    outln!("{}", "Found cleanup targets:".bold());
+    outln!();
 
     for cat in &categories {
         let size_str = format_size(cat.size);
         let sudo_marker = if cat.requires_sudo { " [sudo]" } else { "" };
+        let marker = if cat.selected {
+            "✓".green()
+        } else {
+            "○".dimmed()
+        };
 
-        if debug {
-            println!(
+        if options.debug {
+            outln!(
                 "  {} {} {} {}",
-                "✓".green(),
+                marker,
                 cat.name.bold(),
                 size_str.yellow(),
                 cat.path.display().to_string().dimmed()
             );
         } else {
-            println!(
+            outln!(
                 "  {} {} {}{}",
-                "✓".green(),
+                marker,
                 cat.name.bold(),
                 size_str.yellow(),
                 sudo_marker.dimmed()
@@ -106,43 +913,396 @@ pub fn run(dry_run: bool, debug: bool) -> Result<()> {
         }
     }
 
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}: {}",
         "Total space to free".bold(),
         format_size(total_size).green().bold()
     );
-    println!();
 
-    if dry_run {
-        println!("{}", "[DRY RUN] No files were deleted.".yellow().bold());
+    if categories.iter().any(|c| !c.selected) {
+        outln!(
+            "{}",
+            format!(
+                "○ Browser caches are kept until the disk is over {}% full (high_water_mark);",
+                Config::load().high_water_mark
+            )
+            .dimmed()
+        );
+        outln!(
+            "{}",
+            "  app caches written to in the last hour are kept as the app is likely running,"
+                .dimmed()
+        );
+        outln!(
+            "{}",
+            "  and Steam shader caches of games played in the last two weeks are kept.".dimmed()
+        );
+        if !Config::load().keep_warm.is_empty() {
+            outln!(
+                "{}",
+                "  categories listed in keep_warm are never selected, nor emptied along with another."
+                    .dimmed()
+            );
+        }
+    }
+    outln!();
+
+    if !is_root() {
+        if options.include_system {
+            print_system_probe(&options);
+        } else {
+            outln!(
+                "{}",
+                "System caches (APT, journal, logs) need sudo; use --include-system to see them."
+                    .dimmed()
+            );
+            outln!();
+        }
+    }
+
+    if let Some(confirmer) = &mut confirmer {
+        for cat in categories.iter_mut().filter(|c| c.selected) {
+            cat.selected = confirmer.approve(&cat.name, cat.size);
+        }
+        if !categories.iter().any(|c| c.selected) {
+            outln!("{}", "Nothing approved, nothing was deleted.".yellow());
+            return Ok(());
+        }
+    }
+    let total_size: u64 = categories.iter().filter(|c| c.selected).map(|c| c.size).sum();
+    let selected: Vec<_> = categories.iter().filter(|c| c.selected).collect();
+    let kept = dirs::home_dir()
+        .map(|home| kept_warm_paths(&Config::load().keep_warm, &home, &categories))
+        .unwrap_or_default();
+
+    if options.dry_run {
+        if options.list_files {
+            print_file_list(&selected, options.files_only, &kept);
+        }
+        if let Some(target) = options.keep_free {
+            print_keep_free_plan(&selected, target);
+        }
+        outln!("{}", "[DRY RUN] No files were deleted.".yellow().bold());
+        outln!("{}", "Run again with --apply to delete them.".dimmed());
         return Ok(());
     }
 
+    let targets: Vec<_> = categories.iter().filter(|c| c.selected).flat_map(|c| c.dirs()).collect();
+    let file_count = || targets.iter().map(|path| count_files(path) as u64).sum();
+    if !confirm_deletion(options.confirm_policy, &targets, total_size, file_count) {
+        outln!("{}", "Aborted, nothing was deleted.".yellow());
+        return Ok(());
+    }
+
+    if let Some(dir) = &options.backup {
+        if !backup_before_delete(dir, &targets, total_size)? {
+            outln!("{}", "Aborted, nothing was deleted.".yellow());
+            return Ok(());
+        }
+    }
+
     // Perform cleanup
-    println!("{}", "Cleaning...".dimmed());
+    outln!("{}", "Cleaning...".dimmed());
+    install_handler();
+    let before = options.verify.then(|| FreeSpaceSnapshot::take(&targets));
 
     let mut freed = 0u64;
+    let mut skipped = Vec::new();
+    let mut spared = Vec::new();
+    let mut freed_by_mount: BTreeMap<String, u64> = BTreeMap::new();
+    let mut sysinfo = SystemInfo::new();
 
-    for cat in &categories {
-        match clean_directory(&cat.path, false) {
-            Ok(size) => {
-                freed += size;
-                println!("  {} Cleaned {}", "✓".green(), cat.name);
+    let reports = if let Some(target) = options.keep_free {
+        // Largest first and one at a time: stop touching a filesystem once
+        // it has enough room
+        let mut reports = Vec::new();
+        for cat in selected {
+            sysinfo.refresh_disks();
+            if let Some(disk) = sysinfo.disk_for_path(&cat.path) {
+                if disk.available_space >= target {
+                    outln!(
+                        "  {} Spared {} ({} free on {})",
+                        "○".dimmed(),
+                        cat.name,
+                        format_size(disk.available_space),
+                        disk.mount_point
+                    );
+                    spared.push(cat.name.as_str());
+                    continue;
+                }
             }
-            Err(e) => {
-                println!("  {} Failed {}: {}", "✗".red(), cat.name, e);
+            reports.push((cat, clean_category(cat, options.files_only, &kept)));
+        }
+        reports
+    } else {
+        // A category inside another selected one (Pip Cache in User Cache)
+        // would race the outer removal, so those go first, one at a time;
+        // the remaining ones are disjoint and are cleaned side by side
+        let (nested, outer): (Vec<&CleanupCategory>, Vec<&CleanupCategory>) =
+            selected.iter().partition(|cat| is_nested(cat, &selected));
+        let mut reports: Vec<_> = nested
+            .into_iter()
+            .map(|cat| (cat, clean_category(cat, options.files_only, &kept)))
+            .collect();
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.parallel.unwrap_or(0))
+            .build()?;
+        reports.extend(pool.install(|| {
+            outer
+                .par_iter()
+                .map(|cat| (*cat, clean_category(cat, options.files_only, &kept)))
+                .collect::<Vec<_>>()
+        }));
+        reports
+    };
+
+    let cleaned_at = Local::now();
+    let total_categories = reports.len();
+    let mut finished = 0;
+    for (cat, report) in reports {
+        let Some(report) = report else {
+            continue;
+        };
+        if !report.interrupted {
+            state.mark_cleaned(&cat.name, cleaned_at);
+            finished += 1;
+        }
+        freed += report.freed;
+        if let Some(disk) = sysinfo.disk_for_path(&cat.path) {
+            *freed_by_mount.entry(disk.mount_point).or_default() += report.freed;
+        }
+        skipped.extend(report.skipped);
+    }
+
+    if let Err(e) = state.save() {
+        tracing::debug!("Could not save state: {}", e);
+    }
+
+    if interrupted() {
+        warn_interrupted(finished, total_categories, "categories");
+    }
+
+    // What the freed space means for each filesystem it came from
+    sysinfo.refresh_disks();
+    let contexts: Vec<_> = freed_by_mount
+        .iter()
+        .filter(|(_, bytes)| **bytes > 0)
+        .filter_map(|(mount, bytes)| {
+            let disk = sysinfo.disk_info().into_iter().find(|d| &d.mount_point == mount)?;
+            Some((*bytes, disk_context(*bytes, disk.total_space, disk.usage_percent(), mount)))
+        })
+        .collect();
+
+    outln!();
+    outln!("{}", "═".repeat(50));
+    match contexts.as_slice() {
+        [(_, context)] => outln!(
+            "{}: {} {}",
+            "Space freed".bold(),
+            format_size(freed).green().bold(),
+            format!("({})", context).dimmed()
+        ),
+        _ => {
+            outln!(
+                "{}: {}",
+                "Space freed".bold(),
+                format_size(freed).green().bold()
+            );
+            for (bytes, context) in &contexts {
+                outln!("  {} {}", format_size(*bytes), format!("({})", context).dimmed());
             }
         }
     }
 
-    println!();
-    println!("{}", "═".repeat(50));
-    println!(
-        "{}: {}",
-        "Space freed".bold(),
-        format_size(freed).green().bold()
-    );
+    if let Some(target) = options.keep_free {
+        if !spared.is_empty() {
+            outln!(
+                "{} {} ({} already free)",
+                "Spared:".bold(),
+                spared.join(", "),
+                format_size(target)
+            );
+        }
+    }
+
+    if let Some(before) = before {
+        print_verification(freed, FreeSpaceSnapshot::take(&targets).freed_since(&before));
+    }
+
+    print_skipped(&skipped);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_nested() {
+        let category = |name: &str, path: &str| CleanupCategory {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            size: 1,
+            requires_sudo: false,
+            selected: true,
+            group: CategoryGroup::Cache,
+            extra_paths: Vec::new(),
+        };
+        let user_cache = category("User Cache", "/home/u/.cache");
+        let pip = category("Pip Cache", "/home/u/.cache/pip");
+        let npm = category("NPM Cache", "/home/u/.npm/_cacache");
+        let all = [&user_cache, &pip, &npm];
+
+        assert!(is_nested(&pip, &all));
+        assert!(!is_nested(&user_cache, &all));
+        assert!(!is_nested(&npm, &all));
+        // A lookalike sibling is not inside
+        assert!(!is_nested(&category("Cachet", "/home/u/.cachet"), &all));
+    }
+
+    #[test]
+    fn test_find_stale_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("big.iso"), vec![0u8; 2048]).unwrap();
+        std::fs::write(temp.path().join("small.txt"), b"hi").unwrap();
+        std::fs::create_dir(temp.path().join("nested")).unwrap();
+        std::fs::write(temp.path().join("nested/keep.iso"), vec![0u8; 4096]).unwrap();
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let later = SystemTime::now() + 100 * day;
+        let keep = temp.path().join("nested/keep.iso");
+
+        let stale = find_stale_files(temp.path(), 90 * day, 1024, later, |p| p == keep);
+        assert_eq!(stale.len(), 1);
+        assert!(stale[0].path.ends_with("big.iso"));
+        assert!(stale[0].age >= 90 * day);
+
+        // Nothing is old enough yet
+        assert!(find_stale_files(temp.path(), 90 * day, 1024, SystemTime::now(), |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_is_kept_warm() {
+        let home = Path::new("/home/u");
+        let keep_warm = vec!["pip cache".to_string(), "~/.cache/go-build".to_string()];
+
+        assert!(is_kept_warm(&keep_warm, home, "Pip Cache", Path::new("/home/u/.cache/pip")));
+        assert!(is_kept_warm(&keep_warm, home, "Go Build", Path::new("/home/u/.cache/go-build")));
+        assert!(!is_kept_warm(&keep_warm, home, "NPM Cache", Path::new("/home/u/.npm/_cacache")));
+        assert!(!is_kept_warm(&[], home, "Pip Cache", Path::new("/home/u/.cache/pip")));
+    }
+
+    #[test]
+    fn test_kept_warm_paths_nested_in_parent() {
+        let home = Path::new("/home/u");
+        let category = |name: &str, path: &str| CleanupCategory {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            size: 1,
+            requires_sudo: false,
+            selected: true,
+            group: CategoryGroup::Cache,
+            extra_paths: Vec::new(),
+        };
+        let categories = [
+            category("User Cache", "/home/u/.cache"),
+            category("Pip Cache", "/home/u/.cache/pip"),
+        ];
+        let keep_warm = vec!["pip cache".to_string(), "~/.cache/huggingface".to_string()];
+
+        let kept = kept_warm_paths(&keep_warm, home, &categories);
+        assert_eq!(
+            kept,
+            [PathBuf::from("/home/u/.cache/pip"), PathBuf::from("/home/u/.cache/huggingface")]
+        );
+
+        // Emptying User Cache leaves both in place
+        let cache = tempfile::TempDir::new().unwrap();
+        let kept: Vec<_> = ["pip", "huggingface"].iter().map(|d| cache.path().join(d)).collect();
+        for dir in &kept {
+            std::fs::create_dir(dir).unwrap();
+            std::fs::write(dir.join("blob"), "warm").unwrap();
+        }
+        std::fs::write(cache.path().join("stale"), "old").unwrap();
+
+        let parent = category("User Cache", &cache.path().to_string_lossy());
+        clean_category(&parent, false, &kept).unwrap();
+        assert!(kept.iter().all(|dir| dir.join("blob").exists()));
+        assert!(!cache.path().join("stale").exists());
+    }
+
+    #[test]
+    fn test_clean_preset_selects() {
+        let cache = Path::new("/home/u/.cache/pip");
+        let apt = Path::new("/var/cache/apt/archives");
+
+        assert!(CleanPreset::Safe.selects(cache, false, false, true));
+        assert!(!CleanPreset::Safe.selects(cache, false, false, false));
+        assert!(!CleanPreset::Safe.selects(cache, true, false, true));
+        assert!(!CleanPreset::Safe.selects(apt, false, false, true));
+
+        assert!(CleanPreset::Aggressive.selects(cache, false, false, false));
+        assert!(CleanPreset::Aggressive.selects(apt, false, false, false));
+        // Browser caches follow high_water_mark, and keep_warm always holds
+        assert!(CleanPreset::Aggressive.selects(cache, true, false, true));
+        assert!(!CleanPreset::Aggressive.selects(cache, true, false, false));
+        assert!(!CleanPreset::Aggressive.selects(cache, false, true, true));
+        assert!(!CleanPreset::Safe.selects(cache, false, true, true));
+
+        let mut options = CleanOptions::default();
+        CleanPreset::Aggressive.configure(&mut options);
+        assert!(options.include_system && options.no_recent_protection);
+    }
+
+    #[test]
+    fn test_largest_entries() {
+        let entries = vec![
+            (PathBuf::from("/c/small"), 1),
+            (PathBuf::from("/c/big"), 300),
+            (PathBuf::from("/c/mid"), 20),
+        ];
+
+        let (shown, more) = largest_entries(entries.clone(), 2);
+        assert_eq!(shown, [(PathBuf::from("/c/big"), 300), (PathBuf::from("/c/mid"), 20)]);
+        assert_eq!(more, 1);
+
+        let (shown, more) = largest_entries(entries, 10);
+        assert_eq!(shown.len(), 3);
+        assert_eq!(more, 0);
+    }
+
+    #[test]
+    fn test_plan_keep_free_stops_once_target_met() {
+        let gib = 1024 * 1024 * 1024;
+        let categories = vec![
+            (3 * gib, "/".to_string()),
+            (2 * gib, "/".to_string()),
+            (gib, "/home".to_string()),
+            (gib, "/".to_string()),
+        ];
+        let available = HashMap::from([("/".to_string(), 6 * gib), ("/home".to_string(), 20 * gib)]);
+
+        let plan = plan_keep_free(&categories, available, 10 * gib);
+
+        assert_eq!(plan, vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn test_disk_context() {
+        let gib = 1024 * 1024 * 1024;
+        assert_eq!(
+            disk_context(gib * 41 / 10, 100 * gib, 67.2, "/"),
+            "4.1% of /, now 67% used"
+        );
+        assert_eq!(disk_context(10, 0, 0.0, "/home"), "0.0% of /home, now 0% used");
+    }
+
+    #[test]
+    fn test_plan_keep_free_unknown_mount_is_cleaned() {
+        let plan = plan_keep_free(&[(100, String::new())], HashMap::new(), 50);
+        assert_eq!(plan, vec![true]);
+    }
+}
@@ -0,0 +1,41 @@
+//! Config command - inspect the configuration file
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+use crate::core::config::{Config, Severity};
+use crate::{outln, sayln};
+
+/// Check the active config file (the profile's, with `--profile`) and list
+/// what is wrong with it; fails when anything would be ignored
+pub fn run_check() -> Result<()> {
+    let path = Config::config_path();
+    if !path.exists() {
+        sayln!("No config file at {}; defaults are in use.", path.display());
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    let issues = Config::check(&content);
+    if issues.is_empty() {
+        sayln!("{} {} is valid", "✓".green(), path.display());
+        return Ok(());
+    }
+
+    sayln!("{}", path.display().to_string().bold());
+    for issue in &issues {
+        let marker = match issue.severity {
+            Severity::Error => "✗".red(),
+            Severity::Warning => "!".yellow(),
+        };
+        sayln!("  {} {}", marker, issue.message);
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    if errors > 0 {
+        bail!("{} error(s) in {}", errors, path.display());
+    }
+    outln!();
+    outln!("{}", "Only warnings; the config is usable as is.".dimmed());
+    Ok(())
+}
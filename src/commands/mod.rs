@@ -2,7 +2,11 @@
 
 pub mod analyze;
 pub mod clean;
+pub mod config;
 pub mod optimize;
 pub mod purge;
+pub mod restore;
+pub mod scan;
 pub mod status;
 pub mod uninstall;
+pub mod version;
@@ -2,10 +2,28 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
 
-use crate::core::distro::{DistroInfo, PackageManager};
-use crate::core::filesystem::is_root;
+use crate::core::distro::{command_exists, DistroInfo, PackageManager};
+use crate::core::config::Config;
+use crate::core::filesystem::{
+    clean_directory, dir_size, find_broken_symlinks, format_size, is_broken_symlink, is_root,
+    parse_size, safe_delete,
+};
+use crate::core::glob;
+use crate::core::output::{confirm, is_quiet};
+use crate::core::report::{Report, ReportItem};
+use crate::core::security::unescape_mount_field;
+use crate::core::system::SystemInfo;
+use crate::core::CleanupPaths;
+use crate::{out, outln, sayln, warnln};
 
 /// Optimization task
 struct OptimizeTask {
@@ -13,89 +31,231 @@ struct OptimizeTask {
     description: String,
     requires_sudo: bool,
     command: Option<(String, Vec<String>)>,
-    action: Option<fn() -> Result<()>>,
+    /// Custom action, optionally returning a short note about what it did
+    action: Option<fn() -> Result<Option<String>>>,
+    /// Touches the network, so transient command failures are retried
+    retry: bool,
+    /// Directories sized before and after the task to see what it reclaimed
+    measure: Vec<PathBuf>,
 }
 
+/// How one task went, for the closing summary
+#[derive(Debug)]
+pub struct TaskOutcome {
+    pub name: String,
+    pub ok: bool,
+    /// Bytes freed in the measured directories; `None` when nothing was measured
+    pub reclaimed: Option<u64>,
+}
+
+/// Tasks picked with `--only` and `--skip`, by name
+///
+/// Names match case-insensitively, and `-` or `_` stand for spaces, so
+/// `vacuum-journal-logs` picks "Vacuum journal logs".
+#[derive(Debug, Clone, Default)]
+pub struct TaskSelection {
+    /// Run only these tasks (all when empty)
+    pub only: Vec<String>,
+    /// Never run these tasks
+    pub skip: Vec<String>,
+}
+
+impl TaskSelection {
+    fn normalize(name: &str) -> String {
+        name.trim().to_lowercase().replace(['-', '_'], " ")
+    }
+
+    /// Fail on a name that matches none of `known`, listing the valid ones
+    pub fn check_known<'a>(&self, known: impl Iterator<Item = &'a str> + Clone) -> Result<()> {
+        for name in self.only.iter().chain(&self.skip) {
+            let wanted = Self::normalize(name);
+            if !known.clone().any(|k| Self::normalize(k) == wanted) {
+                let names: Vec<_> = known.clone().map(|k| format!("\"{}\"", k)).collect();
+                return Err(anyhow::anyhow!(
+                    "Unknown optimize task \"{}\"; tasks on this system: {}",
+                    name,
+                    names.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn wants(&self, name: &str) -> bool {
+        let name = Self::normalize(name);
+        let listed = |names: &[String]| names.iter().any(|n| Self::normalize(n) == name);
+        (self.only.is_empty() || listed(&self.only)) && !listed(&self.skip)
+    }
+}
+
+/// Waits before the second and third attempt of a network-touching command
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(5)];
+
+/// Local filesystems that support discard via `fstrim`
+const TRIMMABLE_FS: &[&str] = &["ext4", "ext3", "xfs", "btrfs", "f2fs", "jfs", "vfat", "exfat"];
+
+/// Swap is worth flushing once this share of it is in use
+const SWAP_RESET_MIN_PERCENT: u64 = 20;
+
+/// RAM that must stay available after swap has been pulled back in
+const SWAP_RESET_HEADROOM: u64 = 512 * 1024 * 1024;
+
+/// Set while a `requires_sudo` task runs for a non-root user, so its
+/// commands go through `sudo -n` using the credential primed up front
+static ELEVATE: AtomicBool = AtomicBool::new(false);
+
 /// Run the optimize command
-pub fn run(dry_run: bool) -> Result<()> {
+///
+/// The package cache can be cleared fully or of obsolete packages only; when
+/// both tasks are picked, `autoclean` runs the obsolete-only one instead of
+/// the full clear. `clear_recent` adds the opt-in recent documents task.
+/// `selection` narrows the tasks down by name.
+pub fn run(
+    dry_run: bool,
+    autoclean: bool,
+    clear_recent: bool,
+    selection: TaskSelection,
+    report: Option<PathBuf>,
+) -> Result<()> {
     let distro = DistroInfo::detect();
     
-    println!("{}", "Mole-RS System Optimize".bold().cyan());
-    println!("{}", "═".repeat(50));
-    println!();
-    println!(
-        "Detected: {} ({})",
-        distro.distro.to_string().green(),
+    outln!("{}", "Mole-RS System Optimize".bold().cyan());
+    outln!("{}", "═".repeat(50));
+    outln!();
+    let mut detected = distro.distro.to_string();
+    if let Some(version) = &distro.version {
+        detected = format!("{} {}", detected, version);
+    }
+    let based_on = if distro.id_like.is_empty() {
+        String::new()
+    } else {
+        format!(", based on {}", distro.id_like.join("/"))
+    };
+    outln!(
+        "Detected: {}{} ({})",
+        detected.green(),
+        based_on.dimmed(),
         format!("{:?}", distro.package_manager).dimmed()
     );
-    println!();
+    outln!();
 
     let is_sudo = is_root();
-    let tasks = build_tasks(&distro);
+    let can_sudo = is_sudo || which("sudo");
+    let tasks = build_tasks(&distro, clear_recent);
+    selection.check_known(tasks.iter().map(|t| t.name.as_str()))?;
 
-    let available_tasks: Vec<_> = tasks
+    let mut available_tasks: Vec<_> = tasks
         .iter()
-        .filter(|t| !t.requires_sudo || is_sudo)
+        .filter(|t| selection.wants(&t.name))
+        .filter(|t| !t.requires_sudo || can_sudo)
         .collect();
 
+    // A full clear leaves nothing for the obsolete-only clean to do
+    let full_clear = package_cache_task_name(&distro.package_manager);
+    let picked = |name: &str| available_tasks.iter().any(|t| t.name == name);
+    if picked(&full_clear) && picked(OBSOLETE_PACKAGES_TASK) {
+        let superseded = if autoclean { full_clear.as_str() } else { OBSOLETE_PACKAGES_TASK };
+        available_tasks.retain(|t| t.name != superseded);
+    }
+
     if available_tasks.is_empty() {
-        println!("{}", "No optimization tasks available.".yellow());
-        println!(
+        outln!("{}", "No optimization tasks available.".yellow());
+        outln!(
             "{}",
             "Run with sudo for system-level optimizations.".dimmed()
         );
         return Ok(());
     }
 
-    println!("{}", "Optimization tasks:".bold());
-    println!();
+    if let Some(report_path) = &report {
+        build_report(&available_tasks, dry_run).write(report_path)?;
+        outln!("Report written to {}", report_path.display().to_string().yellow());
+        outln!();
+    }
+
+    outln!("{}", "Optimization tasks:".bold());
+    outln!();
 
     for task in &available_tasks {
         let sudo_marker = if task.requires_sudo { " [sudo]" } else { "" };
-        println!(
+        outln!(
             "  {} {} {}",
             "→".cyan(),
             task.name.bold(),
             sudo_marker.dimmed()
         );
-        println!("    {}", task.description.dimmed());
+        outln!("    {}", task.description.dimmed());
     }
 
-    println!();
+    outln!();
 
     if dry_run {
-        println!("{}", "[DRY RUN] No changes were made.".yellow().bold());
+        outln!("{}", "[DRY RUN] No changes were made.".yellow().bold());
         return Ok(());
     }
 
+    // Ask for the password once up front rather than once per task
+    let sudo_tasks = available_tasks.iter().filter(|t| t.requires_sudo).count();
+    let mut skipped_sudo = false;
+    if !is_sudo && sudo_tasks > 0 && !prime_sudo() {
+        warnln!(
+            "{} Could not get sudo; skipping {} system task(s).",
+            "!".yellow(),
+            sudo_tasks
+        );
+        available_tasks.retain(|t| !t.requires_sudo);
+        skipped_sudo = true;
+    }
+
     // Execute tasks
-    println!("{}", "Running optimizations...".dimmed());
-    println!();
+    outln!("{}", "Running optimizations...".dimmed());
+    outln!();
 
+    let mut outcomes = Vec::new();
     for task in &available_tasks {
-        print!("  {} {}... ", "→".cyan(), task.name);
+        out!("  {} {}... ", "→".cyan(), task.name);
+        ELEVATE.store(task.requires_sudo && !is_sudo, Ordering::Relaxed);
+        let before = measured_size(&task.measure);
 
         let result = if let Some((cmd, args)) = &task.command {
-            run_command(cmd, &args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+            let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            if task.retry {
+                run_command_with_retry(cmd, &args)
+            } else {
+                run_command(cmd, &args).map(|_| None)
+            }
         } else if let Some(action) = task.action {
             action()
         } else {
-            Ok(())
+            Ok(None)
         };
 
+        outcomes.push(TaskOutcome {
+            name: task.name.clone(),
+            ok: result.is_ok(),
+            reclaimed: before
+                .zip(measured_size(&task.measure))
+                .map(|(before, after)| before.saturating_sub(after)),
+        });
+
         match result {
-            Ok(_) => println!("{}", "done".green()),
-            Err(e) => println!("{} {}", "failed:".red(), e),
+            Ok(Some(note)) => outln!("{} {}", "done".green(), note.dimmed()),
+            Ok(None) => outln!("{}", "done".green()),
+            Err(e) if is_quiet() => warnln!("{} {}: {}", "failed:".red(), task.name, e),
+            Err(e) => warnln!("{} {}", "failed:".red(), e),
         }
     }
+    ELEVATE.store(false, Ordering::Relaxed);
 
-    println!();
-    println!("{}", "═".repeat(50));
-    println!("{}", "System optimization completed.".green().bold());
+    outln!();
+    outln!("{}", "═".repeat(50));
+    print_summary(&outcomes);
+    outln!();
+    outln!("{}", "System optimization completed.".green().bold());
 
-    if !is_sudo {
-        println!();
-        println!(
+    if !can_sudo || skipped_sudo {
+        outln!();
+        outln!(
             "{}",
             "Tip: Run with sudo for additional optimizations.".dimmed()
         );
@@ -104,8 +264,77 @@ pub fn run(dry_run: bool) -> Result<()> {
     Ok(())
 }
 
+/// Combined size of `dirs`, or `None` when there is nothing to measure
+fn measured_size(dirs: &[PathBuf]) -> Option<u64> {
+    if dirs.is_empty() {
+        return None;
+    }
+    Some(dirs.iter().map(|dir| dir_size(dir).unwrap_or(0)).sum())
+}
+
+/// Rows of the closing summary (task, status, reclaimed) and the grand total
+pub fn summary_rows(outcomes: &[TaskOutcome]) -> (Vec<[String; 3]>, u64) {
+    let rows = outcomes
+        .iter()
+        .map(|o| {
+            [
+                o.name.clone(),
+                if o.ok { "done" } else { "failed" }.to_string(),
+                o.reclaimed.map(format_size).unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    let total = outcomes.iter().filter_map(|o| o.reclaimed).sum();
+
+    (rows, total)
+}
+
+fn print_summary(outcomes: &[TaskOutcome]) {
+    let (rows, total) = summary_rows(outcomes);
+    let width = rows.iter().map(|[name, ..]| name.chars().count()).max().unwrap_or(0);
+
+    outln!("{}", "Summary:".bold());
+    for [name, status, reclaimed] in &rows {
+        let status = if status == "done" {
+            format!("{:<6}", status).green()
+        } else {
+            format!("{:<6}", status).red()
+        };
+        outln!("  {:<width$}  {}  {:>10}", name, status, reclaimed, width = width);
+    }
+    outln!(
+        "{}: {}",
+        "Total reclaimed".bold(),
+        format_size(total).green().bold()
+    );
+}
+
+fn build_report(tasks: &[&OptimizeTask], dry_run: bool) -> Report {
+    let items = tasks
+        .iter()
+        .map(|t| ReportItem {
+            name: t.name.clone(),
+            kind: Some(t.description.clone()),
+            path: None,
+            size: 0,
+            selected: true,
+            requires_sudo: t.requires_sudo,
+        })
+        .collect();
+
+    Report::new("optimize", dry_run, items)
+}
+
+/// Task that removes only cached packages that can no longer be installed
+const OBSOLETE_PACKAGES_TASK: &str = "Clean obsolete packages only";
+
+/// Name of the task clearing the whole package cache
+fn package_cache_task_name(package_manager: &PackageManager) -> String {
+    format!("Clear {:?} cache", package_manager)
+}
+
 /// Build tasks based on detected distro
-fn build_tasks(distro: &DistroInfo) -> Vec<OptimizeTask> {
+fn build_tasks(distro: &DistroInfo, clear_recent: bool) -> Vec<OptimizeTask> {
     let mut tasks = Vec::new();
 
     // Universal tasks
@@ -115,6 +344,8 @@ fn build_tasks(distro: &DistroInfo) -> Vec<OptimizeTask> {
         requires_sudo: false,
         command: None,
         action: Some(clear_thumbnails),
+        retry: false,
+        measure: vec![CleanupPaths::new().thumbnails],
     });
 
     tasks.push(OptimizeTask {
@@ -123,40 +354,152 @@ fn build_tasks(distro: &DistroInfo) -> Vec<OptimizeTask> {
         requires_sudo: false,
         command: Some(("fc-cache".to_string(), vec!["-f".to_string()])),
         action: None,
+        retry: false,
+        measure: Vec::new(),
+    });
+
+    tasks.push(OptimizeTask {
+        name: "Remove old app logs".to_string(),
+        description: format!(
+            "Delete logs older than {} days under ~/.config and ~/.local/state",
+            Config::load().app_log_max_age_days
+        ),
+        requires_sudo: false,
+        command: None,
+        action: Some(clean_app_logs),
+        retry: false,
+        measure: Vec::new(),
+    });
+
+    tasks.push(OptimizeTask {
+        name: "Remove broken symlinks".to_string(),
+        description: "Delete links whose target is gone under ~/.local and ~/.config".to_string(),
+        requires_sudo: false,
+        command: None,
+        action: Some(remove_broken_symlinks),
+        retry: false,
+        measure: Vec::new(),
     });
 
+    // Opt-in: some users rely on the recent files list
+    if clear_recent {
+        tasks.push(OptimizeTask {
+            name: "Clear recent documents".to_string(),
+            description: "Forget the GTK and KDE recently used files lists".to_string(),
+            requires_sudo: false,
+            command: None,
+            action: Some(clear_recent_documents),
+            retry: false,
+            measure: Vec::new(),
+        });
+    }
+
+    let stale = find_stale_files(&stale_file_dirs(), &ProcessUsage::read(), STALE_MIN_AGE);
+    if !stale.is_empty() {
+        tasks.push(OptimizeTask {
+            name: "Remove stale sockets and lock files".to_string(),
+            description: format!(
+                "{} in /tmp and /var/tmp whose process is gone",
+                stale.len()
+            ),
+            requires_sudo: false,
+            command: None,
+            action: Some(remove_stale_files),
+            retry: false,
+            measure: Vec::new(),
+        });
+    }
+
     // Package manager specific tasks
+    let package_cache: Vec<PathBuf> = distro
+        .package_manager
+        .cache_paths()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+
     if let Some(cmd) = distro.package_manager.clean_cache_cmd() {
         tasks.push(OptimizeTask {
-            name: format!("Clear {} cache", format!("{:?}", distro.package_manager)),
+            name: package_cache_task_name(&distro.package_manager),
             description: "Remove downloaded package files".to_string(),
             requires_sudo: true,
             command: Some((cmd[0].to_string(), cmd[1..].iter().map(|s| s.to_string()).collect())),
             action: None,
+            retry: false,
+            measure: package_cache.clone(),
+        });
+    }
+
+    if let Some(cmd) = distro.package_manager.autoclean_cmd() {
+        tasks.push(OptimizeTask {
+            name: OBSOLETE_PACKAGES_TASK.to_string(),
+            description: "Remove cached packages that can no longer be installed".to_string(),
+            requires_sudo: true,
+            command: Some((cmd[0].to_string(), cmd[1..].iter().map(|s| s.to_string()).collect())),
+            action: None,
+            retry: false,
+            measure: package_cache,
         });
     }
 
     if let Some(cmd) = distro.package_manager.autoremove_cmd() {
-        // Skip pacman's complex command for now
-        if distro.package_manager != PackageManager::Pacman {
-            tasks.push(OptimizeTask {
-                name: "Remove orphan packages".to_string(),
-                description: "Remove unused dependencies".to_string(),
-                requires_sudo: true,
-                command: Some((cmd[0].to_string(), cmd[1..].iter().map(|s| s.to_string()).collect())),
-                action: None,
-            });
-        }
+        tasks.push(OptimizeTask {
+            name: "Remove orphan packages".to_string(),
+            description: "Remove unused dependencies".to_string(),
+            requires_sudo: true,
+            command: Some((cmd[0].to_string(), cmd[1..].iter().map(|s| s.to_string()).collect())),
+            action: None,
+            retry: true,
+            measure: Vec::new(),
+        });
+    } else if distro.package_manager.orphans_query_cmd().is_some() {
+        tasks.push(OptimizeTask {
+            name: "Remove orphan packages".to_string(),
+            description: "Remove unused dependencies".to_string(),
+            requires_sudo: true,
+            command: None,
+            action: Some(remove_orphans),
+            retry: false,
+            measure: Vec::new(),
+        });
+    }
+
+    // Libraries nothing depends on anymore, which autoremove keeps because
+    // they were installed by hand
+    if distro.package_manager == PackageManager::Apt {
+        let tool = if command_exists("deborphan") {
+            "deborphan"
+        } else {
+            "apt-mark showmanual"
+        };
+        tasks.push(OptimizeTask {
+            name: "Remove orphaned libraries".to_string(),
+            description: format!(
+                "List manually installed libraries with no reverse dependencies ({}) and ask before removing",
+                tool
+            ),
+            requires_sudo: true,
+            command: None,
+            action: Some(remove_apt_orphans),
+            retry: false,
+            measure: Vec::new(),
+        });
     }
 
     // Journal cleanup (systemd-based distros)
     if std::path::Path::new("/usr/bin/journalctl").exists() {
+        let journal_size = Config::load().journal_max_size;
         tasks.push(OptimizeTask {
             name: "Vacuum journal logs".to_string(),
-            description: "Limit journal size to 100M".to_string(),
+            description: format!("Limit journal size to {}", journal_size),
             requires_sudo: true,
-            command: Some(("journalctl".to_string(), vec!["--vacuum-size=100M".to_string()])),
+            command: Some((
+                "journalctl".to_string(),
+                vec![format!("--vacuum-size={}", journal_size)],
+            )),
             action: None,
+            retry: false,
+            measure: vec![PathBuf::from("/var/log/journal")],
         });
     }
 
@@ -164,10 +507,67 @@ fn build_tasks(distro: &DistroInfo) -> Vec<OptimizeTask> {
     if distro.has_snap {
         tasks.push(OptimizeTask {
             name: "Clean old snap revisions".to_string(),
-            description: "Remove disabled snap versions".to_string(),
+            description: snap_revisions_description(),
             requires_sudo: true,
             command: None,
             action: Some(clean_old_snaps),
+            retry: false,
+            measure: vec![PathBuf::from("/var/lib/snapd/snaps")],
+        });
+    }
+
+    // btrfs snapshots are invisible to path-based scans; prune them through
+    // the tool that made them
+    let mounts = std::fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+    if root_is_btrfs(&mounts) {
+        if let Some(tool) = SnapshotTool::detect() {
+            tasks.push(OptimizeTask {
+                name: "Remove old btrfs snapshots".to_string(),
+                description: btrfs_snapshots_description(tool),
+                requires_sudo: true,
+                command: None,
+                action: Some(remove_old_snapshots),
+                retry: false,
+                measure: Vec::new(),
+            });
+        }
+    }
+
+    // Swap reset (only when RAM can absorb everything that's swapped out)
+    let sysinfo = SystemInfo::new();
+    if swap_reset_advisable(sysinfo.total_swap(), sysinfo.used_swap(), sysinfo.available_memory()) {
+        tasks.push(OptimizeTask {
+            name: "Reset swap".to_string(),
+            description: format!(
+                "Move {} of swapped-out memory back into RAM",
+                format_size(sysinfo.used_swap())
+            ),
+            requires_sudo: true,
+            command: None,
+            action: Some(reset_swap),
+            retry: false,
+            measure: Vec::new(),
+        });
+    }
+
+    // SSD TRIM
+    let trimmable = trimmable_mountpoints(&mounts, is_rotational);
+    if !trimmable.is_empty() && which("fstrim") {
+        tasks.push(OptimizeTask {
+            name: "TRIM SSD filesystems".to_string(),
+            description: format!(
+                "Discard unused blocks on {}",
+                trimmable
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            requires_sudo: true,
+            command: None,
+            action: Some(trim_ssds),
+            retry: false,
+            measure: Vec::new(),
         });
     }
 
@@ -179,14 +579,75 @@ fn build_tasks(distro: &DistroInfo) -> Vec<OptimizeTask> {
             requires_sudo: false,
             command: Some(("flatpak".to_string(), vec!["uninstall".to_string(), "--unused".to_string(), "-y".to_string()])),
             action: None,
+            retry: true,
+            measure: Vec::new(),
+        });
+    }
+
+    // Per-app Flatpak caches, largest first so the hoarders stand out
+    let flatpak_app_caches = CleanupPaths::new().flatpak_app_caches();
+    let flatpak_cache_dirs: Vec<PathBuf> =
+        flatpak_app_caches.iter().map(|(_, path)| path.clone()).collect();
+    let mut flatpak_caches: Vec<_> = flatpak_app_caches
+        .into_iter()
+        .map(|(app, path)| (app, dir_size(&path).unwrap_or(0)))
+        .filter(|(_, size)| *size > 0)
+        .collect();
+    flatpak_caches.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    if !flatpak_caches.is_empty() {
+        let total: u64 = flatpak_caches.iter().map(|(_, size)| size).sum();
+        let top = flatpak_caches
+            .iter()
+            .take(3)
+            .map(|(app, size)| format!("{} {}", app, format_size(*size)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        tasks.push(OptimizeTask {
+            name: "Clear Flatpak app caches".to_string(),
+            description: format!("Empty ~/.var/app/*/cache, {} total ({})", format_size(total), top),
+            requires_sudo: false,
+            command: None,
+            action: Some(clear_flatpak_caches),
+            retry: false,
+            measure: flatpak_cache_dirs,
         });
     }
 
     tasks
 }
 
+/// Validate sudo credentials once so the system tasks that follow don't
+/// each prompt
+///
+/// Without a terminal there is nobody to type a password, so only a cached
+/// credential (or a NOPASSWD rule) is accepted.
+fn prime_sudo() -> bool {
+    let mut sudo = Command::new("sudo");
+    if std::io::stdin().is_terminal() {
+        outln!("{}", "System tasks need sudo; you may be asked for your password once.".dimmed());
+        sudo.arg("-v");
+    } else {
+        sudo.args(["-n", "-v"]);
+    }
+
+    sudo.status().map(|s| s.success()).unwrap_or(false)
+}
+
+/// Build a command for `program`, run through `sudo -n` while `ELEVATE` is set
+fn system_command(program: &str) -> Command {
+    if ELEVATE.load(Ordering::Relaxed) {
+        let mut cmd = Command::new("sudo");
+        cmd.args(["-n", program]);
+        cmd
+    } else {
+        Command::new(program)
+    }
+}
+
 fn run_command(cmd: &str, args: &[&str]) -> Result<()> {
-    let output = Command::new(cmd).args(args).output()?;
+    let output = system_command(cmd).args(args).output()?;
 
     if output.status.success() {
         Ok(())
@@ -198,7 +659,596 @@ fn run_command(cmd: &str, args: &[&str]) -> Result<()> {
     }
 }
 
-fn clear_thumbnails() -> Result<()> {
+/// Query orphaned packages and remove them by name
+///
+/// Pacman and zypper can't do this in one command without a shell, so the
+/// query's output is parsed here and the names passed on.
+fn remove_orphans() -> Result<Option<String>> {
+    let manager = DistroInfo::detect().package_manager;
+    let Some(query) = manager.orphans_query_cmd() else {
+        return Ok(None);
+    };
+
+    // `pacman -Qtdq` exits 1 when there is nothing to list
+    let output = Command::new(query[0]).args(&query[1..]).output()?;
+    let orphans = manager.parse_orphans(&String::from_utf8_lossy(&output.stdout));
+
+    let Some(cmd) = manager.remove_orphans_cmd(&orphans) else {
+        return Ok(Some("(no orphans)".to_string()));
+    };
+
+    let args: Vec<&str> = cmd[1..].iter().map(|s| s.as_str()).collect();
+    run_command_with_retry(&cmd[0], &args)?;
+
+    Ok(Some(format!("({} removed: {})", orphans.len(), orphans.join(", "))))
+}
+
+/// Packages from `apt-cache rdepends --installed` output that nothing
+/// installed depends on
+///
+/// Each block is the package name followed by `Reverse Depends:` and one
+/// indented line per dependent.
+pub fn parse_rdepends_orphans(output: &str) -> Vec<String> {
+    let mut orphans = Vec::new();
+    let mut current: Option<(&str, bool)> = None;
+
+    for line in output.lines() {
+        if line.trim() == "Reverse Depends:" {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, needed)) = current.as_mut() {
+                *needed = true;
+            }
+            continue;
+        }
+        if let Some((name, false)) = current {
+            orphans.push(name.to_string());
+        }
+        current = Some((line.trim(), false)).filter(|(name, _)| !name.is_empty());
+    }
+    if let Some((name, false)) = current {
+        orphans.push(name.to_string());
+    }
+
+    orphans
+}
+
+/// Packages from `dpkg-query -W -f '${Package}\t${Section}\n'` output filed
+/// under the `libs` or `oldlibs` section of any archive area
+///
+/// The section is what marks a library; a name such as libreoffice or
+/// libvirt-daemon-system does not.
+pub fn parse_library_sections(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(_, section)| {
+            let section = section.trim();
+            let section = section.rsplit_once('/').map_or(section, |(_, s)| s);
+            matches!(section, "libs" | "oldlibs")
+        })
+        .map(|(name, _)| name.trim().to_string())
+        .collect()
+}
+
+/// Orphaned library candidates, from deborphan when installed or else from
+/// manually installed packages in the `libs` sections without installed
+/// reverse dependencies
+fn apt_orphan_candidates() -> Result<Vec<String>> {
+    if command_exists("deborphan") {
+        let output = Command::new("deborphan").output()?;
+        return Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect());
+    }
+
+    let manual = Command::new("apt-mark").arg("showmanual").output()?;
+    let manual: Vec<String> = String::from_utf8_lossy(&manual.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+    if manual.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sections = Command::new("dpkg-query")
+        .args(["-W", "-f", "${Package}\t${Section}\n"])
+        .args(&manual)
+        .output()?;
+    let libraries = parse_library_sections(&String::from_utf8_lossy(&sections.stdout));
+    if libraries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = Command::new("apt-cache")
+        .args(["rdepends", "--installed", "--no-suggests", "--no-enhances"])
+        .args(&libraries)
+        .output()?;
+    Ok(parse_rdepends_orphans(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn remove_apt_orphans() -> Result<Option<String>> {
+    let orphans = apt_orphan_candidates()?;
+    if orphans.is_empty() {
+        return Ok(Some("(no orphans)".to_string()));
+    }
+
+    // These are guesses, so someone has to look at them first
+    if !std::io::stdin().is_terminal() {
+        return Ok(Some(format!(
+            "({} candidates, skipped; run interactively to review them)",
+            orphans.len()
+        )));
+    }
+
+    // Shown even with --quiet: the prompt below asks about exactly these
+    sayln!();
+    for name in &orphans {
+        sayln!("      {} {}", "•".dimmed(), name);
+    }
+    if !confirm(&format!("    Remove these {} packages?", orphans.len())) {
+        return Ok(Some("(kept)".to_string()));
+    }
+
+    let args: Vec<&str> = ["remove", "-y"]
+        .into_iter()
+        .chain(orphans.iter().map(String::as_str))
+        .collect();
+    run_command("apt-get", &args)?;
+
+    Ok(Some(format!("({} removed)", orphans.len())))
+}
+
+/// Whether a command's error output points at a passing problem (network,
+/// mirror or lock trouble) rather than one that retrying won't fix
+pub fn is_transient_failure(stderr: &str) -> bool {
+    const TRANSIENT: &[&str] = &[
+        "timed out",
+        "timeout",
+        "temporary failure",
+        "could not resolve",
+        "connection",
+        "network",
+        "try again",
+        "could not get lock",
+        "503",
+        "502",
+    ];
+
+    let stderr = stderr.to_lowercase();
+    TRANSIENT.iter().any(|pattern| stderr.contains(pattern))
+}
+
+/// Run a network-touching command, retrying transient failures with a
+/// growing delay
+fn run_command_with_retry(cmd: &str, args: &[&str]) -> Result<Option<String>> {
+    let mut attempt = 1;
+
+    loop {
+        match run_command(cmd, args) {
+            Ok(()) if attempt > 1 => {
+                return Ok(Some(format!("(succeeded on attempt {})", attempt)));
+            }
+            Ok(()) => return Ok(None),
+            Err(e) if attempt <= RETRY_DELAYS.len() && is_transient_failure(&e.to_string()) => {
+                tracing::debug!("{} failed (attempt {}), retrying: {}", cmd, attempt, e);
+                std::thread::sleep(RETRY_DELAYS[attempt - 1]);
+                attempt += 1;
+            }
+            Err(e) if attempt > 1 => {
+                return Err(anyhow::anyhow!(
+                    "{} (gave up after {} attempts)",
+                    e.to_string().trim(),
+                    attempt
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn which(program: &str) -> bool {
+    ["/usr/sbin", "/sbin", "/usr/bin", "/bin"]
+        .iter()
+        .any(|dir| Path::new(dir).join(program).exists())
+}
+
+/// Pick the mountpoints `fstrim` should run on from `/proc/self/mounts` content
+///
+/// Only local block-device filesystems whose device is known to be
+/// non-rotational are returned, and each device is listed once (btrfs
+/// subvolumes mount the same device several times).
+pub fn trimmable_mountpoints<F>(mounts: &str, is_rotational: F) -> Vec<PathBuf>
+where
+    F: Fn(&Path) -> Option<bool>,
+{
+    let mut seen_devices = Vec::new();
+    let mut mountpoints = Vec::new();
+
+    for line in mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(mountpoint), Some(fs_type)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        if !device.starts_with("/dev/") || !TRIMMABLE_FS.contains(&fs_type) {
+            continue;
+        }
+
+        if seen_devices.contains(&device) || is_rotational(Path::new(device)) != Some(false) {
+            continue;
+        }
+
+        seen_devices.push(device);
+        mountpoints.push(PathBuf::from(unescape_mount_field(mountpoint)));
+    }
+
+    mountpoints
+}
+
+/// Read `queue/rotational` for the disk behind a `/dev` node
+///
+/// Partitions don't have a queue of their own, so their parent disk is
+/// consulted instead.
+fn is_rotational(device: &Path) -> Option<bool> {
+    let device = std::fs::canonicalize(device).ok()?;
+    let name = device.file_name()?;
+    let sys = std::fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()?;
+
+    let flag = std::fs::read_to_string(sys.join("queue/rotational"))
+        .or_else(|_| std::fs::read_to_string(sys.parent().unwrap_or(&sys).join("queue/rotational")))
+        .ok()?;
+
+    Some(flag.trim() == "1")
+}
+
+/// Extract the byte count from `fstrim -v` output
+///
+/// Handles both `/: 1.2 GiB (1288490188 bytes) trimmed` and the older
+/// `/: 1288490188 bytes were trimmed` format.
+pub fn parse_fstrim_bytes(output: &str) -> Option<u64> {
+    let words: Vec<&str> = output.split_whitespace().collect();
+
+    words
+        .windows(2)
+        .find(|pair| pair[1].trim_end_matches(')') == "bytes")
+        .and_then(|pair| pair[0].trim_start_matches('(').parse().ok())
+}
+
+fn trim_ssds() -> Result<Option<String>> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts")?;
+    let mut trimmed = 0u64;
+
+    for mountpoint in trimmable_mountpoints(&mounts, is_rotational) {
+        let output = system_command("fstrim").arg("-v").arg(&mountpoint).output()?;
+
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "{}: {}",
+                mountpoint.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        trimmed += parse_fstrim_bytes(&String::from_utf8_lossy(&output.stdout)).unwrap_or(0);
+    }
+
+    Ok(Some(format!("({} trimmed)", format_size(trimmed))))
+}
+
+/// Decide whether `swapoff -a && swapon -a` is both useful and safe
+///
+/// Useful when a meaningful share of swap is in use; safe only when the
+/// available RAM can take all of it and still keep some headroom, since
+/// `swapoff` otherwise pushes the system into the OOM killer.
+pub fn swap_reset_advisable(total_swap: u64, used_swap: u64, available_memory: u64) -> bool {
+    if total_swap == 0 || used_swap * 100 < total_swap * SWAP_RESET_MIN_PERCENT {
+        return false;
+    }
+
+    available_memory > used_swap.saturating_add(SWAP_RESET_HEADROOM)
+}
+
+fn reset_swap() -> Result<Option<String>> {
+    // Re-check right before acting; memory may have changed since planning
+    let before = SystemInfo::new();
+    if !swap_reset_advisable(before.total_swap(), before.used_swap(), before.available_memory()) {
+        return Err(anyhow::anyhow!(
+            "not enough free memory to absorb {} of swap",
+            format_size(before.used_swap())
+        ));
+    }
+
+    run_command("swapoff", &["-a"])?;
+    run_command("swapon", &["-a"])?;
+
+    let after = SystemInfo::new();
+    Ok(Some(format!(
+        "(swap {} → {})",
+        format_size(before.used_swap()),
+        format_size(after.used_swap())
+    )))
+}
+
+/// Find stale app logs under `root`, grouped by the app directory they're in
+///
+/// A file qualifies when its name matches one of `patterns` and it was last
+/// modified more than `max_age` ago. Groups are keyed by the first path
+/// component below `root` and sorted by name.
+pub fn find_app_logs(
+    root: &Path,
+    patterns: &[String],
+    max_age: Duration,
+) -> Vec<(String, Vec<PathBuf>)> {
+    let now = SystemTime::now();
+    let mut groups: Vec<(String, Vec<PathBuf>)> = Vec::new();
+
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        if !glob::matches_any(patterns, &entry.file_name().to_string_lossy()) {
+            continue;
+        }
+
+        let is_old = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| now.duration_since(t).ok())
+            .is_some_and(|age| age > max_age);
+        if !is_old {
+            continue;
+        }
+
+        let app = entry
+            .path()
+            .strip_prefix(root)
+            .ok()
+            .and_then(|rel| rel.components().next())
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        match groups.iter_mut().find(|(name, _)| *name == app) {
+            Some((_, files)) => files.push(entry.into_path()),
+            None => groups.push((app, vec![entry.into_path()])),
+        }
+    }
+
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+    groups
+}
+
+/// What the size-based optimize tasks would free, for `mo scan`
+///
+/// Covers the Flatpak app caches and stale app logs; the other tasks run
+/// package managers or tools whose savings can't be known up front.
+pub fn reclaimable_estimates() -> Vec<(String, u64)> {
+    let flatpak: u64 = CleanupPaths::new()
+        .flatpak_app_caches()
+        .iter()
+        .map(|(_, path)| dir_size(path).unwrap_or(0))
+        .sum();
+
+    let config = Config::load();
+    let max_age = Duration::from_secs(u64::from(config.app_log_max_age_days) * 86400);
+    let app_logs: u64 = dirs::home_dir()
+        .map(|home| {
+            [home.join(".config"), home.join(".local/state")]
+                .iter()
+                .flat_map(|root| find_app_logs(root, &config.app_log_patterns, max_age))
+                .flat_map(|(_, files)| files)
+                .filter_map(|file| file.symlink_metadata().ok())
+                .map(|meta| meta.len())
+                .sum()
+        })
+        .unwrap_or(0);
+
+    [("Flatpak app caches", flatpak), ("Stale app logs", app_logs)]
+        .into_iter()
+        .filter(|(_, size)| *size > 0)
+        .map(|(name, size)| (name.to_string(), size))
+        .collect()
+}
+
+fn clean_app_logs() -> Result<Option<String>> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+    let config = Config::load();
+    let max_age = Duration::from_secs(u64::from(config.app_log_max_age_days) * 86400);
+
+    let mut total = 0u64;
+    let mut lines = Vec::new();
+
+    for root in [home.join(".config"), home.join(".local/state")] {
+        for (app, files) in find_app_logs(&root, &config.app_log_patterns, max_age) {
+            let freed: u64 = files
+                .iter()
+                .filter_map(|file| safe_delete(file, false).ok())
+                .sum();
+
+            if freed > 0 {
+                total += freed;
+                lines.push(format!("      {} {}", format_size(freed), root.join(app).display()));
+            }
+        }
+    }
+
+    if lines.is_empty() {
+        return Ok(Some("(nothing to remove)".to_string()));
+    }
+
+    Ok(Some(format!("({} freed)\n{}", format_size(total), lines.join("\n"))))
+}
+
+/// Directories searched for abandoned sockets and lock files
+const STALE_FILE_DIRS: &[&str] = &["/tmp", "/var/tmp", "/tmp/.X11-unix", "/tmp/.ICE-unix"];
+
+/// Sockets and lock files younger than this are left alone even if they
+/// look abandoned; `/proc/net/unix` only lists this network namespace, so a
+/// sandboxed app's socket can look unbound
+const STALE_MIN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn stale_file_dirs() -> Vec<PathBuf> {
+    STALE_FILE_DIRS.iter().map(PathBuf::from).collect()
+}
+
+/// What running processes hold, read from `/proc`
+#[derive(Debug, Default)]
+pub struct ProcessUsage {
+    /// Paths of bound unix sockets
+    pub sockets: HashSet<PathBuf>,
+    /// Files some process has open
+    pub open_files: HashSet<PathBuf>,
+    pub pids: HashSet<u32>,
+}
+
+impl ProcessUsage {
+    pub fn read() -> Self {
+        let mut usage = Self {
+            sockets: parse_unix_sockets(
+                &std::fs::read_to_string("/proc/net/unix").unwrap_or_default(),
+            ),
+            ..Self::default()
+        };
+
+        let Ok(procs) = std::fs::read_dir("/proc") else {
+            return usage;
+        };
+
+        for proc in procs.flatten() {
+            let Some(pid) = proc.file_name().to_str().and_then(|n| n.parse().ok()) else {
+                continue;
+            };
+            usage.pids.insert(pid);
+
+            // Other users' fds are unreadable without root; their files are
+            // also not ours to remove
+            if let Ok(fds) = std::fs::read_dir(proc.path().join("fd")) {
+                usage
+                    .open_files
+                    .extend(fds.flatten().filter_map(|fd| std::fs::read_link(fd.path()).ok()));
+            }
+        }
+
+        usage
+    }
+}
+
+/// Socket paths bound by a live process, from `/proc/net/unix`
+///
+/// The path is the eighth column; abstract and unnamed sockets have none or
+/// start with `@`.
+pub fn parse_unix_sockets(proc_net_unix: &str) -> HashSet<PathBuf> {
+    proc_net_unix
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(7))
+        .filter(|path| path.starts_with('/'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// PID recorded in a lock or pid file (`.X0-lock` pads it with spaces)
+pub fn parse_lock_pid(content: &str) -> Option<u32> {
+    content.lines().next()?.trim().parse().ok()
+}
+
+fn is_lock_name(name: &str) -> bool {
+    name.ends_with(".lock") || name.ends_with("-lock") || name.ends_with(".pid")
+}
+
+/// Sockets nobody is bound to and lock files whose owner has exited
+///
+/// Only direct entries of `dirs` older than `min_age` are considered, and
+/// only the current user's unless running as root.
+pub fn find_stale_files(dirs: &[PathBuf], usage: &ProcessUsage, min_age: Duration) -> Vec<PathBuf> {
+    let me = unsafe { libc::geteuid() };
+    let mut stale = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            if (me != 0 && metadata.uid() != me) || is_recent(&metadata, min_age) {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_stale = if metadata.file_type().is_socket() {
+                !usage.sockets.contains(&path)
+            } else if metadata.is_file() && is_lock_name(&name) {
+                match std::fs::read_to_string(&path).ok().as_deref().and_then(parse_lock_pid) {
+                    Some(pid) => !usage.pids.contains(&pid),
+                    None => !usage.open_files.contains(&path),
+                }
+            } else {
+                false
+            };
+
+            if is_stale {
+                stale.push(path);
+            }
+        }
+    }
+
+    stale
+}
+
+fn is_recent(metadata: &std::fs::Metadata, window: Duration) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .is_none_or(|age| age < window)
+}
+
+fn remove_stale_files() -> Result<Option<String>> {
+    let removed = find_stale_files(&stale_file_dirs(), &ProcessUsage::read(), STALE_MIN_AGE)
+        .into_iter()
+        .filter(|path| safe_delete(path, false).is_ok())
+        .count();
+
+    Ok(Some(format!("({} removed)", removed)))
+}
+
+fn clear_flatpak_caches() -> Result<Option<String>> {
+    let mut total = 0u64;
+    let mut lines = Vec::new();
+
+    for (app, cache) in CleanupPaths::new().flatpak_app_caches() {
+        let freed = clean_directory(&cache, false).map(|r| r.freed).unwrap_or(0);
+
+        if freed > 0 {
+            total += freed;
+            lines.push(format!("      {} {}", format_size(freed), app));
+        }
+    }
+
+    if lines.is_empty() {
+        return Ok(Some("(nothing to remove)".to_string()));
+    }
+
+    Ok(Some(format!("({} freed)\n{}", format_size(total), lines.join("\n"))))
+}
+
+fn clear_thumbnails() -> Result<Option<String>> {
     let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
     let thumb_dir = home.join(".cache/thumbnails");
 
@@ -207,30 +1257,813 @@ fn clear_thumbnails() -> Result<()> {
         std::fs::create_dir_all(&thumb_dir)?;
     }
 
-    Ok(())
+    Ok(None)
+}
+
+/// Recently used files as tracked by GTK and KDE, relative to home
+const GTK_RECENT_FILE: &str = ".local/share/recently-used.xbel";
+const KDE_RECENT_DIR: &str = ".local/share/RecentDocuments";
+
+/// Remove the GTK recent files list and empty KDE's RecentDocuments
+///
+/// Returns how many GTK bookmarks and KDE entries were cleared.
+pub fn clear_recent_in(home: &Path) -> std::io::Result<(usize, usize)> {
+    let xbel = home.join(GTK_RECENT_FILE);
+    let gtk = match std::fs::read_to_string(&xbel) {
+        Ok(content) => {
+            std::fs::remove_file(&xbel)?;
+            content.matches("<bookmark ").count()
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+        Err(e) => return Err(e),
+    };
+
+    let mut kde = 0;
+    if let Ok(entries) = std::fs::read_dir(home.join(KDE_RECENT_DIR)) {
+        for entry in entries.flatten() {
+            if safe_delete(&entry.path(), false).is_ok() {
+                kde += 1;
+            }
+        }
+    }
+
+    Ok((gtk, kde))
+}
+
+/// How deep under ~/.local and ~/.config broken symlinks are looked for;
+/// enough for ~/.local/share/applications/*.desktop
+const BROKEN_SYMLINK_DEPTH: usize = 4;
+
+/// Mount roots for removable media; a link into one is only dangling
+/// while the drive is unplugged
+const REMOVABLE_MEDIA_ROOTS: &[&str] = &["/media", "/mnt", "/run/media"];
+
+/// Whether a dangling link is expected to dangle: a lock held by a running
+/// program (Chromium's `SingletonLock` points at `host-pid`, Firefox's
+/// `lock` at `ip:+pid`) or a link into unmounted removable media
+fn is_intentionally_dangling(link: &Path) -> bool {
+    let name = link
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if name.starts_with("singleton") || name == "lock" || name.ends_with(".lock") {
+        return true;
+    }
+
+    match std::fs::read_link(link) {
+        // Relative targets stay relative to the link's own directory
+        Ok(target) => {
+            let target = link.parent().unwrap_or(Path::new("/")).join(target);
+            REMOVABLE_MEDIA_ROOTS
+                .iter()
+                .any(|root| target.starts_with(root))
+        }
+        Err(_) => true,
+    }
 }
 
-fn clean_old_snaps() -> Result<()> {
+/// Broken symlinks in the user's dotfile dirs, leaving the trash, lock
+/// links and links into removable media alone
+pub fn broken_user_symlinks(home: &Path) -> Vec<PathBuf> {
+    let trash = home.join(".local/share/Trash");
+    [home.join(".local"), home.join(".config")]
+        .iter()
+        .flat_map(|root| find_broken_symlinks(root, BROKEN_SYMLINK_DEPTH))
+        .filter(|link| !link.starts_with(&trash) && !is_intentionally_dangling(link))
+        .collect()
+}
+
+fn remove_broken_symlinks() -> Result<Option<String>> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+
+    let mut removed = 0;
+    for link in broken_user_symlinks(&home) {
+        // Its target may have come back since the scan
+        if is_broken_symlink(&link) && std::fs::remove_file(&link).is_ok() {
+            tracing::debug!("Removed broken symlink {}", link.display());
+            removed += 1;
+        }
+    }
+
+    Ok(Some(match removed {
+        0 => "(none found)".to_string(),
+        1 => "(1 link)".to_string(),
+        n => format!("({} links)", n),
+    }))
+}
+
+fn clear_recent_documents() -> Result<Option<String>> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+
+    let note = match clear_recent_in(&home)? {
+        (0, 0) => "(nothing to clear)".to_string(),
+        (gtk, 0) => format!("({} recent files)", gtk),
+        (0, kde) => format!("({} KDE recent documents)", kde),
+        (gtk, kde) => format!("({} recent files, {} KDE recent documents)", gtk, kde),
+    };
+    Ok(Some(note))
+}
+
+/// Revisions snapd keeps per snap when `refresh.retain` is unset
+const SNAP_DEFAULT_RETAIN: u32 = 2;
+
+/// Revisions of one snap from `snap list --all`
+#[derive(Debug, PartialEq, Eq)]
+pub struct SnapRevisions {
+    pub name: String,
+    pub total: usize,
+    pub disabled: usize,
+}
+
+/// Count revisions per snap in `snap list --all` output
+pub fn parse_snap_revisions(list: &str) -> Vec<SnapRevisions> {
+    let mut snaps: Vec<SnapRevisions> = Vec::new();
+
+    for line in list.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some(name) = parts.first() else {
+            continue;
+        };
+        let disabled = parts.len() >= 6 && parts[5].split(',').any(|n| n == "disabled");
+
+        match snaps.iter_mut().find(|s| s.name == *name) {
+            Some(snap) => {
+                snap.total += 1;
+                snap.disabled += usize::from(disabled);
+            }
+            None => snaps.push(SnapRevisions {
+                name: name.to_string(),
+                total: 1,
+                disabled: usize::from(disabled),
+            }),
+        }
+    }
+
+    snaps
+}
+
+/// Parse `snap get system refresh.retain`; unset prints an error instead
+fn parse_refresh_retain(output: &str) -> Option<u32> {
+    output.trim().parse().ok()
+}
+
+/// Describe the disabled revisions and how many snapd is set to keep
+fn snap_revisions_description() -> String {
+    let retain = Command::new("snap")
+        .args(["get", "system", "refresh.retain"])
+        .output()
+        .ok()
+        .and_then(|o| parse_refresh_retain(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or(SNAP_DEFAULT_RETAIN);
+
+    let snaps = Command::new("snap")
+        .args(["list", "--all"])
+        .output()
+        .map(|o| parse_snap_revisions(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default();
+
+    let disabled: usize = snaps.iter().map(|s| s.disabled).sum();
+    let most = snaps
+        .iter()
+        .max_by_key(|s| s.total)
+        .map(|s| format!("; {} keeps the most ({})", s.name, s.total))
+        .unwrap_or_default();
+
+    format!(
+        "Remove {} disabled snap revisions (refresh.retain = {}{})",
+        disabled, retain, most
+    )
+}
+
+fn clean_old_snaps() -> Result<Option<String>> {
     // List disabled snaps and remove them
     let output = Command::new("snap")
         .args(["list", "--all"])
         .output()?;
 
     if !output.status.success() {
-        return Ok(());
+        return Ok(None);
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     for line in stdout.lines().skip(1) {
         let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 6 && parts[5] == "disabled" {
+        if parts.len() >= 6 && parts[5].split(',').any(|n| n == "disabled") {
             let name = parts[0];
             let revision = parts[2];
-            let _ = Command::new("sudo")
-                .args(["snap", "remove", name, "--revision", revision])
+            let _ = system_command("snap")
+                .args(["remove", name, "--revision", revision])
                 .output();
         }
     }
 
-    Ok(())
+    Ok(None)
+}
+
+/// Whether `/` is a btrfs filesystem, from `/proc/self/mounts` content
+pub fn root_is_btrfs(mounts: &str) -> bool {
+    mounts.lines().any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        fields.len() >= 3 && fields[1] == "/" && fields[2] == "btrfs"
+    })
+}
+
+/// Tool managing btrfs snapshots of the root filesystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotTool {
+    Snapper,
+    Timeshift,
+}
+
+impl SnapshotTool {
+    pub fn detect() -> Option<Self> {
+        if which("snapper") {
+            Some(Self::Snapper)
+        } else if which("timeshift") {
+            Some(Self::Timeshift)
+        } else {
+            None
+        }
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::Snapper => "snapper",
+            Self::Timeshift => "timeshift",
+        }
+    }
+
+    fn list_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Snapper => &["list"],
+            Self::Timeshift => &["--list"],
+        }
+    }
+
+    /// Parse the tool's listing, oldest snapshot first
+    pub fn parse_list(self, output: &str) -> Vec<BtrfsSnapshot> {
+        match self {
+            Self::Snapper => parse_snapper_list(output),
+            Self::Timeshift => parse_timeshift_list(output),
+        }
+    }
+
+    /// List snapshots, elevated when the running task is
+    fn list(self) -> Result<Vec<BtrfsSnapshot>> {
+        let output = system_command(self.program()).args(self.list_args()).output()?;
+        if !output.status.success() {
+            anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(self.parse_list(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn delete(self, snapshots: &[&BtrfsSnapshot]) -> Result<()> {
+        let ids: Vec<&str> = snapshots.iter().map(|s| s.id.as_str()).collect();
+        match self {
+            Self::Snapper => run_command("snapper", &[&["delete"], ids.as_slice()].concat()),
+            Self::Timeshift => ids.iter().try_for_each(|id| {
+                run_command("timeshift", &["--delete", "--snapshot", id, "--scripted"])
+            }),
+        }
+    }
+}
+
+/// A btrfs snapshot as listed by Snapper or Timeshift
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtrfsSnapshot {
+    /// Snapper number or Timeshift snapshot name
+    pub id: String,
+    /// Space only this snapshot holds, when the tool reports it
+    pub used: Option<u64>,
+    /// Taken by a schedule or a package hook rather than by hand; only
+    /// these are pruned
+    pub automatic: bool,
+    /// For a Snapper post snapshot, the pre snapshot it closes
+    pub pre: Option<String>,
+}
+
+/// Parse `snapper list`, skipping snapshot 0 (the live system)
+///
+/// Column order differs between snapper versions, so the columns are
+/// located from the header row. Only the `number` and `timeline` cleanup
+/// algorithms mark a snapshot as automatic.
+pub fn parse_snapper_list(output: &str) -> Vec<BtrfsSnapshot> {
+    let mut lines = output.lines();
+    let Some(header) = lines.find(|l| l.contains('|')) else {
+        return Vec::new();
+    };
+    let columns: Vec<&str> = header.split('|').map(str::trim).collect();
+    let Some(id_col) = columns.iter().position(|c| *c == "#") else {
+        return Vec::new();
+    };
+    let column = |name: &str| columns.iter().position(|c| *c == name);
+    let (used_col, cleanup_col, pre_col) = (column("Used Space"), column("Cleanup"), column("Pre #"));
+
+    lines
+        .filter(|l| !l.starts_with('-'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+            let id = fields.get(id_col)?;
+            if id.is_empty() || *id == "0" {
+                return None;
+            }
+            let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).copied().unwrap_or("");
+            Some(BtrfsSnapshot {
+                id: id.to_string(),
+                used: parse_size(field(used_col)).ok(),
+                automatic: matches!(field(cleanup_col), "number" | "timeline"),
+                pre: Some(field(pre_col)).filter(|pre| !pre.is_empty()).map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Parse `timeshift --list`; snapshots are named by their creation time
+///
+/// The tags after the name say which schedules (`B`oot, `H`ourly, `D`aily,
+/// `W`eekly, `M`onthly) hold a snapshot; `O`n-demand ones were taken by
+/// hand and are not automatic.
+pub fn parse_timeshift_list(output: &str) -> Vec<BtrfsSnapshot> {
+    output
+        .lines()
+        .skip_while(|l| !l.starts_with("---"))
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace().skip_while(|token| {
+                let bytes = token.as_bytes();
+                !(bytes.len() == 19 && bytes[4] == b'-' && bytes[10] == b'_')
+            });
+            let name = tokens.next()?;
+            let tags = tokens
+                .next()
+                .filter(|tags| tags.chars().all(|c| "BHDWMO".contains(c)))
+                .unwrap_or("");
+            Some(BtrfsSnapshot {
+                id: name.to_string(),
+                used: None,
+                automatic: !tags.is_empty() && !tags.contains('O'),
+                pre: None,
+            })
+        })
+        .collect()
+}
+
+/// The oldest automatic snapshots beyond the newest `keep`
+///
+/// A Snapper pre/post pair counts as one snapshot and is removed or kept
+/// as a whole; a pair with a hand-made half is kept.
+pub fn snapshots_to_remove(snapshots: &[BtrfsSnapshot], keep: usize) -> Vec<&BtrfsSnapshot> {
+    let mut units: Vec<Vec<&BtrfsSnapshot>> = Vec::new();
+    for snapshot in snapshots {
+        let pair = snapshot
+            .pre
+            .as_ref()
+            .and_then(|pre| units.iter_mut().find(|unit| unit[0].id == *pre));
+        match pair {
+            Some(unit) => unit.push(snapshot),
+            None => units.push(vec![snapshot]),
+        }
+    }
+
+    let prunable: Vec<_> = units
+        .into_iter()
+        .filter(|unit| unit.iter().all(|s| s.automatic))
+        .collect();
+    let old = prunable.len().saturating_sub(keep);
+    prunable.into_iter().take(old).flatten().collect()
+}
+
+/// Space the given snapshots hold, if the tool reported any of it
+fn snapshots_used(snapshots: &[&BtrfsSnapshot]) -> Option<u64> {
+    snapshots
+        .iter()
+        .filter_map(|s| s.used)
+        .reduce(|a, b| a + b)
+}
+
+fn btrfs_snapshots_description(tool: SnapshotTool) -> String {
+    let keep = Config::load().btrfs_snapshot_keep as usize;
+
+    // Listing usually needs root; without it just name the policy
+    match tool.list() {
+        Ok(snapshots) => {
+            let old = snapshots_to_remove(&snapshots, keep);
+            let space = snapshots_used(&old)
+                .map(|bytes| format!(", ~{}", format_size(bytes)))
+                .unwrap_or_default();
+            format!(
+                "{} {} snapshots; delete the {} beyond the newest {} automatic{}",
+                snapshots.len(),
+                tool.program(),
+                old.len(),
+                keep,
+                space
+            )
+        }
+        Err(_) => format!("Keep the newest {} automatic {} snapshots", keep, tool.program()),
+    }
+}
+
+fn remove_old_snapshots() -> Result<Option<String>> {
+    let tool = SnapshotTool::detect().ok_or_else(|| anyhow::anyhow!("no snapshot tool found"))?;
+    let keep = Config::load().btrfs_snapshot_keep as usize;
+
+    let snapshots = tool.list()?;
+    let old = snapshots_to_remove(&snapshots, keep);
+    if old.is_empty() {
+        return Ok(Some(format!("({} snapshots, nothing to remove)", snapshots.len())));
+    }
+
+    tool.delete(&old)?;
+    let space = snapshots_used(&old)
+        .map(|bytes| format!(", ~{}", format_size(bytes)))
+        .unwrap_or_default();
+    Ok(Some(format!("({} of {} removed{})", old.len(), snapshots.len(), space)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_is_btrfs() {
+        let btrfs = "/dev/nvme0n1p2 / btrfs rw,subvol=/@ 0 0\n/dev/nvme0n1p2 /home btrfs rw 0 0\n";
+        let ext4 = "/dev/sda1 / ext4 rw 0 0\n/dev/sdb1 /data btrfs rw 0 0\n";
+
+        assert!(root_is_btrfs(btrfs));
+        assert!(!root_is_btrfs(ext4));
+    }
+
+    #[test]
+    fn test_parse_snapper_list() {
+        let output = "\
+ # | Type   | Pre # | Date                     | User | Used Space | Cleanup | Description
+---+--------+-------+--------------------------+------+------------+---------+------------
+0  | single |       |                          | root |            |         | current
+1  | single |       | Mon 01 Jan 2024 10:00:00 | root |   1.50 GiB | number  | first
+2  | pre    |       | Tue 02 Jan 2024 10:00:00 | root |  16.00 KiB | number  | apt
+3  | post   |     2 | Tue 02 Jan 2024 10:01:00 | root |            | number  | apt
+4  | single |       | Wed 03 Jan 2024 10:00:00 | root |   2.00 MiB |         | before resize
+";
+        let snapshots = parse_snapper_list(output);
+
+        assert_eq!(snapshots.len(), 4);
+        assert_eq!(snapshots[0].id, "1");
+        assert_eq!(snapshots[0].used, Some(1536 * 1024 * 1024));
+        assert_eq!(snapshots[1].used, Some(16 * 1024));
+        assert_eq!(snapshots[2].used, None);
+        assert_eq!(snapshots[2].pre.as_deref(), Some("2"));
+        assert!(snapshots[..3].iter().all(|s| s.automatic));
+        assert!(!snapshots[3].automatic);
+
+        // Older snapper: type first, no used space column
+        let old = "Type   | # | Pre # | Date | User | Cleanup | Description\n\
+-------+---+-------+------+------+---------+------------\n\
+single | 0 |       |      | root |         | current\n\
+single | 7 |       | Mon  | root | number  | boot\n";
+        assert_eq!(
+            parse_snapper_list(old),
+            vec![BtrfsSnapshot {
+                id: "7".to_string(),
+                used: None,
+                automatic: true,
+                pre: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_timeshift_list() {
+        let output = "\
+Device : /dev/sda2
+Mode   : BTRFS
+Status : OK
+2 snapshots, 40.1 GB free
+
+Num     Name                 Tags  Description
+------------------------------------------------------------------------------
+0    >  2024-01-01_10-00-01  D
+1    >  2024-01-02_10-00-01  O     before upgrade
+2    >  2024-01-03_10-00-01  DW
+";
+        let snapshots = parse_timeshift_list(output);
+        let ids: Vec<_> = snapshots.iter().map(|s| s.id.as_str()).collect();
+        let automatic: Vec<_> = snapshots.iter().map(|s| s.automatic).collect();
+
+        assert_eq!(ids, ["2024-01-01_10-00-01", "2024-01-02_10-00-01", "2024-01-03_10-00-01"]);
+        assert_eq!(automatic, [true, false, true]);
+    }
+
+    #[test]
+    fn test_snapshots_to_remove() {
+        let snapshot = |id: u32, automatic: bool, pre: Option<u32>| BtrfsSnapshot {
+            id: id.to_string(),
+            used: None,
+            automatic,
+            pre: pre.map(|pre| pre.to_string()),
+        };
+        let ids = |old: Vec<&BtrfsSnapshot>| old.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+
+        let snapshots: Vec<_> = (1..=7).map(|n| snapshot(n, true, None)).collect();
+        assert_eq!(ids(snapshots_to_remove(&snapshots, 5)), ["1", "2"]);
+        assert!(snapshots_to_remove(&snapshots, 10).is_empty());
+
+        // Hand-made snapshots neither go nor use up the quota; a pre/post
+        // pair goes or stays together
+        let snapshots = vec![
+            snapshot(1, false, None),
+            snapshot(2, true, None),
+            snapshot(3, true, None),
+            snapshot(4, true, Some(3)),
+            snapshot(5, true, None),
+            snapshot(6, true, None),
+            snapshot(7, true, Some(6)),
+        ];
+        assert_eq!(ids(snapshots_to_remove(&snapshots, 3)), ["2"]);
+        assert_eq!(ids(snapshots_to_remove(&snapshots, 2)), ["2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_parse_rdepends_orphans() {
+        let output = "\
+libfoo1
+Reverse Depends:
+  foo-utils
+  libbar2
+libunused3
+Reverse Depends:
+libalso-unused0
+Reverse Depends:
+";
+        assert_eq!(parse_rdepends_orphans(output), ["libunused3", "libalso-unused0"]);
+        assert!(parse_rdepends_orphans("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_library_sections() {
+        let output = "\
+libssl3\tlibs
+libreoffice\teditors
+libvirt-daemon-system\tadmin
+librecad\tuniverse/graphics
+libfoo0\tuniverse/oldlibs
+zlib1g\tlibs
+libgtk-3-dev\tlibdevel
+";
+        assert_eq!(parse_library_sections(output), ["libssl3", "libfoo0", "zlib1g"]);
+        assert!(parse_library_sections("").is_empty());
+    }
+
+    #[test]
+    fn test_task_selection() {
+        let known = ["Clear thumbnail cache", "Vacuum journal logs", "TRIM SSD filesystems"];
+
+        let only = TaskSelection {
+            only: vec!["vacuum-journal-logs".to_string()],
+            skip: Vec::new(),
+        };
+        assert!(only.check_known(known.iter().copied()).is_ok());
+        assert!(only.wants("Vacuum journal logs"));
+        assert!(!only.wants("Clear thumbnail cache"));
+
+        let skip = TaskSelection {
+            only: Vec::new(),
+            skip: vec!["trim ssd filesystems".to_string()],
+        };
+        assert!(skip.wants("Clear thumbnail cache"));
+        assert!(!skip.wants("TRIM SSD filesystems"));
+
+        let unknown = TaskSelection {
+            only: vec!["Purge old kernels".to_string()],
+            skip: Vec::new(),
+        };
+        let err = unknown.check_known(known.iter().copied()).unwrap_err().to_string();
+        assert!(err.contains("Purge old kernels"));
+        assert!(err.contains("\"Vacuum journal logs\""));
+    }
+
+    #[test]
+    fn test_summary_rows() {
+        let outcomes = vec![
+            TaskOutcome {
+                name: "Clear thumbnail cache".to_string(),
+                ok: true,
+                reclaimed: Some(2048),
+            },
+            TaskOutcome {
+                name: "Update font cache".to_string(),
+                ok: true,
+                reclaimed: None,
+            },
+            TaskOutcome {
+                name: "Vacuum journal logs".to_string(),
+                ok: false,
+                reclaimed: Some(0),
+            },
+        ];
+
+        let (rows, total) = summary_rows(&outcomes);
+
+        assert_eq!(rows[0], ["Clear thumbnail cache", "done", "2 KiB"]);
+        assert_eq!(rows[1][2], "-");
+        assert_eq!(rows[2][1], "failed");
+        assert_eq!(total, 2048);
+    }
+
+    #[test]
+    fn test_broken_user_symlinks() {
+        let home = tempfile::TempDir::new().unwrap();
+        let apps = home.path().join(".local/share/applications");
+        let trashed = home.path().join(".local/share/Trash/files");
+        let autostart = home.path().join(".config/autostart");
+        for dir in [&apps, &trashed, &autostart] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::os::unix::fs::symlink("/nonexistent/app.desktop", apps.join("app.desktop")).unwrap();
+        std::os::unix::fs::symlink("/nonexistent/old", trashed.join("old")).unwrap();
+        std::os::unix::fs::symlink(&apps, autostart.join("apps")).unwrap();
+
+        // Live lock links and links into unplugged media are left alone
+        let profile = home.path().join(".config/chromium");
+        std::fs::create_dir_all(&profile).unwrap();
+        std::os::unix::fs::symlink("myhost-4242", profile.join("SingletonLock")).unwrap();
+        std::os::unix::fs::symlink("myhost-4242", profile.join("SingletonCookie")).unwrap();
+        std::os::unix::fs::symlink("10.0.0.2:+4242", profile.join("lock")).unwrap();
+        std::os::unix::fs::symlink("/run/media/u/usb/music", autostart.join("music")).unwrap();
+        std::os::unix::fs::symlink("/mnt/backup/notes", autostart.join("notes")).unwrap();
+
+        assert_eq!(broken_user_symlinks(home.path()), [apps.join("app.desktop")]);
+    }
+
+    #[test]
+    fn test_clear_recent_in() {
+        let home = tempfile::TempDir::new().unwrap();
+        assert_eq!(clear_recent_in(home.path()).unwrap(), (0, 0));
+
+        let share = home.path().join(".local/share");
+        std::fs::create_dir_all(share.join("RecentDocuments")).unwrap();
+        std::fs::write(
+            share.join("recently-used.xbel"),
+            "<xbel>\n<bookmark href=\"file:///a\"/>\n<bookmark href=\"file:///b\"/>\n</xbel>",
+        )
+        .unwrap();
+        std::fs::write(share.join("RecentDocuments/a.desktop"), "").unwrap();
+
+        assert_eq!(clear_recent_in(home.path()).unwrap(), (2, 1));
+        assert!(!share.join("recently-used.xbel").exists());
+        assert!(share.join("RecentDocuments").exists());
+        assert_eq!(std::fs::read_dir(share.join("RecentDocuments")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_parse_unix_sockets() {
+        let proc_net_unix = "\
+Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 23551 /tmp/.X11-unix/X0
+0000000000000000: 00000002 00000000 00010000 0001 01 30123 @/tmp/.ICE-unix/1234
+0000000000000000: 00000003 00000000 00000000 0001 03 40001
+";
+        let sockets = parse_unix_sockets(proc_net_unix);
+
+        assert_eq!(sockets.len(), 1);
+        assert!(sockets.contains(Path::new("/tmp/.X11-unix/X0")));
+    }
+
+    #[test]
+    fn test_parse_lock_pid() {
+        assert_eq!(parse_lock_pid("      1234\n"), Some(1234));
+        assert_eq!(parse_lock_pid("locked by editor"), None);
+        assert_eq!(parse_lock_pid(""), None);
+    }
+
+    #[test]
+    fn test_find_stale_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dirs = [temp.path().to_path_buf()];
+        let bound = temp.path().join("bound.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&bound).unwrap();
+        let orphan = temp.path().join("orphan.sock");
+        drop(std::os::unix::net::UnixListener::bind(&orphan).unwrap());
+        std::fs::write(temp.path().join(".X9-lock"), "  4000000\n").unwrap();
+        std::fs::write(temp.path().join("live.pid"), std::process::id().to_string()).unwrap();
+        std::fs::write(temp.path().join("notes.txt"), "").unwrap();
+
+        let usage = ProcessUsage {
+            sockets: HashSet::from([bound]),
+            pids: HashSet::from([std::process::id()]),
+            ..ProcessUsage::default()
+        };
+
+        let mut stale = find_stale_files(&dirs, &usage, Duration::ZERO);
+        stale.sort();
+        assert_eq!(stale, vec![temp.path().join(".X9-lock"), orphan]);
+
+        // Everything was just created, so nothing is old enough yet
+        assert!(find_stale_files(&dirs, &usage, STALE_MIN_AGE).is_empty());
+    }
+
+    #[test]
+    fn test_is_transient_failure() {
+        assert!(is_transient_failure("error: Unable to connect: Connection timed out"));
+        assert!(is_transient_failure("Temporary failure resolving 'archive.ubuntu.com'"));
+        assert!(is_transient_failure("E: Could not get lock /var/lib/dpkg/lock-frontend"));
+        assert!(!is_transient_failure("error: No such ref 'org.gnome.Foo' in remote flathub"));
+        assert!(!is_transient_failure("Permission denied"));
+    }
+
+    const MOUNTS: &str = "\
+/dev/nvme0n1p2 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid 0 0
+/dev/nvme0n1p3 /home/my\\040files btrfs rw,subvol=/home 0 0
+/dev/nvme0n1p3 /snapshots btrfs rw,subvol=/snap 0 0
+/dev/sda1 /data ext4 rw 0 0
+server:/export /mnt/nfs nfs4 rw 0 0
+/dev/loop0 /snap/core/1 squashfs ro 0 0
+";
+
+    #[test]
+    fn test_parse_snap_revisions() {
+        let list = "\
+Name      Version    Rev    Tracking       Publisher   Notes
+core22    20240111   1122   latest/stable  canonical✓  base,disabled
+core22    20240408   1380   latest/stable  canonical✓  base
+firefox   124.0      4090   latest/stable  mozilla✓    disabled
+firefox   125.0      4173   latest/stable  mozilla✓    -
+snapd     2.62       21465  latest/stable  canonical✓  snapd
+";
+
+        let snaps = parse_snap_revisions(list);
+
+        assert_eq!(snaps.len(), 3);
+        assert_eq!(
+            snaps[0],
+            SnapRevisions { name: "core22".to_string(), total: 2, disabled: 1 }
+        );
+        assert_eq!(snaps[1].disabled, 1);
+        assert_eq!(snaps[2].disabled, 0);
+        assert_eq!(parse_refresh_retain("3\n"), Some(3));
+        assert_eq!(parse_refresh_retain(""), None);
+    }
+
+    #[test]
+    fn test_trimmable_mountpoints_skips_rotational_and_network() {
+        let mounts = trimmable_mountpoints(MOUNTS, |device| {
+            Some(device.to_string_lossy().starts_with("/dev/sd"))
+        });
+
+        assert_eq!(
+            mounts,
+            vec![PathBuf::from("/"), PathBuf::from("/home/my files")]
+        );
+    }
+
+    #[test]
+    fn test_trimmable_mountpoints_skips_unknown_devices() {
+        assert!(trimmable_mountpoints(MOUNTS, |_| None).is_empty());
+    }
+
+    #[test]
+    fn test_swap_reset_advisable() {
+        const GIB: u64 = 1024 * 1024 * 1024;
+
+        // Plenty of RAM, swap mostly used
+        assert!(swap_reset_advisable(4 * GIB, 2 * GIB, 8 * GIB));
+        // Swap barely used
+        assert!(!swap_reset_advisable(4 * GIB, GIB / 2, 8 * GIB));
+        // Not enough RAM to take it all back
+        assert!(!swap_reset_advisable(4 * GIB, 3 * GIB, 3 * GIB));
+        // No swap at all
+        assert!(!swap_reset_advisable(0, 0, 8 * GIB));
+    }
+
+    #[test]
+    fn test_find_app_logs_groups_old_matches() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let old = SystemTime::now() - Duration::from_secs(30 * 86400);
+        let patterns = vec!["*.log".to_string(), "*.log.*".to_string()];
+
+        for rel in ["Code/logs/main.log", "Code/logs/main.log.1", "Slack/app.log", "Slack/settings.json"] {
+            let path = temp.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::File::create(&path).unwrap().set_modified(old).unwrap();
+        }
+        std::fs::write(temp.path().join("Slack/fresh.log"), "x").unwrap();
+
+        let groups = find_app_logs(temp.path(), &patterns, Duration::from_secs(7 * 86400));
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "Code");
+        assert_eq!(groups[0].1.len(), 2);
+        assert_eq!(groups[1].0, "Slack");
+        assert_eq!(groups[1].1, vec![temp.path().join("Slack/app.log")]);
+    }
+
+    #[test]
+    fn test_parse_fstrim_bytes() {
+        assert_eq!(
+            parse_fstrim_bytes("/: 1.2 GiB (1288490188 bytes) trimmed on /dev/nvme0n1p2\n"),
+            Some(1288490188)
+        );
+        assert_eq!(parse_fstrim_bytes("/home: 4096 bytes were trimmed\n"), Some(4096));
+        assert_eq!(parse_fstrim_bytes("fstrim: /: the discard operation is not supported"), None);
+    }
 }
@@ -2,12 +2,29 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use std::path::PathBuf;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
 use crate::core::config::Config;
-use crate::core::filesystem::{format_size, safe_delete};
+use crate::core::filesystem::{
+    begin_scan, count_files, dir_size_dedup, format_size, safe_delete, scan_expired, SkippedEntry,
+};
+use crate::core::backup::backup_before_delete;
+use crate::core::output::{
+    confirm_deletion, mark_nothing_to_do, ItemConfirmer, print_skipped,
+    print_verification, warn_if_timed_out, warn_interrupted, warn_no_recent_protection,
+};
+use crate::core::ignore::IgnoreFile;
+use crate::core::interrupt::{install_handler, interrupted};
 use crate::core::paths::DevArtifacts;
+use crate::core::report::{Report, ReportItem};
+use crate::core::security::{ConfirmPolicy, PathValidation, SecurityValidator};
+use crate::core::system::FreeSpaceSnapshot;
+use crate::core::time::age_days;
+use crate::{outln, warnln};
 
 /// Found artifact with metadata
 #[derive(Debug)]
@@ -18,113 +35,286 @@ pub struct FoundArtifact {
     pub size: u64,
     pub age_days: u64,
     pub selected: bool,
+    /// A package cache shared by all projects rather than one project's output
+    pub shared: bool,
 }
 
-/// Scan for development artifacts
-pub fn scan_artifacts(paths: &[PathBuf]) -> Vec<FoundArtifact> {
+/// How shared caches are labelled in the listing
+const SHARED_LABEL: &str = "shared — affects all projects";
+
+/// Artifact directory found by the walk, before it has been sized
+#[derive(Debug)]
+struct Candidate {
+    project_name: String,
+    artifact_type: String,
+    path: PathBuf,
+}
+
+/// How many directory levels below each scan path are searched by default
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// Scan for development artifacts up to `max_depth` levels below `paths`
+///
+/// The directory walk itself is cheap; sizing each artifact is a full
+/// subtree walk, so that part runs in parallel.
+pub fn scan_artifacts(paths: &[PathBuf], max_depth: usize) -> Vec<FoundArtifact> {
+    let _scan = begin_scan();
+    let mut artifacts: Vec<FoundArtifact> = find_candidates(paths, max_depth)
+        .into_par_iter()
+        .map(measure_candidate)
+        .collect();
+
+    // Sort by size descending
+    artifacts.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    artifacts
+}
+
+/// Walk `paths` for directories matching an artifact pattern
+///
+/// Artifacts matched by a `.moleignore` in their project or any directory
+/// above it, up to the scan path, are left out.
+fn find_candidates(paths: &[PathBuf], max_depth: usize) -> Vec<Candidate> {
     let patterns = DevArtifacts::new();
-    let mut artifacts = Vec::new();
+    let mut candidates = Vec::new();
+    let mut ignore_files: HashMap<PathBuf, Option<IgnoreFile>> = HashMap::new();
 
     for scan_path in paths {
         if !scan_path.exists() {
             continue;
         }
 
-        for entry in WalkDir::new(scan_path)
-            .max_depth(4)
+        let mut walker = WalkDir::new(scan_path)
+            .max_depth(max_depth)
             .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+            .into_iter();
+
+        while let Some(entry) = walker.next() {
+            if scan_expired() {
+                break;
+            }
+
+            let Ok(entry) = entry else {
+                continue;
+            };
+
             if !entry.file_type().is_dir() {
                 continue;
             }
 
+            let Some(parent) = entry.path().parent() else {
+                continue;
+            };
+
             let dir_name = entry.file_name().to_string_lossy();
 
-            for pattern in &patterns.patterns {
-                if dir_name == pattern.dir_name {
-                    // Check if parent has marker file
-                    if let Some(parent) = entry.path().parent() {
-                        let has_marker = pattern.marker_files.is_empty()
-                            || pattern
-                                .marker_files
-                                .iter()
-                                .any(|m| parent.join(m).exists());
-
-                        if has_marker {
-                            let size = calculate_size(entry.path());
-                            let age = calculate_age(entry.path());
-
-                            let project_name = parent
-                                .file_name()
-                                .map(|n| n.to_string_lossy().to_string())
-                                .unwrap_or_else(|| "unknown".to_string());
-
-                            artifacts.push(FoundArtifact {
-                                project_name,
-                                artifact_type: pattern.name.to_string(),
-                                path: entry.path().to_path_buf(),
-                                size,
-                                age_days: age,
-                                selected: age > 7, // Select old artifacts by default
-                            });
-                        }
-                    }
+            // Check if parent has marker file
+            let matched = patterns.patterns.iter().find(|pattern| {
+                dir_name == pattern.dir_name
+                    && (pattern.marker_files.is_empty()
+                        || pattern
+                            .marker_files
+                            .iter()
+                            .any(|m| parent.join(m).exists()))
+            });
+
+            if let Some(pattern) = matched {
+                if is_moleignored(scan_path, entry.path(), &mut ignore_files) {
+                    walker.skip_current_dir();
+                    continue;
                 }
+
+                let project_name = parent
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                candidates.push(Candidate {
+                    project_name,
+                    artifact_type: pattern.name.to_string(),
+                    path: entry.path().to_path_buf(),
+                });
+
+                // Nested artifacts (node_modules/x/node_modules) are already
+                // part of this one; don't report or count them twice
+                walker.skip_current_dir();
             }
         }
     }
 
-    // Sort by size descending
-    artifacts.sort_by(|a, b| b.size.cmp(&a.size));
+    candidates
+}
 
-    artifacts
+/// Whether a `.moleignore` between `scan_path` and `artifact` excludes it
+fn is_moleignored(
+    scan_path: &Path,
+    artifact: &Path,
+    ignore_files: &mut HashMap<PathBuf, Option<IgnoreFile>>,
+) -> bool {
+    artifact
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(scan_path))
+        .any(|dir| {
+            let file = ignore_files
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| IgnoreFile::load(dir));
+            match (file, artifact.strip_prefix(dir)) {
+                (Some(file), Ok(relative)) => file.is_ignored(relative, true),
+                _ => false,
+            }
+        })
+}
+
+fn measure_candidate(candidate: Candidate) -> FoundArtifact {
+    let size = calculate_size(&candidate.path);
+    let age = calculate_age(&candidate.path);
+
+    FoundArtifact {
+        project_name: candidate.project_name,
+        artifact_type: candidate.artifact_type,
+        path: candidate.path,
+        size,
+        age_days: age,
+        selected: age > 7, // Select old artifacts by default
+        shared: false,
+    }
+}
+
+/// Size the global package caches that exist under `home`
+///
+/// They were asked for explicitly, so they start out selected.
+pub fn scan_global_caches(home: &std::path::Path) -> Vec<FoundArtifact> {
+    DevArtifacts::global_caches(home)
+        .into_par_iter()
+        .filter(|(_, path)| path.is_dir())
+        .map(|(name, path)| FoundArtifact {
+            project_name: name.to_string(),
+            artifact_type: SHARED_LABEL.to_string(),
+            size: calculate_size(&path),
+            age_days: calculate_age(&path),
+            path,
+            selected: true,
+            shared: true,
+        })
+        .filter(|cache| cache.size > 0)
+        .collect()
 }
 
+/// Artifact size with hardlinks counted once, since package managers like
+/// pnpm hardlink `node_modules` contents from a shared store
 fn calculate_size(path: &std::path::Path) -> u64 {
-    WalkDir::new(path)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.metadata().map(|m| m.len()).unwrap_or(0))
-        .sum()
+    dir_size_dedup(path).unwrap_or(0)
 }
 
 fn calculate_age(path: &std::path::Path) -> u64 {
     path.metadata()
         .and_then(|m| m.modified())
-        .ok()
-        .and_then(|t| t.elapsed().ok())
-        .map(|d| d.as_secs() / 86400)
+        .map(|modified| age_days(modified, SystemTime::now()))
         .unwrap_or(0)
 }
 
+fn build_report(artifacts: &[FoundArtifact], dry_run: bool) -> Report {
+    let items = artifacts
+        .iter()
+        .map(|a| ReportItem {
+            name: a.project_name.clone(),
+            kind: Some(a.artifact_type.clone()),
+            path: Some(a.path.clone()),
+            size: a.size,
+            selected: a.selected,
+            requires_sudo: false,
+        })
+        .collect();
+
+    Report::new("purge", dry_run, items)
+}
+
+/// Options for the purge command
+#[derive(Debug, Clone)]
+pub struct PurgeOptions {
+    /// Directories to scan instead of the configured project paths
+    pub paths: Option<Vec<PathBuf>>,
+    /// Directory levels searched below each path
+    pub max_depth: usize,
+    /// Preview without deleting
+    pub dry_run: bool,
+    /// Compare the freed total with the disk's free space before and after
+    pub verify: bool,
+    /// Archive the artifacts into this directory before deleting them
+    pub backup: Option<PathBuf>,
+    /// Also list package caches shared by all projects
+    pub include_global_caches: bool,
+    /// Select recently built artifacts too
+    pub no_recent_protection: bool,
+    /// Ask before deleting each selected artifact
+    pub confirm_each: bool,
+    /// Which conditions ask for confirmation before deleting
+    pub confirm_policy: ConfirmPolicy,
+    /// Write the plan to this file
+    pub report: Option<PathBuf>,
+}
+
 /// Run the purge command
-pub fn run(paths: Option<Vec<PathBuf>>, dry_run: bool) -> Result<()> {
-    println!("{}", "Mole-RS Project Purge".bold().cyan());
-    println!("{}", "═".repeat(60));
-    println!();
+pub fn run(options: PurgeOptions) -> Result<()> {
+    let PurgeOptions {
+        paths,
+        max_depth,
+        dry_run,
+        verify,
+        backup,
+        include_global_caches,
+        no_recent_protection,
+        confirm_each,
+        confirm_policy,
+        report,
+    } = options;
+    let mut confirmer = confirm_each.then(ItemConfirmer::new).transpose()?;
+
+    outln!("{}", "Mole-RS Project Purge".bold().cyan());
+    outln!("{}", "═".repeat(60));
+    outln!();
 
     let config = Config::load();
     let scan_paths = paths.unwrap_or(config.project_paths);
 
-    println!("{}", "Scanning for development artifacts...".dimmed());
-    println!();
+    outln!("{}", "Scanning for development artifacts...".dimmed());
+    outln!();
+
+    let mut artifacts = scan_artifacts(&scan_paths, max_depth);
+    if include_global_caches {
+        if let Some(home) = dirs::home_dir() {
+            let mut caches = scan_global_caches(&home);
+            caches.sort_by(|a, b| b.size.cmp(&a.size));
+            artifacts.extend(caches);
+        }
+    }
+    warn_if_timed_out();
+
+    if no_recent_protection {
+        warn_no_recent_protection();
+        for artifact in &mut artifacts {
+            artifact.selected = true;
+        }
+    }
 
-    let artifacts = scan_artifacts(&scan_paths);
+    if let Some(report_path) = &report {
+        build_report(&artifacts, dry_run).write(report_path)?;
+        outln!("Report written to {}", report_path.display().to_string().yellow());
+        outln!();
+    }
 
     if artifacts.is_empty() {
-        println!("{}", "No development artifacts found.".yellow());
+        outln!("{}", "No development artifacts found.".yellow());
+        mark_nothing_to_do();
         return Ok(());
     }
 
     let total_size: u64 = artifacts.iter().filter(|a| a.selected).map(|a| a.size).sum();
     let selected_count = artifacts.iter().filter(|a| a.selected).count();
 
-    println!("{}", "Found artifacts:".bold());
-    println!();
+    outln!("{}", "Found artifacts:".bold());
+    outln!();
 
     for artifact in &artifacts {
         let marker = if artifact.selected { "●" } else { "○" };
@@ -148,52 +338,240 @@ pub fn run(paths: Option<Vec<PathBuf>>, dry_run: bool) -> Result<()> {
             age_str.dimmed()
         };
 
-        println!(
+        let kind = if artifact.shared {
+            artifact.artifact_type.red()
+        } else {
+            artifact.artifact_type.dimmed()
+        };
+
+        outln!(
             " {} {:<20} {:>10} | {} | {}",
             marker_color,
             artifact.project_name.bold(),
             format_size(artifact.size).yellow(),
-            artifact.artifact_type.dimmed(),
+            kind,
             age_colored
         );
     }
 
-    println!();
-    println!(
+    outln!();
+    outln!(
         "Selected: {} artifacts, {}",
         selected_count.to_string().bold(),
         format_size(total_size).green().bold()
     );
-    println!();
+    outln!();
 
     if dry_run {
-        println!("{}", "[DRY RUN] No files were deleted.".yellow().bold());
+        outln!("{}", "[DRY RUN] No files were deleted.".yellow().bold());
+        outln!("{}", "Run again with --apply to delete them.".dimmed());
         return Ok(());
     }
 
+    if let Some(confirmer) = &mut confirmer {
+        for artifact in artifacts.iter_mut().filter(|a| a.selected) {
+            artifact.selected = confirmer.approve(&artifact.path.display().to_string(), artifact.size);
+        }
+        if !artifacts.iter().any(|a| a.selected) {
+            outln!("{}", "Nothing approved, nothing was deleted.".yellow());
+            return Ok(());
+        }
+    }
+
+    delete_artifacts(&artifacts, verify, backup.as_deref(), confirm_policy)
+}
+
+/// Delete the selected `artifacts`, after confirming large deletions
+///
+/// With `verify`, the freed total is checked against the disk's free space;
+/// with `backup`, the artifacts are archived into that directory first.
+pub fn delete_artifacts(
+    artifacts: &[FoundArtifact],
+    verify: bool,
+    backup: Option<&Path>,
+    confirm_policy: ConfirmPolicy,
+) -> Result<()> {
+    let total_size: u64 = artifacts.iter().filter(|a| a.selected).map(|a| a.size).sum();
+
+    let targets: Vec<_> = artifacts.iter().filter(|a| a.selected).map(|a| &a.path).collect();
+    let file_count = || targets.iter().map(|path| count_files(path) as u64).sum();
+    if !confirm_deletion(confirm_policy, &targets, total_size, file_count) {
+        outln!("{}", "Aborted, nothing was deleted.".yellow());
+        return Ok(());
+    }
+
+    if let Some(dir) = backup {
+        if !backup_before_delete(dir, &targets, total_size)? {
+            outln!("{}", "Aborted, nothing was deleted.".yellow());
+            return Ok(());
+        }
+    }
+
     // Perform deletion
-    println!("{}", "Cleaning selected artifacts...".dimmed());
+    outln!("{}", "Cleaning selected artifacts...".dimmed());
+    let before = verify.then(|| FreeSpaceSnapshot::take(&targets));
 
+    let validator = SecurityValidator::new();
     let mut freed = 0u64;
-    for artifact in artifacts.iter().filter(|a| a.selected) {
+    let mut skipped = Vec::new();
+
+    install_handler();
+    let selected: Vec<_> = artifacts.iter().filter(|a| a.selected).collect();
+    let mut finished = 0;
+
+    for artifact in &selected {
+        if interrupted() {
+            break;
+        }
+        finished += 1;
+
+        if let PathValidation::Blocked { reason } = validator.validate_path(&artifact.path) {
+            skipped.push(SkippedEntry {
+                path: artifact.path.clone(),
+                reason,
+            });
+            continue;
+        }
+
         match safe_delete(&artifact.path, false) {
             Ok(size) => {
                 freed += size;
-                println!("  {} Removed {}", "✓".green(), artifact.project_name);
+                outln!("  {} Removed {}", "✓".green(), artifact.project_name);
             }
             Err(e) => {
-                println!("  {} Failed {}: {}", "✗".red(), artifact.project_name, e);
+                warnln!("  {} Failed {}: {}", "✗".red(), artifact.project_name, e);
             }
         }
     }
 
-    println!();
-    println!("{}", "═".repeat(60));
-    println!(
+    if interrupted() {
+        warn_interrupted(finished, selected.len(), "artifacts");
+    }
+
+    outln!();
+    outln!("{}", "═".repeat(60));
+    outln!(
         "{}: {}",
         "Space freed".bold(),
         format_size(freed).green().bold()
     );
 
+    if let Some(before) = before {
+        print_verification(freed, FreeSpaceSnapshot::take(&targets).freed_since(&before));
+    }
+
+    print_skipped(&skipped);
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn make_project(root: &std::path::Path, name: &str, marker: &str, artifact: &str, bytes: usize) {
+        let project = root.join(name);
+        fs::create_dir_all(project.join(artifact)).unwrap();
+        fs::write(project.join(marker), "").unwrap();
+        fs::write(project.join(artifact).join("blob"), vec![0u8; bytes]).unwrap();
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_serial() {
+        let temp = TempDir::new().unwrap();
+        for i in 0..12 {
+            make_project(temp.path(), &format!("web{}", i), "package.json", "node_modules", 100 * i);
+            make_project(temp.path(), &format!("crate{}", i), "Cargo.toml", "target", 50 * i + 1);
+        }
+        let paths = vec![temp.path().to_path_buf()];
+
+        let mut serial: Vec<_> = find_candidates(&paths, DEFAULT_MAX_DEPTH)
+            .into_iter()
+            .map(measure_candidate)
+            .map(|a| (a.path, a.artifact_type, a.size))
+            .collect();
+        serial.sort();
+
+        let mut parallel: Vec<_> = scan_artifacts(&paths, DEFAULT_MAX_DEPTH)
+            .into_iter()
+            .map(|a| (a.path, a.artifact_type, a.size))
+            .collect();
+        parallel.sort();
+
+        assert_eq!(parallel.len(), 24);
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn test_scan_artifacts_max_depth() {
+        let temp = TempDir::new().unwrap();
+        let deep = temp.path().join("a/b/c");
+        make_project(&deep, "web", "package.json", "node_modules", 10);
+
+        // node_modules sits five levels below the scan path
+        let paths = [temp.path().to_path_buf()];
+        assert!(scan_artifacts(&paths, DEFAULT_MAX_DEPTH).is_empty());
+        assert_eq!(scan_artifacts(&paths, 5).len(), 1);
+        assert!(scan_artifacts(&paths, 2).is_empty());
+    }
+
+    #[test]
+    fn test_nested_artifacts_reported_once() {
+        let temp = TempDir::new().unwrap();
+        make_project(temp.path(), "app", "package.json", "node_modules", 10);
+        let nested = temp.path().join("app/node_modules/dep");
+        fs::create_dir_all(nested.join("node_modules/inner")).unwrap();
+        fs::write(nested.join("package.json"), "").unwrap();
+        fs::write(nested.join("node_modules/inner/index.js"), vec![0u8; 20]).unwrap();
+
+        let artifacts = scan_artifacts(&[temp.path().to_path_buf()], DEFAULT_MAX_DEPTH);
+
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].path, temp.path().join("app/node_modules"));
+        assert_eq!(artifacts[0].size, 30);
+    }
+
+    #[test]
+    fn test_moleignore_skips_artifacts() {
+        let temp = TempDir::new().unwrap();
+        make_project(temp.path(), "repo/web", "package.json", "node_modules", 10);
+        make_project(temp.path(), "repo/api", "Cargo.toml", "target", 10);
+        make_project(temp.path(), "repo/tool", "Cargo.toml", "target", 10);
+        fs::write(temp.path().join("repo/.moleignore"), "# keep the web deps\nweb/node_modules/\n").unwrap();
+        fs::write(temp.path().join("repo/api/.moleignore"), "target\n").unwrap();
+
+        let artifacts = scan_artifacts(&[temp.path().to_path_buf()], DEFAULT_MAX_DEPTH);
+
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].path, temp.path().join("repo/tool/target"));
+    }
+
+    #[test]
+    fn test_future_mtime_is_zero_days_old() {
+        let temp = TempDir::new().unwrap();
+        let file = fs::File::create(temp.path().join("restored")).unwrap();
+        let future = SystemTime::now() + std::time::Duration::from_secs(30 * 86400);
+        file.set_modified(future).unwrap();
+
+        assert_eq!(calculate_age(&temp.path().join("restored")), 0);
+    }
+
+    #[test]
+    fn test_scan_global_caches() {
+        let home = TempDir::new().unwrap();
+        let registry = home.path().join(".cargo/registry/cache");
+        fs::create_dir_all(&registry).unwrap();
+        fs::write(registry.join("serde.crate"), vec![0u8; 64]).unwrap();
+        fs::create_dir_all(home.path().join(".cache/pip")).unwrap();
+
+        let caches = scan_global_caches(home.path());
+
+        assert_eq!(caches.len(), 1);
+        assert_eq!(caches[0].project_name, "Cargo registry");
+        assert_eq!(caches[0].artifact_type, SHARED_LABEL);
+        assert_eq!(caches[0].size, 64);
+        assert!(caches[0].shared && caches[0].selected);
+    }
+}
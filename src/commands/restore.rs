@@ -0,0 +1,93 @@
+//! Restore command - bring items back from the trash
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+use crate::core::filesystem::{dir_size, format_size};
+use crate::core::trash::{list_trashed, restore};
+use crate::core::CleanupPaths;
+use crate::{outln, sayln, warnln};
+
+/// How many items the listing shows
+const LIST_LIMIT: usize = 20;
+
+/// Run the restore command
+///
+/// Without an index, lists the most recently trashed items. With one,
+/// moves that item back to where it came from.
+pub fn run(index: Option<usize>, rename: bool) -> Result<()> {
+    outln!("{}", "Mole-RS Restore".bold().cyan());
+    outln!("{}", "═".repeat(60));
+    outln!();
+
+    let trash = CleanupPaths::new().trash;
+    let items = list_trashed(&trash);
+
+    if items.is_empty() {
+        outln!("{}", "Trash is empty.".yellow());
+        return Ok(());
+    }
+
+    let Some(index) = index else {
+        sayln!("{}", "Recently trashed:".bold());
+        sayln!();
+
+        for (i, item) in items.iter().take(LIST_LIMIT).enumerate() {
+            let deleted = item
+                .deleted_at
+                .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "unknown date".to_string());
+
+            sayln!(
+                " {:3}. {} {:>10}  {}",
+                i + 1,
+                deleted.dimmed(),
+                format_size(dir_size(&item.file_path).unwrap_or(0)).yellow(),
+                item.original_path.display()
+            );
+        }
+
+        if items.len() > LIST_LIMIT {
+            sayln!();
+            sayln!(
+                "  {} {} more items...",
+                "...".dimmed(),
+                items.len() - LIST_LIMIT
+            );
+        }
+
+        sayln!();
+        sayln!("{}", "Run `mo restore <N>` to restore an item.".dimmed());
+        return Ok(());
+    };
+
+    let Some(item) = index.checked_sub(1).and_then(|i| items.get(i)) else {
+        bail!("No trashed item #{} (1-{} available)", index, items.len());
+    };
+
+    if item.original_path.symlink_metadata().is_ok() && !rename {
+        warnln!(
+            "{} {} already exists.",
+            "!".yellow(),
+            item.original_path.display()
+        );
+        warnln!(
+            "{}",
+            format!(
+                "Run `mo restore {} --rename` to restore it under a suffixed name.",
+                index
+            )
+            .dimmed()
+        );
+        return Ok(());
+    }
+
+    let restored = restore(item, rename)?;
+    outln!(
+        "  {} Restored {}",
+        "✓".green(),
+        restored.display().to_string().bold()
+    );
+
+    Ok(())
+}
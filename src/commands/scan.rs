@@ -0,0 +1,243 @@
+//! Scan command - estimate what every other command could reclaim
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::commands::{clean, optimize, purge, uninstall};
+use crate::core::config::Config;
+use crate::core::csv;
+use crate::core::filesystem::format_size;
+use crate::core::json;
+use crate::core::output::{warn_if_timed_out, OutputFormat};
+use crate::{outln, say, sayln};
+
+/// Items listed under each section in the text summary
+const TOP_ITEMS: usize = 3;
+
+/// Reclaimable estimates of every command, serialized for `--format json`
+#[derive(Debug, Serialize)]
+pub struct ScanSummary {
+    pub sections: Vec<ScanSection>,
+    pub total: u64,
+}
+
+/// What one command would remove by default
+#[derive(Debug, Serialize)]
+pub struct ScanSection {
+    pub name: &'static str,
+    /// Command that reclaims this space
+    pub command: &'static str,
+    pub size: u64,
+    /// Largest first
+    pub items: Vec<ScanItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScanItem {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub size: u64,
+    pub requires_sudo: bool,
+}
+
+impl From<(String, u64)> for ScanItem {
+    fn from((name, size): (String, u64)) -> Self {
+        Self {
+            name,
+            path: None,
+            size,
+            requires_sudo: false,
+        }
+    }
+}
+
+impl ScanSection {
+    pub fn new<I>(name: &'static str, command: &'static str, items: Vec<I>) -> Self
+    where
+        I: Into<ScanItem>,
+    {
+        let mut items: Vec<ScanItem> = items.into_iter().map(Into::into).collect();
+        items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        Self {
+            name,
+            command,
+            size: items.iter().map(|i| i.size).sum(),
+            items,
+        }
+    }
+}
+
+impl ScanSummary {
+    pub fn new(sections: Vec<ScanSection>) -> Self {
+        Self {
+            total: sections.iter().map(|s| s.size).sum(),
+            sections,
+        }
+    }
+
+    /// Run every command's scan; only what each would select by default counts
+    fn collect() -> Self {
+        let categories = clean::scan_categories(true)
+            .into_iter()
+            .filter(|c| c.selected)
+            .map(|c| ScanItem {
+                name: c.name,
+                path: Some(c.path),
+                size: c.size,
+                requires_sudo: c.requires_sudo,
+            })
+            .collect();
+
+        let artifacts = purge::scan_artifacts(&Config::load().project_paths, purge::DEFAULT_MAX_DEPTH)
+            .into_iter()
+            .filter(|a| a.selected)
+            .map(|a| ScanItem {
+                name: format!("{} {}", a.project_name, a.artifact_type),
+                path: Some(a.path),
+                size: a.size,
+                requires_sudo: false,
+            })
+            .collect();
+
+        Self::new(vec![
+            ScanSection::new("Clean", "mo clean --apply", categories),
+            ScanSection::new("Purge", "mo purge --apply", artifacts),
+            ScanSection::new("Optimize", "mo optimize", optimize::reclaimable_estimates()),
+            ScanSection::new("Uninstall leftovers", "mo uninstall <package>", uninstall::removed_package_leftovers()),
+        ])
+    }
+
+    /// `category,path,size_bytes,requires_sudo`, one row per item
+    pub fn to_csv(&self) -> String {
+        let mut out = csv::row(&["category", "path", "size_bytes", "requires_sudo"]);
+        for item in self.sections.iter().flat_map(|s| &s.items) {
+            let path = item
+                .path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            out.push_str(&csv::row(&[
+                item.name.clone(),
+                path,
+                item.size.to_string(),
+                item.requires_sudo.to_string(),
+            ]));
+        }
+        out
+    }
+}
+
+/// Run the scan command
+pub fn run(format: OutputFormat) -> Result<()> {
+    if format == OutputFormat::Text {
+        outln!("{}", "Mole-RS Scan".bold().cyan());
+        outln!("{}", "═".repeat(50));
+        outln!();
+        outln!("{}", "Estimating reclaimable space...".dimmed());
+        outln!();
+    }
+
+    let summary = ScanSummary::collect();
+
+    match format {
+        OutputFormat::Json => {
+            sayln!("{}", json::to_string_pretty(&summary)?);
+            return Ok(());
+        }
+        OutputFormat::Csv => {
+            say!("{}", summary.to_csv());
+            return Ok(());
+        }
+        OutputFormat::Text => {}
+    }
+
+    for section in &summary.sections {
+        sayln!(
+            "  {:<20} {:>10}  {}",
+            section.name.bold(),
+            format_size(section.size).yellow(),
+            section.command.dimmed()
+        );
+        for item in section.items.iter().take(TOP_ITEMS) {
+            outln!(
+                "    {:<30} {:>10}",
+                item.name.dimmed(),
+                format_size(item.size).dimmed()
+            );
+        }
+        if section.items.len() > TOP_ITEMS {
+            outln!("    {}", format!("... {} more", section.items.len() - TOP_ITEMS).dimmed());
+        }
+    }
+
+    sayln!("{}", "─".repeat(50));
+    sayln!(
+        "  {:<20} {:>10}",
+        "Total reclaimable".bold(),
+        format_size(summary.total).green().bold()
+    );
+    outln!();
+    outln!("{}", "Nothing was deleted.".dimmed());
+    warn_if_timed_out();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_summary_totals() {
+        let summary = ScanSummary::new(vec![
+            ScanSection::new(
+                "Clean",
+                "mo clean --apply",
+                vec![("Pip Cache".to_string(), 100), ("Trash".to_string(), 300)],
+            ),
+            ScanSection::new("Purge", "mo purge --apply", Vec::<ScanItem>::new()),
+        ]);
+
+        assert_eq!(summary.total, 400);
+        assert_eq!(summary.sections[0].size, 400);
+        assert_eq!(summary.sections[0].items[0].name, "Trash");
+        assert_eq!(summary.sections[1].size, 0);
+
+        let json = json::to_string(&summary).unwrap();
+        assert!(json.contains("\"total\":400"));
+        assert!(json.contains("\"command\":\"mo purge --apply\""));
+    }
+
+    #[test]
+    fn test_scan_summary_csv() {
+        let summary = ScanSummary::new(vec![ScanSection::new(
+            "Clean",
+            "mo clean --apply",
+            vec![
+                ScanItem {
+                    name: "Pip Cache".to_string(),
+                    path: Some(PathBuf::from("/home/u/.cache/pip")),
+                    size: 100,
+                    requires_sudo: false,
+                },
+                ScanItem {
+                    name: "Logs".to_string(),
+                    path: Some(PathBuf::from("/var/log/a,b")),
+                    size: 300,
+                    requires_sudo: true,
+                },
+            ],
+        )]);
+
+        assert_eq!(
+            summary.to_csv(),
+            "category,path,size_bytes,requires_sudo\n\
+             Logs,\"/var/log/a,b\",300,true\n\
+             Pip Cache,/home/u/.cache/pip,100,false\n"
+        );
+    }
+}
@@ -2,15 +2,213 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 use std::io::{self, Write};
 use std::time::Duration;
 
+use crate::core::config::Config;
 use crate::core::filesystem::format_size;
-use crate::core::system::SystemInfo;
+use crate::core::json;
+use crate::core::system::{zram_total, DiskInfo, ProcessInfo, SystemInfo, ZramStats};
+use crate::sayln;
+
+/// Point-in-time system status, serialized for `--json`
+#[derive(Debug, Serialize)]
+pub struct StatusSnapshot {
+    pub hostname: String,
+    pub cpu_percent: f32,
+    pub cpu_per_core: Vec<f32>,
+    pub memory: MemorySnapshot,
+    pub load_average: LoadSnapshot,
+    pub disks: Vec<DiskSnapshot>,
+    pub network: NetworkSnapshot,
+    pub uptime_secs: u64,
+    pub top_processes: Vec<ProcessInfo>,
+    pub temperatures: Vec<TemperatureSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemperatureSnapshot {
+    pub label: String,
+    pub celsius: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemorySnapshot {
+    pub used: u64,
+    pub total: u64,
+    pub percent: f32,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    /// All zram devices together, when there are any in use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zram: Option<ZramStats>,
+    /// Used memory with zram data counted uncompressed, in percent of RAM
+    pub pressure_percent: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoadSnapshot {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiskSnapshot {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub percent: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetworkSnapshot {
+    pub received: u64,
+    pub transmitted: u64,
+}
+
+impl StatusSnapshot {
+    /// Capture the current values from a refreshed `SystemInfo`
+    pub fn capture(sysinfo: &SystemInfo) -> Self {
+        let (one, five, fifteen) = sysinfo.load_average();
+        let (received, transmitted) = sysinfo.network_totals();
+
+        Self {
+            hostname: sysinfo.hostname(),
+            cpu_percent: sysinfo.cpu_usage(),
+            cpu_per_core: sysinfo.cpu_per_core(),
+            memory: MemorySnapshot {
+                used: sysinfo.used_memory(),
+                total: sysinfo.total_memory(),
+                percent: sysinfo.memory_usage(),
+                swap_used: sysinfo.used_swap(),
+                swap_total: sysinfo.total_swap(),
+                zram: zram_total(&sysinfo.zram()),
+                pressure_percent: sysinfo.memory_pressure(),
+            },
+            load_average: LoadSnapshot { one, five, fifteen },
+            disks: sysinfo
+                .disk_info()
+                .into_iter()
+                .map(|disk| DiskSnapshot {
+                    used: disk.used_space(),
+                    percent: disk.usage_percent(),
+                    name: disk.name,
+                    mount_point: disk.mount_point,
+                    file_system: disk.file_system,
+                    total: disk.total_space,
+                    available: disk.available_space,
+                })
+                .collect(),
+            network: NetworkSnapshot {
+                received,
+                transmitted,
+            },
+            uptime_secs: sysinfo.uptime(),
+            top_processes: sysinfo.top_processes_by_cpu(5),
+            temperatures: sysinfo
+                .components()
+                .into_iter()
+                .map(|(label, celsius)| TemperatureSnapshot { label, celsius })
+                .collect(),
+        }
+    }
+}
+
+/// Resource that crossed its configured alert threshold
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alert {
+    LowSpace { mount_point: String, percent: f32 },
+    HighMemory { percent: f32 },
+    HighCpu { percent: f32 },
+}
+
+impl std::fmt::Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Alert::LowSpace { mount_point, percent } => {
+                write!(f, "LOW SPACE on {} ({:.1}% used)", mount_point, percent)
+            }
+            Alert::HighMemory { percent } => write!(f, "HIGH MEMORY ({:.1}% used)", percent),
+            Alert::HighCpu { percent } => write!(f, "HIGH CPU ({:.1}%)", percent),
+        }
+    }
+}
+
+/// Compare usage figures against the thresholds in `config`
+///
+/// `disks` holds `(mount point, percent used)` for the disks on screen.
+pub fn check_alerts(cpu: f32, memory: f32, disks: &[(String, f32)], config: &Config) -> Vec<Alert> {
+    let mut alerts: Vec<Alert> = disks
+        .iter()
+        .filter(|(_, percent)| *percent > f32::from(config.disk_alert_percent))
+        .map(|(mount_point, percent)| Alert::LowSpace {
+            mount_point: mount_point.clone(),
+            percent: *percent,
+        })
+        .collect();
+
+    if memory > f32::from(config.memory_alert_percent) {
+        alerts.push(Alert::HighMemory { percent: memory });
+    }
+    if cpu > f32::from(config.cpu_alert_percent) {
+        alerts.push(Alert::HighCpu { percent: cpu });
+    }
+
+    alerts
+}
+
+/// Temperatures shown in red from this point
+const HOT_CELSIUS: f32 = 80.0;
+
+/// The `limit` hottest sensors, hottest first
+pub fn hottest(mut sensors: Vec<(String, f32)>, limit: usize) -> Vec<(String, f32)> {
+    sensors.sort_by(|a, b| b.1.total_cmp(&a.1));
+    sensors.truncate(limit);
+    sensors
+}
+
+/// Disks the status view shows: the root filesystem and anything under /home
+fn shown_disks(sysinfo: &SystemInfo) -> Vec<DiskInfo> {
+    sysinfo
+        .disk_info()
+        .into_iter()
+        .filter(|disk| disk.mount_point == "/" || disk.mount_point.starts_with("/home"))
+        .collect()
+}
+
+fn current_alerts(sysinfo: &SystemInfo, config: &Config) -> Vec<Alert> {
+    let disks: Vec<_> = shown_disks(sysinfo)
+        .into_iter()
+        .map(|disk| (disk.mount_point.clone(), disk.usage_percent()))
+        .collect();
+
+    // zram hides how much memory is really wanted, so alert on the
+    // uncompressed figure
+    check_alerts(sysinfo.cpu_usage(), sysinfo.memory_pressure(), &disks, config)
+}
 
 /// Run the status command (non-TUI version)
-pub fn run() -> Result<()> {
+pub fn run(once: bool, json: bool) -> Result<()> {
     let mut sysinfo = SystemInfo::new();
+    let config = Config::load();
+
+    if once || json {
+        // CPU usage is a delta between two refreshes
+        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sysinfo.refresh();
+
+        if json {
+            sayln!("{}", json::to_string(&StatusSnapshot::capture(&sysinfo))?);
+        } else {
+            render_status(&sysinfo, false, &current_alerts(&sysinfo, &config));
+        }
+        return Ok(());
+    }
 
     // Clear screen and hide cursor
     print!("\x1B[2J\x1B[H");
@@ -26,9 +224,18 @@ pub fn run() -> Result<()> {
     })
     .ok();
 
+    let mut previous_alerts = Vec::new();
     while running.load(std::sync::atomic::Ordering::SeqCst) {
         sysinfo.refresh();
-        render_status(&sysinfo);
+        let alerts = current_alerts(&sysinfo, &config);
+
+        // Ring once when something starts alerting, not on every redraw
+        if config.alert_bell && alerts.iter().any(|a| !previous_alerts.contains(a)) {
+            print!("\x07");
+        }
+
+        render_status(&sysinfo, true, &alerts);
+        previous_alerts = alerts;
         std::thread::sleep(Duration::from_secs(1));
     }
 
@@ -39,25 +246,35 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
-fn render_status(sysinfo: &SystemInfo) {
-    // Move to top-left
-    print!("\x1B[H");
+fn render_status(sysinfo: &SystemInfo, live: bool, alerts: &[Alert]) {
+    if live {
+        // Move to top-left
+        print!("\x1B[H");
+    }
 
     let width = 60;
 
     // Header
-    println!(
+    sayln!(
         "{}",
         format!("  Mole-RS Status {:>width$}", sysinfo.hostname(), width = width - 18)
             .bold()
             .on_bright_black()
     );
-    println!("{}", "─".repeat(width));
+    sayln!("{}", "─".repeat(width));
+
+    // Alerts go first so they are visible without reading the bars
+    for alert in alerts {
+        sayln!("  {}", format!("⚠ {}", alert).bold().white().on_red());
+    }
+    if !alerts.is_empty() {
+        sayln!();
+    }
 
     // CPU
     let cpu_usage = sysinfo.cpu_usage();
     let cpu_bar = progress_bar(cpu_usage as f64, 20);
-    println!(
+    sayln!(
         "  {} {} {:>5.1}%",
         "CPU".bold(),
         cpu_bar,
@@ -66,7 +283,7 @@ fn render_status(sysinfo: &SystemInfo) {
 
     // Load average
     let (l1, l5, l15) = sysinfo.load_average();
-    println!(
+    sayln!(
         "  {}  {:.2} / {:.2} / {:.2}",
         "Load".dimmed(),
         l1,
@@ -74,69 +291,125 @@ fn render_status(sysinfo: &SystemInfo) {
         l15
     );
 
-    println!();
+    // Temperatures and fans, when the machine exposes them
+    let temps = hottest(sysinfo.components(), 3);
+    if !temps.is_empty() {
+        let shown: Vec<String> = temps
+            .iter()
+            .map(|(label, celsius)| {
+                let value = format!("{:.0}°C", celsius);
+                let value = if *celsius >= HOT_CELSIUS {
+                    value.red().bold()
+                } else {
+                    value.normal()
+                };
+                format!("{} {}", label, value)
+            })
+            .collect();
+        sayln!("  {}  {}", "Temp".dimmed(), shown.join(" · "));
+    }
+
+    let fans = sysinfo.fans();
+    if !fans.is_empty() {
+        let shown: Vec<String> = fans
+            .iter()
+            .map(|(label, rpm)| format!("{} {} rpm", label, rpm))
+            .collect();
+        sayln!("  {}  {}", "Fans".dimmed(), shown.join(" · "));
+    }
+
+    sayln!();
 
     // Memory
     let mem_usage = sysinfo.memory_usage();
     let mem_bar = progress_bar(mem_usage as f64, 20);
     let used_mem = format_size(sysinfo.used_memory());
     let total_mem = format_size(sysinfo.total_memory());
-    println!(
+    sayln!(
         "  {} {} {:>5.1}%",
         "Memory".bold(),
         mem_bar,
         mem_usage
     );
-    println!(
+    sayln!(
         "  {}  {} / {}",
         "     ".dimmed(),
         used_mem,
         total_mem
     );
 
-    println!();
-
-    // Disks
-    println!("  {}", "Disks".bold());
-    for disk in sysinfo.disk_info() {
-        if disk.mount_point == "/" || disk.mount_point.starts_with("/home") {
-            let usage = disk.usage_percent();
-            let bar = progress_bar(usage as f64, 15);
-            let used = format_size(disk.used_space());
-            let total = format_size(disk.total_space);
-            println!(
-                "   {:10} {} {:>5.1}%  {} / {}",
-                disk.mount_point,
-                bar,
-                usage,
-                used,
-                total
+    // Swap
+    match sysinfo.swap_usage() {
+        Some(swap_usage) => {
+            sayln!(
+                "  {}   {} {:>5.1}%  {} / {}",
+                "Swap".bold(),
+                progress_bar(swap_usage as f64, 20),
+                swap_usage,
+                format_size(sysinfo.used_swap()),
+                format_size(sysinfo.total_swap())
             );
         }
+        None => sayln!("  {}   {}", "Swap".bold(), "no swap".dimmed()),
+    }
+
+    // zram swap lives in RAM, so show what it holds and what it costs
+    if let Some(zram) = zram_total(&sysinfo.zram()) {
+        sayln!(
+            "  {}   {} compressed → {} original ({:.1}x)",
+            "zram".bold(),
+            format_size(zram.compressed),
+            format_size(zram.original),
+            zram.ratio()
+        );
+        sayln!(
+            "  {}  {:.1}% of RAM with zram data uncompressed",
+            "     ".dimmed(),
+            sysinfo.memory_pressure()
+        );
     }
 
-    println!();
+    sayln!();
+
+    // Disks
+    sayln!("  {}", "Disks".bold());
+    for disk in shown_disks(sysinfo) {
+        let usage = disk.usage_percent();
+        let bar = progress_bar(usage as f64, 15);
+        let used = format_size(disk.used_space());
+        let total = format_size(disk.total_space);
+        sayln!(
+            "   {:10} {} {:>5.1}%  {} / {}",
+            disk.mount_point,
+            bar,
+            usage,
+            used,
+            total
+        );
+    }
+
+    sayln!();
 
     // Network I/O
     let (rx, tx) = sysinfo.network_io();
-    println!(
+    sayln!(
         "  {} ↓ {}  ↑ {}",
         "Network".bold(),
         format_size(rx),
         format_size(tx)
     );
 
-    println!();
+    sayln!();
 
     // Top processes
-    println!("  {} {:>15} {:>10}", "Top Processes".bold(), "CPU%", "Memory");
+    sayln!("  {} {:>15} {:>10}", "Top Processes".bold(), "CPU%", "Memory");
     for proc in sysinfo.top_processes_by_cpu(5) {
         let name = if proc.name.len() > 15 {
             format!("{}...", &proc.name[..12])
         } else {
             proc.name.clone()
         };
-        println!(
+        sayln!(
             "   {:<15} {:>14.1} {:>10}",
             name,
             proc.cpu_usage,
@@ -144,14 +417,14 @@ fn render_status(sysinfo: &SystemInfo) {
         );
     }
 
-    println!();
+    sayln!();
 
     // Uptime
     let uptime = sysinfo.uptime();
     let days = uptime / 86400;
     let hours = (uptime % 86400) / 3600;
     let mins = (uptime % 3600) / 60;
-    println!(
+    sayln!(
         "  {} {}d {}h {}m",
         "Uptime".dimmed(),
         days,
@@ -159,8 +432,12 @@ fn render_status(sysinfo: &SystemInfo) {
         mins
     );
 
-    println!();
-    println!("  {}", "Press Ctrl+C to exit".dimmed());
+    if live {
+        sayln!();
+        sayln!("  {}", "Press Ctrl+C to exit".dimmed());
+        // Alerts come and go, so wipe whatever the previous frame left below
+        print!("\x1B[J");
+    }
 
     io::stdout().flush().ok();
 }
@@ -179,3 +456,56 @@ fn progress_bar(percent: f64, width: usize) -> String {
         bar.green().to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_alerts() {
+        let config = Config::default();
+        let disks = vec![("/".to_string(), 93.5), ("/home".to_string(), 40.0)];
+
+        let alerts = check_alerts(50.0, 95.0, &disks, &config);
+
+        assert_eq!(
+            alerts,
+            vec![
+                Alert::LowSpace {
+                    mount_point: "/".to_string(),
+                    percent: 93.5
+                },
+                Alert::HighMemory { percent: 95.0 },
+            ]
+        );
+        assert_eq!(alerts[0].to_string(), "LOW SPACE on / (93.5% used)");
+    }
+
+    #[test]
+    fn test_check_alerts_respects_thresholds() {
+        let config = Config {
+            disk_alert_percent: 95,
+            cpu_alert_percent: 50,
+            ..Config::default()
+        };
+
+        let alerts = check_alerts(60.0, 10.0, &[("/".to_string(), 93.5)], &config);
+
+        assert_eq!(alerts, vec![Alert::HighCpu { percent: 60.0 }]);
+    }
+
+    #[test]
+    fn test_hottest() {
+        let sensors = vec![
+            ("acpitz".to_string(), 45.0),
+            ("Package id 0".to_string(), 82.0),
+            ("nvme Composite".to_string(), 51.5),
+            ("Core 0".to_string(), 79.0),
+        ];
+
+        let hot = hottest(sensors, 2);
+
+        assert_eq!(hot, vec![("Package id 0".to_string(), 82.0), ("Core 0".to_string(), 79.0)]);
+        assert!(hottest(Vec::new(), 3).is_empty());
+    }
+}
@@ -3,10 +3,14 @@
 use anyhow::Result;
 use colored::Colorize;
 use std::collections::HashMap;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 use crate::core::filesystem::{dir_size, format_size, safe_delete};
+use crate::core::output::confirm;
+use crate::core::report::{Report, ReportItem};
+use crate::{outln, sayln, warnln};
 
 /// Installed application info
 #[derive(Debug, Clone)]
@@ -19,11 +23,12 @@ pub struct InstalledApp {
 }
 
 /// Type of application
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
 pub enum AppType {
     Deb,        // Installed via apt/dpkg
     Snap,       // Installed via snap
     Flatpak,    // Installed via flatpak
+    #[value(name = "appimage")]
     AppImage,   // AppImage file
     Manual,     // Manually installed (in /opt, ~/.local/bin, etc.)
 }
@@ -57,6 +62,7 @@ pub enum LeftoverType {
     Log,         // Log files
     Desktop,     // Desktop entries
     Autostart,   // Autostart entries
+    Other,       // User-supplied search path
 }
 
 impl std::fmt::Display for LeftoverType {
@@ -68,6 +74,28 @@ impl std::fmt::Display for LeftoverType {
             LeftoverType::Log => write!(f, "log"),
             LeftoverType::Desktop => write!(f, "desktop"),
             LeftoverType::Autostart => write!(f, "autostart"),
+            LeftoverType::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Deepest leftover search allowed below each location
+pub const MAX_LEFTOVER_DEPTH: usize = 3;
+
+/// Where and how deep to look for leftovers
+#[derive(Debug, Clone)]
+pub struct LeftoverSearch {
+    /// Levels below each location to search (1 = direct children only)
+    pub depth: usize,
+    /// Locations to search instead of the defaults
+    pub paths: Vec<PathBuf>,
+}
+
+impl Default for LeftoverSearch {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            paths: Vec::new(),
         }
     }
 }
@@ -124,33 +152,99 @@ pub fn scan_installed_apps() -> Result<Vec<InstalledApp>> {
 /// Scan dpkg installed packages
 fn scan_dpkg_apps() -> Result<Vec<InstalledApp>> {
     let output = std::process::Command::new("dpkg-query")
-        .args(["-W", "-f", "${Package}\t${Installed-Size}\n"])
+        .args(["-W", "-f", "${Package}\t${Installed-Size}\t${db:Status-Abbrev}\n"])
         .output();
-    
-    let mut apps = Vec::new();
-    
+
     if let Ok(output) = output {
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 2 {
-                    let name = parts[0].to_string();
-                    let size_kb: u64 = parts[1].parse().unwrap_or(0);
-                    
-                    apps.push(InstalledApp {
-                        name: name.clone(),
-                        path: PathBuf::from(format!("/var/lib/dpkg/info/{}.list", name)),
-                        size: size_kb * 1024, // Convert KB to bytes
-                        app_type: AppType::Deb,
-                        leftovers: Vec::new(),
-                    });
-                }
+            return Ok(parse_dpkg_output(&String::from_utf8_lossy(&output.stdout)));
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Leftovers of removed packages whose config is still registered (`rc`),
+/// with their total size, for `mo scan`
+pub fn removed_package_leftovers() -> Vec<(String, u64)> {
+    let Ok(output) = std::process::Command::new("dpkg-query")
+        .args(["-W", "-f", "${Package}\t${Installed-Size}\t${db:Status-Abbrev}\n"])
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    let search = LeftoverSearch::default();
+    parse_residual_packages(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .map(|name| {
+            let size = find_leftovers(&name, &search).iter().map(|l| l.size).sum();
+            (name, size)
+        })
+        .filter(|(_, size)| *size > 0)
+        .collect()
+}
+
+/// Names of removed-but-configured (`rc`) packages in the same
+/// `dpkg-query` output [`parse_dpkg_output`] reads
+pub fn parse_residual_packages(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let name = parts.next()?.trim();
+            let status = parts.nth(1)?;
+            (!name.is_empty() && status.chars().nth(1) == Some('c')).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Parse `dpkg-query -W -f '${Package}\t${Installed-Size}\t${db:Status-Abbrev}\n'`
+///
+/// Only packages whose files are actually on disk (status `ii`/`hi` etc.) are
+/// returned; removed-but-configured (`rc`) entries are skipped. An empty or
+/// non-numeric `Installed-Size` is reported as 0 rather than guessed.
+fn parse_dpkg_output(stdout: &str) -> Vec<InstalledApp> {
+    let mut apps = Vec::new();
+
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        let name = parts[0].trim();
+        if name.is_empty() {
+            continue;
+        }
+
+        // Second status letter is the current state: 'i' means installed
+        if let Some(status) = parts.get(2) {
+            if status.chars().nth(1) != Some('i') {
+                continue;
             }
         }
+
+        let size = match parts.get(1).map(|s| s.trim()).unwrap_or("") {
+            "" => 0,
+            raw => match raw.parse::<u64>() {
+                Ok(size_kb) => size_kb.checked_mul(1024).unwrap_or_else(|| {
+                    tracing::debug!("Installed-Size overflows for {}: {}", name, raw);
+                    0
+                }),
+                Err(_) => {
+                    tracing::debug!("Unparseable Installed-Size for {}: {}", name, raw);
+                    0
+                }
+            },
+        };
+
+        apps.push(InstalledApp {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/var/lib/dpkg/info/{}.list", name)),
+            size,
+            app_type: AppType::Deb,
+            leftovers: Vec::new(),
+        });
     }
-    
-    Ok(apps)
+
+    apps
 }
 
 /// Scan snap packages
@@ -158,34 +252,34 @@ fn scan_snap_apps() -> Result<Vec<InstalledApp>> {
     let output = std::process::Command::new("snap")
         .args(["list"])
         .output();
-    
-    let mut apps = Vec::new();
-    
+
     if let Ok(output) = output {
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for (i, line) in stdout.lines().enumerate() {
-                if i == 0 { continue; } // Skip header
-                
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if !parts.is_empty() {
-                    let name = parts[0].to_string();
-                    let snap_path = PathBuf::from(format!("/snap/{}", name));
-                    let size = dir_size(&snap_path).unwrap_or(0);
-                    
-                    apps.push(InstalledApp {
-                        name,
-                        path: snap_path,
-                        size,
-                        app_type: AppType::Snap,
-                        leftovers: Vec::new(),
-                    });
-                }
+            let mut apps = parse_snap_output(&String::from_utf8_lossy(&output.stdout));
+            for app in &mut apps {
+                app.size = dir_size(&app.path).unwrap_or(0);
             }
+            return Ok(apps);
         }
     }
-    
-    Ok(apps)
+
+    Ok(Vec::new())
+}
+
+/// Parse `snap list` output (sizes are filled in by the caller)
+fn parse_snap_output(stdout: &str) -> Vec<InstalledApp> {
+    stdout
+        .lines()
+        .skip(1) // Skip header
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|name| InstalledApp {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/snap/{}", name)),
+            size: 0,
+            app_type: AppType::Snap,
+            leftovers: Vec::new(),
+        })
+        .collect()
 }
 
 /// Scan flatpak packages
@@ -193,110 +287,195 @@ fn scan_flatpak_apps() -> Result<Vec<InstalledApp>> {
     let output = std::process::Command::new("flatpak")
         .args(["list", "--app", "--columns=application,name,size"])
         .output();
-    
-    let mut apps = Vec::new();
-    
+
     if let Ok(output) = output {
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 2 {
-                    let app_id = parts[0].to_string();
-                    let name = parts.get(1).unwrap_or(&parts[0]).to_string();
-                    
-                    apps.push(InstalledApp {
-                        name,
-                        path: PathBuf::from(format!("/var/lib/flatpak/app/{}", app_id)),
-                        size: 0, // Would need to calculate
-                        app_type: AppType::Flatpak,
-                        leftovers: Vec::new(),
-                    });
-                }
-            }
+            return Ok(parse_flatpak_output(&String::from_utf8_lossy(&output.stdout)));
         }
     }
-    
-    Ok(apps)
+
+    Ok(Vec::new())
+}
+
+/// Parse `flatpak list --app --columns=application,name,size` output
+fn parse_flatpak_output(stdout: &str) -> Vec<InstalledApp> {
+    let mut apps = Vec::new();
+
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() >= 2 {
+            let app_id = parts[0].trim();
+            let name = parts[1].trim();
+
+            apps.push(InstalledApp {
+                name: if name.is_empty() { app_id } else { name }.to_string(),
+                path: PathBuf::from(format!("/var/lib/flatpak/app/{}", app_id)),
+                size: parts.get(2).and_then(|s| parse_flatpak_size(s)).unwrap_or(0),
+                app_type: AppType::Flatpak,
+                leftovers: Vec::new(),
+            });
+        }
+    }
+
+    apps
+}
+
+/// Parse flatpak's human-readable sizes such as "245.6 MB" (decimal units)
+fn parse_flatpak_size(raw: &str) -> Option<u64> {
+    let raw = raw.replace('\u{a0}', " ");
+    let mut parts = raw.split_whitespace();
+    let value: f64 = parts.next()?.parse().ok()?;
+
+    let multiplier = match parts.next().unwrap_or("bytes") {
+        "bytes" | "B" => 1.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+
+    Some((value * multiplier) as u64)
 }
 
 /// Find leftover files for a given app name
-pub fn find_leftovers(app_name: &str) -> Vec<LeftoverFile> {
-    let mut leftovers = Vec::new();
-    let locations = get_leftover_locations();
-    
-    // Normalize app name for matching
-    let normalized = normalize_app_name(app_name);
-    let patterns = generate_search_patterns(&normalized);
-    
+pub fn find_leftovers(app_name: &str, search: &LeftoverSearch) -> Vec<LeftoverFile> {
+    let mut leftovers: Vec<LeftoverFile> = Vec::new();
+    let locations = if search.paths.is_empty() {
+        get_leftover_locations()
+    } else {
+        search
+            .paths
+            .iter()
+            .map(|p| (p.clone(), LeftoverType::Other))
+            .collect()
+    };
+
     for (base_path, file_type) in locations {
         if !base_path.exists() {
             continue;
         }
-        
-        // Search only first level to avoid deep recursion
-        if let Ok(entries) = std::fs::read_dir(&base_path) {
-            for entry in entries.filter_map(|e| e.ok()) {
-                let entry_name = entry.file_name().to_string_lossy().to_lowercase();
-                
-                for pattern in &patterns {
-                    if entry_name.contains(pattern) {
-                        let path = entry.path();
-                        let size = dir_size(&path).unwrap_or(0);
-                        
-                        leftovers.push(LeftoverFile {
-                            path,
-                            file_type: file_type.clone(),
-                            size,
-                        });
-                        break;
-                    }
-                }
+
+        let mut walker = WalkDir::new(&base_path)
+            .min_depth(1)
+            .max_depth(search.depth.clamp(1, MAX_LEFTOVER_DEPTH))
+            .follow_links(false)
+            .into_iter();
+
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+
+            if !matches_app_name(app_name, &entry.file_name().to_string_lossy()) {
+                continue;
+            }
+
+            // Everything below a match belongs to it
+            if entry.file_type().is_dir() {
+                walker.skip_current_dir();
             }
+
+            let path = entry.into_path();
+            if leftovers.iter().any(|l| path.starts_with(&l.path)) {
+                continue;
+            }
+
+            let size = dir_size(&path).unwrap_or(0);
+            leftovers.push(LeftoverFile {
+                path,
+                file_type: file_type.clone(),
+                size,
+            });
         }
     }
-    
+
     leftovers
 }
 
-/// Normalize app name for matching
-fn normalize_app_name(name: &str) -> String {
-    name.to_lowercase()
-        .replace('-', "")
-        .replace('_', "")
-        .replace(' ', "")
+/// Split a name into lowercase tokens on `-`, `_`, `.` and spaces
+fn name_tokens(name: &str) -> Vec<String> {
+    name.split(['-', '_', '.', ' '])
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
 }
 
-/// Generate search patterns from app name
-fn generate_search_patterns(normalized: &str) -> Vec<String> {
-    let mut patterns = vec![normalized.to_string()];
-    
-    // Add common variations
-    if normalized.len() > 3 {
-        // First 5 chars if long enough
-        if normalized.len() >= 5 {
-            patterns.push(normalized[..5].to_string());
+/// Check whether `entry_name` refers to `app_name`
+///
+/// The app name's tokens must appear as a contiguous run of whole tokens in
+/// the entry name, so "gimp" matches `GIMP` and `gimp-2.10` but not
+/// `gimpy` or `libgimpui`.
+pub fn matches_app_name(app_name: &str, entry_name: &str) -> bool {
+    let app = name_tokens(app_name);
+    let entry = name_tokens(entry_name);
+
+    !app.is_empty() && entry.windows(app.len()).any(|window| window == app.as_slice())
+}
+
+fn build_report(apps: &[InstalledApp], dry_run: bool) -> Report {
+    let mut items = Vec::new();
+
+    for app in apps {
+        items.push(ReportItem {
+            name: app.name.clone(),
+            kind: Some(app.app_type.to_string()),
+            path: Some(app.path.clone()),
+            size: app.size,
+            selected: true,
+            requires_sudo: matches!(app.app_type, AppType::Deb | AppType::Snap),
+        });
+
+        for leftover in &app.leftovers {
+            items.push(ReportItem {
+                name: app.name.clone(),
+                kind: Some(format!("leftover {}", leftover.file_type)),
+                path: Some(leftover.path.clone()),
+                size: leftover.size,
+                selected: true,
+                requires_sudo: false,
+            });
         }
     }
-    
-    patterns
+
+    Report::new("uninstall", dry_run, items)
+}
+
+/// What uninstalling one app did
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UninstallOutcome {
+    /// Bytes freed (or that would be freed in a dry run)
+    pub freed: u64,
+    /// Whether the package itself was removed (always true in a dry run)
+    pub removed: bool,
 }
 
 /// Uninstall an app based on its type
-pub fn uninstall_app(app: &InstalledApp, dry_run: bool, remove_leftovers: bool) -> Result<u64> {
+///
+/// Leftovers are taken from `app.leftovers`, which the caller fills in with
+/// `find_leftovers` beforehand.
+pub fn uninstall_app(
+    app: &InstalledApp,
+    dry_run: bool,
+    remove_leftovers: bool,
+) -> Result<UninstallOutcome> {
     let mut freed = 0u64;
+    let mut removed = dry_run;
     
-    println!();
-    println!(
+    outln!();
+    outln!(
         "Uninstalling {} ({})...",
         app.name.bold(),
         app.app_type.to_string().dimmed()
     );
     
     if dry_run {
-        println!("  {} Would remove app", "→".cyan());
+        outln!("  {} Would remove app", "→".cyan());
     } else {
         // Uninstall based on type
+        let sampled = reclaim_paths(app);
+        let before = sampled_size(&sampled);
+
         let result = match app.app_type {
             AppType::Deb => uninstall_deb(&app.name),
             AppType::Snap => uninstall_snap(&app.name),
@@ -307,25 +486,30 @@ pub fn uninstall_app(app: &InstalledApp, dry_run: bool, remove_leftovers: bool)
         
         match result {
             Ok(_) => {
-                println!("  {} Removed app", "✓".green());
-                freed += app.size;
+                outln!("  {} Removed app", "✓".green());
+                freed += if sampled.is_empty() {
+                    app.size
+                } else {
+                    report_reclaim(app, before, sampled_size(&sampled))
+                };
+                removed = true;
             }
             Err(e) => {
-                println!("  {} Failed: {}", "✗".red(), e);
+                warnln!("  {} Failed to remove {}: {}", "✗".red(), app.name, e);
             }
         }
     }
     
     // Handle leftovers
     if remove_leftovers {
-        let leftovers = find_leftovers(&app.name);
+        let leftovers = &app.leftovers;
         
         if !leftovers.is_empty() {
-            println!("  {} Found {} leftover locations", "→".cyan(), leftovers.len());
+            outln!("  {} Found {} leftover locations", "→".cyan(), leftovers.len());
             
-            for leftover in &leftovers {
+            for leftover in leftovers {
                 if dry_run {
-                    println!(
+                    outln!(
                         "    {} Would remove {} ({})",
                         "→".dimmed(),
                         leftover.path.display(),
@@ -335,7 +519,7 @@ pub fn uninstall_app(app: &InstalledApp, dry_run: bool, remove_leftovers: bool)
                 } else {
                     match safe_delete(&leftover.path, false) {
                         Ok(size) => {
-                            println!(
+                            outln!(
                                 "    {} Removed {} ({})",
                                 "✓".green(),
                                 leftover.path.display(),
@@ -344,7 +528,7 @@ pub fn uninstall_app(app: &InstalledApp, dry_run: bool, remove_leftovers: bool)
                             freed += size;
                         }
                         Err(e) => {
-                            println!(
+                            warnln!(
                                 "    {} Failed {}: {}",
                                 "✗".red(),
                                 leftover.path.display(),
@@ -357,7 +541,72 @@ pub fn uninstall_app(app: &InstalledApp, dry_run: bool, remove_leftovers: bool)
         }
     }
     
-    Ok(freed)
+    Ok(UninstallOutcome { freed, removed })
+}
+
+/// Where an app's bytes live on disk, sampled before and after removal
+///
+/// Debs are removed synchronously by dpkg and their files are spread over
+/// the system, so they are not sampled.
+fn reclaim_paths(app: &InstalledApp) -> Vec<PathBuf> {
+    match app.app_type {
+        AppType::Deb => Vec::new(),
+        AppType::Snap => snap_files(Path::new("/var/lib/snapd/snaps"), &app.name),
+        AppType::Flatpak | AppType::AppImage | AppType::Manual => vec![app.path.clone()],
+    }
+}
+
+/// Revision files of snap `name` (`<name>_<revision>.snap`) in `dir`
+pub fn snap_files(dir: &Path, name: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}_", name);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            file_name.starts_with(&prefix) && file_name.ends_with(".snap")
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+fn sampled_size(paths: &[PathBuf]) -> u64 {
+    paths.iter().map(|p| dir_size(p).unwrap_or(0)).sum()
+}
+
+/// Bytes still on disk after removal worth telling the user about
+///
+/// Up to a tenth of the original may legitimately linger (logs written on
+/// the way out, filesystem metadata); more than that means the removal
+/// hasn't finished, e.g. a snap still being unmounted.
+pub fn pending_reclaim(before: u64, after: u64) -> Option<u64> {
+    (after > 0 && after > before / 10).then_some(after)
+}
+
+/// Print how much removing `app` actually reclaimed and return it
+fn report_reclaim(app: &InstalledApp, before: u64, after: u64) -> u64 {
+    let reclaimed = before.saturating_sub(after);
+    outln!("    {} Reclaimed {}", "→".dimmed(), format_size(reclaimed));
+
+    if let Some(pending) = pending_reclaim(before, after) {
+        let hint = match app.app_type {
+            AppType::Snap => "snapd may still be unmounting it",
+            AppType::Flatpak => "flatpak may still be pruning it",
+            _ => "something may still be holding it",
+        };
+        warnln!(
+            "    {} Pending reclaim: {} of {} is still on disk; {}. Check again shortly.",
+            "!".yellow(),
+            format_size(pending),
+            app.name,
+            hint
+        );
+    }
+
+    reclaimed
 }
 
 fn uninstall_deb(name: &str) -> Result<()> {
@@ -410,21 +659,228 @@ fn uninstall_manual(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Options for the uninstall command
+#[derive(Debug, Clone, Default)]
+pub struct UninstallOptions {
+    /// Preview changes without uninstalling
+    pub dry_run: bool,
+    /// Only list installed apps
+    pub list_only: bool,
+    /// Where to look for leftovers
+    pub search: LeftoverSearch,
+    /// Uninstall every package named in this file
+    pub from: Option<PathBuf>,
+    /// In batch mode, uninstall every app a name matches instead of skipping ambiguous names
+    pub all_matches: bool,
+    /// Only consider apps of this type
+    pub app_type: Option<AppType>,
+    /// Only match apps named exactly like the argument (case-insensitive)
+    pub exact: bool,
+    /// Write the plan to this file
+    pub report: Option<PathBuf>,
+}
+
+impl UninstallOptions {
+    /// Installed apps, narrowed to `app_type` when one was given
+    fn installed_apps(&self) -> Result<Vec<InstalledApp>> {
+        let mut apps = scan_installed_apps()?;
+        if let Some(app_type) = self.app_type {
+            apps.retain(|a| a.app_type == app_type);
+        }
+        Ok(apps)
+    }
+}
+
+/// Read package names from a batch file, one per line
+///
+/// Blank lines and `#` comments are ignored.
+pub fn parse_package_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Apps whose name equals `name` (case-insensitive), or with `exact` off,
+/// every app whose name contains it
+pub fn find_apps<'a>(apps: &'a [InstalledApp], name: &str, exact: bool) -> Vec<&'a InstalledApp> {
+    let name = name.to_lowercase();
+
+    apps.iter()
+        .filter(|a| {
+            let app_name = a.name.to_lowercase();
+            if exact {
+                app_name == name
+            } else {
+                app_name.contains(&name)
+            }
+        })
+        .collect()
+}
+
+/// Apps matching `name`: exact (case-insensitive) matches if there are any,
+/// otherwise (unless `exact`) every app whose name contains it
+pub fn match_apps<'a>(apps: &'a [InstalledApp], name: &str, exact: bool) -> Vec<&'a InstalledApp> {
+    let matches = find_apps(apps, name, true);
+    if !matches.is_empty() || exact {
+        return matches;
+    }
+
+    find_apps(apps, name, false)
+}
+
+/// Uninstall every package listed in `list`, scanning installed apps once
+fn run_batch(list: &Path, options: &UninstallOptions) -> Result<()> {
+    let names = parse_package_list(&std::fs::read_to_string(list)?);
+    outln!(
+        "Uninstalling {} packages from {}...",
+        names.len(),
+        list.display().to_string().yellow()
+    );
+
+    let apps = options.installed_apps()?;
+    let mut total_freed = 0u64;
+    let mut summary: Vec<(String, std::result::Result<u64, String>)> = Vec::new();
+
+    for name in names {
+        let matching = match_apps(&apps, &name, options.exact);
+
+        if matching.is_empty() {
+            summary.push((name, Err("not installed".to_string())));
+            continue;
+        }
+
+        if matching.len() > 1 && !options.all_matches {
+            let candidates: Vec<_> = matching.iter().map(|a| a.name.as_str()).collect();
+            warnln!(
+                "{} '{}' matches {} apps: {}",
+                "!".yellow(),
+                name,
+                matching.len(),
+                candidates.join(", ")
+            );
+            summary.push((
+                name,
+                Err(format!("{} matches, skipped (use --all-matches)", matching.len())),
+            ));
+            continue;
+        }
+
+        for app in matching {
+            let app = InstalledApp {
+                leftovers: find_leftovers(&app.name, &options.search),
+                ..app.clone()
+            };
+
+            let result = match uninstall_app(&app, options.dry_run, true) {
+                Ok(outcome) => {
+                    total_freed += outcome.freed;
+                    if outcome.removed {
+                        Ok(outcome.freed)
+                    } else {
+                        Err("removal failed".to_string())
+                    }
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            summary.push((app.name, result));
+        }
+    }
+
+    outln!();
+    outln!("{}", "═".repeat(50));
+    outln!("{}", "Summary:".bold());
+    for (name, result) in &summary {
+        match result {
+            Ok(freed) => outln!("  {} {} {}", "✓".green(), name, format_size(*freed).dimmed()),
+            Err(reason) => warnln!("  {} {} {}", "✗".red(), name, reason.dimmed()),
+        }
+    }
+
+    let failed = summary.iter().filter(|(_, r)| r.is_err()).count();
+    outln!();
+    outln!(
+        "{}: {} ({} ok, {} failed){}",
+        if options.dry_run { "Would free" } else { "Space freed" }.bold(),
+        format_size(total_freed).green().bold(),
+        summary.len() - failed,
+        failed,
+        if options.dry_run { " (dry-run)" } else { "" }
+    );
+
+    Ok(())
+}
+
+/// Uninstall `apps` with their leftovers and print the total freed
+fn uninstall_selected(apps: &[&InstalledApp], options: &UninstallOptions) -> Result<()> {
+    let UninstallOptions {
+        dry_run,
+        ref search,
+        ref report,
+        ..
+    } = *options;
+
+    let matching: Vec<_> = apps
+        .iter()
+        .map(|a| InstalledApp {
+            leftovers: find_leftovers(&a.name, search),
+            ..(*a).clone()
+        })
+        .collect();
+
+    if let Some(report_path) = report {
+        build_report(&matching, dry_run).write(report_path)?;
+        outln!("Report written to {}", report_path.display().to_string().yellow());
+    }
+
+    let mut total_freed = 0u64;
+    for app in &matching {
+        total_freed += uninstall_app(app, dry_run, true)?.freed;
+    }
+
+    outln!();
+    outln!("{}", "═".repeat(50));
+
+    if dry_run {
+        outln!(
+            "{}: {} (dry-run)",
+            "Would free".bold(),
+            format_size(total_freed).green().bold()
+        );
+    } else {
+        outln!(
+            "{}: {}",
+            "Space freed".bold(),
+            format_size(total_freed).green().bold()
+        );
+    }
+
+    Ok(())
+}
+
 /// Run the uninstall command
-pub fn run(app_name: Option<String>, dry_run: bool, list_only: bool) -> Result<()> {
-    println!("{}", "Mole-RS Uninstall".bold().cyan());
-    println!("{}", "═".repeat(50));
-    println!();
+pub fn run(app_name: Option<String>, options: UninstallOptions) -> Result<()> {
+    let UninstallOptions { dry_run, list_only, .. } = options;
+
+    outln!("{}", "Mole-RS Uninstall".bold().cyan());
+    outln!("{}", "═".repeat(50));
+    outln!();
+
+    if let Some(list) = &options.from {
+        return run_batch(list, &options);
+    }
     
     if list_only {
         // Just list installed apps
-        println!("{}", "Scanning installed applications...".dimmed());
+        sayln!("{}", "Scanning installed applications...".dimmed());
         
-        let apps = scan_installed_apps()?;
+        let apps = options.installed_apps()?;
         
-        println!();
-        println!("Found {} installed packages:", apps.len().to_string().bold());
-        println!();
+        sayln!();
+        sayln!("Found {} installed packages:", apps.len().to_string().bold());
+        sayln!();
         
         // Group by type
         let mut by_type: HashMap<String, Vec<&InstalledApp>> = HashMap::new();
@@ -436,9 +892,9 @@ pub fn run(app_name: Option<String>, dry_run: bool, list_only: bool) -> Result<(
         }
         
         for (app_type, type_apps) in &by_type {
-            println!("  {} ({}):", app_type.bold(), type_apps.len());
+            sayln!("  {} ({}):", app_type.bold(), type_apps.len());
             for app in type_apps.iter().take(10) {
-                println!(
+                sayln!(
                     "    {} {} {}",
                     "•".dimmed(),
                     app.name,
@@ -446,9 +902,9 @@ pub fn run(app_name: Option<String>, dry_run: bool, list_only: bool) -> Result<(
                 );
             }
             if type_apps.len() > 10 {
-                println!("    {} ... and {} more", "".dimmed(), type_apps.len() - 10);
+                sayln!("    {} ... and {} more", "".dimmed(), type_apps.len() - 10);
             }
-            println!();
+            sayln!();
         }
         
         return Ok(());
@@ -456,50 +912,247 @@ pub fn run(app_name: Option<String>, dry_run: bool, list_only: bool) -> Result<(
     
     if let Some(name) = app_name {
         // Uninstall specific app
-        println!("Searching for '{}'...", name.yellow());
+        outln!("Searching for '{}'...", name.yellow());
+        
+        let apps = options.installed_apps()?;
+        let candidates = find_apps(&apps, &name, options.exact);
         
-        let apps = scan_installed_apps()?;
-        let matching: Vec<_> = apps
+        if candidates.is_empty() {
+            outln!("{}", "No matching applications found.".yellow());
+            return Ok(());
+        }
+
+        // Never remove several packages because a name happened to be a substring
+        if candidates.len() > 1 && !options.exact {
+            warnln!(
+                "{} '{}' matches {} apps:",
+                "!".yellow(),
+                name,
+                candidates.len()
+            );
+            for app in &candidates {
+                warnln!("    {} {} {}", "•".dimmed(), app.name, format!("({})", app.app_type).dimmed());
+            }
+            warnln!("Use a more specific name, or --exact to match the name exactly.");
+            return Ok(());
+        }
+
+        outln!();
+        outln!("Found {} matching apps:", candidates.len());
+        uninstall_selected(&candidates, &options)?;
+    } else if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        // No name given: let the user find the app instead of knowing it
+        let apps = options.installed_apps()?;
+        let labels: Vec<String> = apps
             .iter()
-            .filter(|a| a.name.to_lowercase().contains(&name.to_lowercase()))
+            .map(|a| format!("{}  ({}, {})", a.name, a.app_type, format_size(a.size)))
             .collect();
-        
-        if matching.is_empty() {
-            println!("{}", "No matching applications found.".yellow());
+        let picked: Vec<&InstalledApp> = crate::tui::pick("Uninstall", labels)?
+            .into_iter()
+            .map(|i| &apps[i])
+            .collect();
+
+        if picked.is_empty() {
+            outln!("{}", "Nothing selected.".yellow());
             return Ok(());
         }
-        
-        println!();
-        println!("Found {} matching apps:", matching.len());
-        
-        let mut total_freed = 0u64;
-        
-        for app in matching {
-            total_freed += uninstall_app(app, dry_run, true)?;
+
+        for app in &picked {
+            outln!("  {} {} {}", "•".dimmed(), app.name, format!("({})", app.app_type).dimmed());
         }
-        
-        println!();
-        println!("{}", "═".repeat(50));
-        
-        if dry_run {
-            println!(
-                "{}: {} (dry-run)",
-                "Would free".bold(),
-                format_size(total_freed).green().bold()
-            );
-        } else {
-            println!(
-                "{}: {}",
-                "Space freed".bold(),
-                format_size(total_freed).green().bold()
-            );
+        let prompt = format!("Uninstall {} app(s)?", picked.len());
+        if !dry_run && !confirm(&prompt) {
+            outln!("{}", "Aborted, nothing was removed.".yellow());
+            return Ok(());
         }
+        uninstall_selected(&picked, &options)?;
     } else {
-        println!("{}", "Usage:".bold());
-        println!("  mo uninstall <app-name>     Uninstall an app");
-        println!("  mo uninstall --list         List installed apps");
-        println!("  mo uninstall <name> --dry-run  Preview uninstall");
+        outln!("{}", "Usage:".bold());
+        outln!("  mo uninstall <app-name>     Uninstall an app");
+        outln!("  mo uninstall --list         List installed apps");
+        outln!("  mo uninstall <name> --dry-run  Preview uninstall");
+        outln!("  mo uninstall <name> --exact  Only match the exact package name");
+        outln!("  mo uninstall --from <file>  Uninstall every package listed in a file");
     }
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str) -> InstalledApp {
+        InstalledApp {
+            name: name.to_string(),
+            path: PathBuf::from("/usr"),
+            size: 0,
+            app_type: AppType::Deb,
+            leftovers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_pending_reclaim() {
+        assert_eq!(pending_reclaim(1000, 0), None);
+        assert_eq!(pending_reclaim(1000, 100), None);
+        assert_eq!(pending_reclaim(1000, 900), Some(900));
+        assert_eq!(pending_reclaim(0, 0), None);
+    }
+
+    #[test]
+    fn test_snap_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for name in ["vlc_3777.snap", "vlc_3721.snap", "vlc-player_1.snap", "vlc_1.partial"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let files = snap_files(dir.path(), "vlc");
+        assert_eq!(
+            files,
+            [dir.path().join("vlc_3721.snap"), dir.path().join("vlc_3777.snap")]
+        );
+        assert!(snap_files(&dir.path().join("missing"), "vlc").is_empty());
+    }
+
+    #[test]
+    fn test_parse_package_list() {
+        let list = "firefox\n\n# browsers\n  vlc  # media\ngimp\n";
+        assert_eq!(parse_package_list(list), vec!["firefox", "vlc", "gimp"]);
+    }
+
+    #[test]
+    fn test_match_apps_prefers_exact() {
+        let apps = vec![app("vlc"), app("vlc-data"), app("libvlc5"), app("Gimp"), app("gimp-data")];
+
+        let names = |m: Vec<&InstalledApp>| m.iter().map(|a| a.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(match_apps(&apps, "vlc", false)), vec!["vlc"]);
+        assert_eq!(names(match_apps(&apps, "gimp", false)), vec!["Gimp"]);
+        assert_eq!(names(match_apps(&apps, "data", false)), vec!["vlc-data", "gimp-data"]);
+        assert!(match_apps(&apps, "emacs", false).is_empty());
+        assert!(match_apps(&apps, "data", true).is_empty());
+    }
+
+    #[test]
+    fn test_find_apps_exact() {
+        let apps = vec![app("code"), app("codium"), app("codeblocks"), app("vscode")];
+
+        let names = |m: Vec<&InstalledApp>| m.iter().map(|a| a.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(find_apps(&apps, "Code", true)), vec!["code"]);
+        assert_eq!(
+            names(find_apps(&apps, "code", false)),
+            vec!["code", "codeblocks", "vscode"]
+        );
+    }
+
+    #[test]
+    fn test_parse_dpkg_output() {
+        let output = "bash\t1864\tii \ncoreutils\t7204\tii \n";
+        let apps = parse_dpkg_output(output);
+
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps[0].name, "bash");
+        assert_eq!(apps[0].size, 1864 * 1024);
+        assert_eq!(apps[1].app_type, AppType::Deb);
+    }
+
+    #[test]
+    fn test_parse_dpkg_output_malformed() {
+        let output = concat!(
+            "virtual-pkg\t\tii \n",
+            "weird-size\t<unknown>\tii \n",
+            "huge\t18446744073709551615\tii \n",
+            "removed\t2048\trc \n",
+            "\t\t\n",
+            "no-status\t10\n",
+        );
+        let apps = parse_dpkg_output(output);
+        let names: Vec<_> = apps.iter().map(|a| a.name.as_str()).collect();
+
+        assert_eq!(names, vec!["virtual-pkg", "weird-size", "huge", "no-status"]);
+        assert!(apps[..3].iter().all(|a| a.size == 0));
+        assert_eq!(apps[3].size, 10 * 1024);
+    }
+
+    #[test]
+    fn test_parse_residual_packages() {
+        let output = "bash\t1864\tii \nold-editor\t\trc \nhalf\t10\tiU \n\t\trc \nno-status\t10\n";
+
+        assert_eq!(parse_residual_packages(output), vec!["old-editor"]);
+    }
+
+    #[test]
+    fn test_parse_snap_output() {
+        let output = r#"Name               Version          Rev    Tracking         Publisher     Notes
+core22             20240111         1122   latest/stable    canonical✓    base
+firefox            122.0-2          3728   latest/stable/…  mozilla✓      -
+snapd              2.61.1           20671  latest/stable    canonical✓    snapd
+"#;
+        let apps = parse_snap_output(output);
+        let names: Vec<_> = apps.iter().map(|a| a.name.as_str()).collect();
+
+        assert_eq!(names, vec!["core22", "firefox", "snapd"]);
+        assert_eq!(apps[1].path, PathBuf::from("/snap/firefox"));
+        assert!(apps.iter().all(|a| a.app_type == AppType::Snap));
+    }
+
+    #[test]
+    fn test_parse_snap_output_header_only() {
+        assert!(parse_snap_output("No snaps are installed yet.\n").is_empty());
+        assert!(parse_snap_output("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_flatpak_output() {
+        let output = concat!(
+            "org.mozilla.firefox\tFirefox\t245.6\u{a0}MB\n",
+            "org.gimp.GIMP\tGNU Image Manipulation Program\t1.2 GB\n",
+            "com.example.NoName\t\t\n",
+            "garbage line\n",
+        );
+        let apps = parse_flatpak_output(output);
+
+        assert_eq!(apps.len(), 3);
+        assert_eq!(apps[0].name, "Firefox");
+        assert_eq!(apps[0].size, 245_600_000);
+        assert_eq!(apps[1].path, PathBuf::from("/var/lib/flatpak/app/org.gimp.GIMP"));
+        assert_eq!(apps[1].size, 1_200_000_000);
+        assert_eq!(apps[2].name, "com.example.NoName");
+        assert_eq!(apps[2].size, 0);
+    }
+
+    #[test]
+    fn test_matches_app_name_whole_tokens() {
+        assert!(matches_app_name("gimp", "GIMP"));
+        assert!(matches_app_name("gimp", "gimp-2.10"));
+        assert!(matches_app_name("gimp", "org.gimp.GIMP.desktop"));
+        assert!(matches_app_name("google-chrome", "google-chrome-beta"));
+    }
+
+    #[test]
+    fn test_matches_app_name_rejects_partials() {
+        assert!(!matches_app_name("gimp", "gimpy"));
+        assert!(!matches_app_name("gimp", "libgimpui"));
+        assert!(!matches_app_name("code", "vscode"));
+        assert!(!matches_app_name("google-chrome", "google-earth"));
+        assert!(!matches_app_name("", "anything"));
+    }
+
+    #[test]
+    fn test_find_leftovers_depth() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("vendor/gimp/plugins")).unwrap();
+        std::fs::create_dir_all(temp.path().join("gimpy")).unwrap();
+
+        let shallow = LeftoverSearch {
+            depth: 1,
+            paths: vec![temp.path().to_path_buf()],
+        };
+        assert!(find_leftovers("gimp", &shallow).is_empty());
+
+        let deep = LeftoverSearch { depth: 2, ..shallow };
+        let found = find_leftovers("gimp", &deep);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, temp.path().join("vendor/gimp"));
+    }
+}
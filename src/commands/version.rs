@@ -0,0 +1,60 @@
+//! Version command - build details for inventory tooling
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::core::distro::DistroInfo;
+use crate::core::json;
+use crate::sayln;
+
+/// What `mo --version-json` reports
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+    /// Short hash of the commit the binary was built from, or "unknown"
+    pub commit: &'static str,
+    /// UTC date of the build (YYYY-MM-DD), or "unknown"
+    pub build_date: &'static str,
+    pub distro: String,
+    pub distro_version: Option<String>,
+    pub package_manager: String,
+}
+
+impl VersionInfo {
+    pub fn new(distro: &DistroInfo) -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            commit: env!("MOLE_GIT_COMMIT"),
+            build_date: env!("MOLE_BUILD_DATE"),
+            distro: distro.distro.to_string(),
+            distro_version: distro.version.clone(),
+            package_manager: format!("{:?}", distro.package_manager),
+        }
+    }
+}
+
+/// Print the build and host details as JSON
+pub fn run_json() -> Result<()> {
+    let info = VersionInfo::new(&DistroInfo::detect());
+    sayln!("{}", json::to_string_pretty(&info)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_info_json() {
+        let info = VersionInfo::new(&DistroInfo::detect());
+        let rendered = json::to_string_pretty(&info).unwrap();
+
+        assert!(rendered.contains("\"name\": \"mole-rs\""));
+        assert!(rendered.contains(&format!("\"version\": \"{}\"", env!("CARGO_PKG_VERSION"))));
+        assert!(rendered.contains("\"commit\": "));
+        assert!(rendered.contains("\"package_manager\": "));
+        assert!(!info.build_date.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+//! Archive cleanup targets before they are deleted
+//!
+//! Backups are written with the system `tar` and its `--zstd` filter, so
+//! they open with any standard tooling.
+
+use chrono::Local;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::errors::{MoleError, Result};
+use super::filesystem::format_size;
+use super::output::confirm;
+
+/// Targets larger than this are not backed up; the archive would mostly
+/// eat the space the cleanup is meant to free
+pub const BACKUP_MAX_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// A written backup archive
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Why a backup of `total` bytes should be skipped
+pub fn backup_skip_reason(total: u64) -> Option<String> {
+    (total > BACKUP_MAX_SIZE).then(|| {
+        format!(
+            "targets exceed the {} backup limit",
+            super::filesystem::format_size(BACKUP_MAX_SIZE)
+        )
+    })
+}
+
+/// Free space that must remain after the archive is written, so a backup
+/// never fills the disk it is meant to relieve
+pub const MIN_FREE_RESERVE: u64 = 256 * 1024 * 1024;
+
+/// Bytes a backup of `total` bytes needs free before it starts
+///
+/// Compression usually shrinks caches a lot, but the worst case is an
+/// archive as large as its input, plus the reserve.
+pub fn backup_space_needed(total: u64) -> u64 {
+    total.saturating_add(MIN_FREE_RESERVE)
+}
+
+/// Refuse a backup of `total` bytes into `dir` if its filesystem can't hold it
+pub fn check_backup_space(dir: &Path, total: u64) -> Result<()> {
+    let Some(disk) = super::system::SystemInfo::new().disk_for_path(dir) else {
+        return Ok(());
+    };
+
+    let needed = backup_space_needed(total);
+    if needed > disk.available_space {
+        return Err(MoleError::InsufficientSpace {
+            mount: disk.mount_point,
+            needed,
+            available: disk.available_space,
+        });
+    }
+    Ok(())
+}
+
+/// Refuse a backup directory inside one of `targets`; the cleanup would
+/// delete the archive along with what it backs up
+///
+/// `dir` must be absolute. Symlinks are resolved where the paths exist.
+pub fn check_backup_dir<P: AsRef<Path>>(dir: &Path, targets: &[P]) -> Result<()> {
+    let resolve = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let dir = resolve(dir);
+
+    match targets
+        .iter()
+        .map(|t| t.as_ref())
+        .find(|target| dir.starts_with(target) || dir.starts_with(resolve(target)))
+    {
+        Some(target) => Err(MoleError::BackupInsideTarget {
+            dir: dir.display().to_string(),
+            target: target.display().to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Archive name for a backup started now
+pub fn archive_name() -> String {
+    format!("mole-backup-{}.tar.zst", Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Write `targets` into a new zstd-compressed tar under `dir`
+///
+/// Paths are stored relative to `/` so the archive extracts back into place
+/// with `tar -xf <archive> -C /`.
+pub fn create_backup<P: AsRef<Path>>(dir: &Path, targets: &[P]) -> Result<Backup> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(archive_name());
+
+    let mut tar = Command::new("tar");
+    tar.arg("--zstd").arg("-cf").arg(&path).arg("-C").arg("/");
+    for target in targets {
+        let target = target.as_ref();
+        tar.arg(target.strip_prefix("/").unwrap_or(target));
+    }
+
+    // Exit status 1 means files changed while being read, which is
+    // expected for live caches; anything else is a real failure
+    let output = tar.output().map_err(|e| MoleError::CommandFailed {
+        command: "tar".to_string(),
+        message: e.to_string(),
+    })?;
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        let _ = std::fs::remove_file(&path);
+        return Err(MoleError::CommandFailed {
+            command: "tar".to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    let size = std::fs::metadata(&path)?.len();
+    Ok(Backup { path, size })
+}
+
+/// Archive `targets` (`total` bytes) into `dir` before they are deleted
+///
+/// Returns whether deletion should go ahead. A backup skipped for size
+/// needs confirmation; a failed one always stops the deletion. Errors when
+/// `dir` lies inside a target or is too full to hold the archive, before
+/// anything is written.
+pub fn backup_before_delete<P: AsRef<Path>>(
+    dir: &Path,
+    targets: &[P],
+    total: u64,
+) -> Result<bool> {
+    let absolute = std::env::current_dir()
+        .map(|cwd| cwd.join(dir))
+        .unwrap_or_else(|_| dir.to_path_buf());
+    check_backup_dir(&absolute, targets)?;
+
+    if let Some(reason) = backup_skip_reason(total) {
+        crate::warnln!("{} Not backing up: {}.", "!".yellow(), reason);
+        return Ok(confirm("Delete without a backup?"));
+    }
+
+    check_backup_space(&absolute, total)?;
+
+    crate::outln!("{}", "Backing up...".dimmed());
+    match create_backup(&absolute, targets) {
+        Ok(backup) => {
+            crate::outln!(
+                "  {} Backup written to {} ({})",
+                "✓".green(),
+                backup.path.display().to_string().yellow(),
+                format_size(backup.size)
+            );
+            Ok(true)
+        }
+        Err(e) => {
+            crate::warnln!("{} Backup failed, nothing was deleted: {}", "✗".red(), e);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_skip_reason() {
+        let gib = 1024 * 1024 * 1024;
+
+        assert_eq!(backup_skip_reason(gib), None);
+        assert!(backup_skip_reason(5 * gib).is_some());
+    }
+
+    #[test]
+    fn test_check_backup_space() {
+        assert_eq!(backup_space_needed(0), MIN_FREE_RESERVE);
+        assert_eq!(backup_space_needed(u64::MAX), u64::MAX);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(check_backup_space(dir.path(), 1024).is_ok());
+
+        let err = check_backup_space(dir.path(), u64::MAX).unwrap_err();
+        assert!(matches!(err, MoleError::InsufficientSpace { .. }));
+        assert!(err.to_string().contains("nothing was deleted"));
+    }
+
+    #[test]
+    fn test_check_backup_dir() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        std::fs::create_dir(&cache).unwrap();
+
+        assert!(check_backup_dir(&temp.path().join("backups"), &[&cache]).is_ok());
+        assert!(check_backup_dir(&temp.path().join("cache2"), &[&cache]).is_ok());
+
+        let err = check_backup_dir(&cache.join("backups"), &[&cache]).unwrap_err();
+        assert!(matches!(err, MoleError::BackupInsideTarget { .. }));
+        assert!(err.to_string().contains("nothing was deleted"));
+        assert!(check_backup_dir(&cache, &[&cache]).is_err());
+
+        // Through a symlink to a target
+        let link = temp.path().join("link");
+        std::os::unix::fs::symlink(&cache, &link).unwrap();
+        assert!(check_backup_dir(&link, &[&cache]).is_err());
+    }
+
+    #[test]
+    fn test_create_backup() {
+        if !crate::core::distro::command_exists("zstd") {
+            return;
+        }
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let target = temp.path().join("cache");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("data"), vec![b'a'; 4096]).unwrap();
+
+        let backup = create_backup(&temp.path().join("backups"), &[&target]).unwrap();
+
+        assert!(backup.path.exists());
+        assert!(backup.size > 0);
+        assert!(backup.path.to_string_lossy().ends_with(".tar.zst"));
+    }
+}
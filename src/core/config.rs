@@ -1,14 +1,22 @@
 //! Configuration handling
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use super::filesystem::parse_size;
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// Paths to never delete
     pub whitelist: Vec<PathBuf>,
 
+    /// Clean categories (by name or path) listed but never selected by default
+    pub keep_warm: Vec<String>,
+
     /// Directories to scan for dev artifacts
     pub project_paths: Vec<PathBuf>,
 
@@ -17,6 +25,60 @@ pub struct Config {
 
     /// Maximum journal log size to keep
     pub journal_max_size: String,
+
+    /// Disk usage percentage above which browser caches are selected by default
+    pub high_water_mark: u8,
+
+    /// App logs under ~/.config and ~/.local/state older than this many days are removed by optimize
+    pub app_log_max_age_days: u32,
+
+    /// File name globs that count as app logs
+    pub app_log_patterns: Vec<String>,
+
+    /// Seconds a size scan may run before reporting partial results (0 = no limit)
+    pub scan_timeout_secs: u64,
+
+    /// Ask before clean/purge removes at least this many files
+    pub large_file_count: u64,
+
+    /// `mo status` flags a disk filled beyond this percentage
+    pub disk_alert_percent: u8,
+
+    /// `mo status` flags memory use beyond this percentage
+    pub memory_alert_percent: u8,
+
+    /// `mo status` flags CPU use beyond this percentage
+    pub cpu_alert_percent: u8,
+
+    /// Ring the terminal bell when the live status view raises a new alert
+    pub alert_bell: bool,
+
+    /// Which entries clean may remove from shared sticky-bit dirs like /tmp
+    pub tmp_policy: TmpPolicy,
+
+    /// With `tmp_policy = "old"`, other users' /tmp entries unused for this many days are removed
+    pub tmp_max_age_days: u32,
+
+    /// Newest automatic Snapper/Timeshift btrfs snapshots optimize keeps
+    pub btrfs_snapshot_keep: u32,
+
+    /// `mo clean --stale-downloads` lists Downloads files not modified for this many days
+    pub stale_download_days: u32,
+
+    /// ... and at least this large
+    pub stale_download_min_size: String,
+}
+
+/// What to remove from sticky-bit directories (`/tmp`, `/var/tmp`) shared
+/// between users
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TmpPolicy {
+    /// Only entries owned by the user running mo
+    #[default]
+    Mine,
+    /// Own entries plus other users' entries not used in `tmp_max_age_days`
+    Old,
 }
 
 impl Default for Config {
@@ -25,6 +87,7 @@ impl Default for Config {
 
         Self {
             whitelist: vec![],
+            keep_warm: vec![],
             project_paths: vec![
                 home.join("Projects"),
                 home.join("Development"),
@@ -34,24 +97,165 @@ impl Default for Config {
             ],
             skip_recent_days: 7,
             journal_max_size: "100M".to_string(),
+            high_water_mark: 85,
+            app_log_max_age_days: 14,
+            app_log_patterns: vec![
+                "*.log".to_string(),
+                "*.log.*".to_string(),
+                "*.old".to_string(),
+            ],
+            scan_timeout_secs: 60,
+            large_file_count: 50_000,
+            disk_alert_percent: 90,
+            memory_alert_percent: 90,
+            cpu_alert_percent: 95,
+            alert_bell: false,
+            tmp_policy: TmpPolicy::Mine,
+            tmp_max_age_days: 10,
+            btrfs_snapshot_keep: 5,
+            stale_download_days: 90,
+            stale_download_min_size: "100M".to_string(),
+        }
+    }
+}
+
+/// Profile chosen with `--profile`, read by every `Config::load`
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Set once an unreadable config was reported, since it is loaded many times
+static LOAD_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// How bad a problem found by `Config::check` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The setting is ignored or the whole file falls back to defaults
+    Error,
+    /// The setting is read but probably does not do what was meant
+    Warning,
+}
+
+/// A problem in a config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
         }
     }
 }
 
 impl Config {
     /// Load config from file or return defaults
+    ///
+    /// With a profile set, that profile's file is read instead of `config.toml`.
     pub fn load() -> Self {
-        let config_path = Self::config_path();
+        match PROFILE.get() {
+            Some(name) => Self::load_profile(name),
+            None => Self::load_from(&Self::config_path()),
+        }
+    }
+
+    /// Load `profiles/<name>.toml` from the config dir, or defaults if it's missing
+    pub fn load_profile(name: &str) -> Self {
+        Self::load_from(&Self::profile_path(name))
+    }
+
+    /// Read `config_path`, falling back to defaults when it is missing; a
+    /// file that cannot be read or parsed is reported on stderr (once)
+    fn load_from(config_path: &Path) -> Self {
+        if !config_path.exists() {
+            return Self::default();
+        }
 
-        if config_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&config_path) {
-                if let Ok(config) = toml::from_str(&content) {
-                    return config;
+        let loaded = std::fs::read_to_string(config_path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()));
+        match loaded {
+            Ok(config) => config,
+            Err(e) => {
+                // stderr, so JSON and CSV on stdout stay parseable
+                if !LOAD_WARNED.swap(true, Ordering::Relaxed) {
+                    eprintln!(
+                        "Ignoring {}, using defaults: {}\nRun `mo config check` for details.",
+                        config_path.display(),
+                        e.trim_end()
+                    );
                 }
+                Self::default()
+            }
+        }
+    }
+
+    /// Look for problems in config file `content`: syntax and type errors,
+    /// unknown keys, size strings that don't parse and paths that don't exist
+    pub fn check(content: &str) -> Vec<ConfigIssue> {
+        let table: toml::Table = match toml::from_str(content) {
+            Ok(table) => table,
+            Err(e) => return vec![ConfigIssue::error(e.to_string().trim_end().to_string())],
+        };
+
+        let known = toml::Table::try_from(Self::default()).unwrap_or_default();
+        let mut issues: Vec<ConfigIssue> = table
+            .keys()
+            .filter(|key| !known.contains_key(*key))
+            .map(|key| ConfigIssue::error(format!("unknown key `{}` is ignored", key)))
+            .collect();
+
+        let config: Self = match toml::from_str(content) {
+            Ok(config) => config,
+            Err(e) => {
+                issues.push(ConfigIssue::error(e.to_string().trim_end().to_string()));
+                return issues;
+            }
+        };
+
+        for (key, value) in [
+            ("journal_max_size", &config.journal_max_size),
+            ("stale_download_min_size", &config.stale_download_min_size),
+        ] {
+            if let Err(e) = parse_size(value) {
+                issues.push(ConfigIssue::error(format!("{}: {}", key, e)));
+            }
+        }
+
+        // Only paths written in the file; the default project dirs may well
+        // not exist
+        for (key, paths) in [
+            ("project_paths", &config.project_paths),
+            ("whitelist", &config.whitelist),
+        ] {
+            if !table.contains_key(key) {
+                continue;
+            }
+            for path in paths.iter().filter(|p| !p.exists()) {
+                let hint = if path.starts_with("~") {
+                    " (`~` is not expanded here; write the full path)"
+                } else {
+                    ""
+                };
+                issues.push(ConfigIssue::warning(format!(
+                    "{}: {} does not exist{}",
+                    key,
+                    path.display(),
+                    hint
+                )));
             }
         }
 
-        Self::default()
+        issues
     }
 
     /// Save config to file
@@ -66,11 +270,44 @@ impl Config {
         std::fs::write(config_path, content)
     }
 
-    /// Get config file path
+    /// Use the named profile for every later `load`; only the first call counts
+    pub fn set_profile(name: &str) -> Result<(), String> {
+        validate_profile_name(name)?;
+        let _ = PROFILE.set(name.to_string());
+        Ok(())
+    }
+
+    /// Get config file path, which is the active profile's file if one is set
     pub fn config_path() -> PathBuf {
+        match PROFILE.get() {
+            Some(name) => Self::profile_path(name),
+            None => Self::config_dir().join("config.toml"),
+        }
+    }
+
+    /// Path of a named profile's config file
+    pub fn profile_path(name: &str) -> PathBuf {
+        Self::config_dir().join("profiles").join(format!("{}.toml", name))
+    }
+
+    fn config_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("mole-rs")
-            .join("config.toml")
     }
 }
+
+/// Check that a profile name is a plain file name, so it can't reach
+/// outside the profiles directory
+pub fn validate_profile_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("profile name is empty".to_string());
+    }
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!(
+            "invalid profile name '{}': use a plain name like 'server'",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
@@ -0,0 +1,26 @@
+//! Minimal CSV output for spreadsheet exports
+//!
+//! Fields are quoted only when they contain a comma, a quote or a line
+//! break, with inner quotes doubled (RFC 4180). Rows end with `\n`.
+
+use std::borrow::Cow;
+
+/// Quote a field if it needs it
+pub fn field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// One CSV line, including the trailing newline
+pub fn row<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
@@ -65,20 +65,92 @@ impl PackageManager {
         }
     }
 
+    /// Get a gentler cache clean that only drops packages that can no longer
+    /// be downloaded or are not installed, keeping installable versions
+    pub fn autoclean_cmd(&self) -> Option<Vec<&'static str>> {
+        match self {
+            PackageManager::Apt => Some(vec!["apt-get", "autoclean"]),
+            PackageManager::Pacman => Some(vec!["pacman", "-Sc", "--noconfirm"]),
+            PackageManager::Apk => Some(vec!["apk", "cache", "clean"]),
+            PackageManager::Portage => Some(vec!["eclean-dist"]),
+            PackageManager::Dnf => None, // Only has all-or-nothing cleans
+            PackageManager::Yum => None,
+            PackageManager::Zypper => None,
+            PackageManager::Unknown => None,
+        }
+    }
+
     /// Get the autoremove command for this package manager
+    ///
+    /// Pacman and zypper have no single command for this; their orphans are
+    /// listed with `orphans_query_cmd` and removed with `remove_orphans_cmd`.
     pub fn autoremove_cmd(&self) -> Option<Vec<&'static str>> {
         match self {
             PackageManager::Apt => Some(vec!["apt-get", "autoremove", "-y"]),
             PackageManager::Dnf => Some(vec!["dnf", "autoremove", "-y"]),
             PackageManager::Yum => Some(vec!["yum", "autoremove", "-y"]),
-            PackageManager::Pacman => Some(vec!["pacman", "-Rns", "$(pacman -Qdtq)", "--noconfirm"]),
-            PackageManager::Zypper => None, // No direct equivalent
+            PackageManager::Pacman => None, // See orphans_query_cmd
+            PackageManager::Zypper => None,
             PackageManager::Apk => None,
             PackageManager::Portage => Some(vec!["emerge", "--depclean"]),
             PackageManager::Unknown => None,
         }
     }
 
+    /// Get the command that lists orphaned packages, for package managers
+    /// whose removal needs the names passed explicitly
+    pub fn orphans_query_cmd(&self) -> Option<Vec<&'static str>> {
+        match self {
+            PackageManager::Pacman => Some(vec!["pacman", "-Qtdq"]),
+            // `--orphaned` would list packages without a repository instead
+            PackageManager::Zypper => Some(vec!["zypper", "--quiet", "packages", "--unneeded"]),
+            _ => None,
+        }
+    }
+
+    /// Extract package names from the output of `orphans_query_cmd`
+    pub fn parse_orphans(&self, output: &str) -> Vec<String> {
+        match self {
+            // One name per line
+            PackageManager::Pacman => output
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect(),
+            // Table rows: `i  | @System | name | version | arch`; `i+` marks
+            // packages the user installed, which are never orphans
+            PackageManager::Zypper => output
+                .lines()
+                .filter_map(|line| {
+                    let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+                    match fields.as_slice() {
+                        [status, _, name, ..] if *status == "i" && !name.is_empty() => {
+                            Some(name.to_string())
+                        }
+                        _ => None,
+                    }
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the command that removes the given orphaned packages
+    pub fn remove_orphans_cmd(&self, names: &[String]) -> Option<Vec<String>> {
+        let base: &[&str] = match self {
+            PackageManager::Pacman => &["pacman", "-Rns", "--noconfirm"],
+            PackageManager::Zypper => &["zypper", "--non-interactive", "remove", "--clean-deps"],
+            _ => return None,
+        };
+
+        if names.is_empty() {
+            return None;
+        }
+
+        Some(base.iter().map(|s| s.to_string()).chain(names.iter().cloned()).collect())
+    }
+
     /// Get the list installed packages command
     pub fn list_packages_cmd(&self) -> Option<Vec<&'static str>> {
         match self {
@@ -107,11 +179,22 @@ impl PackageManager {
     }
 }
 
+/// Fields of interest from an os-release file
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsRelease {
+    pub distro: Distro,
+    pub version: Option<String>,
+    /// Distros this one derives from (`ID_LIKE`), most specific first
+    pub id_like: Vec<String>,
+}
+
 /// System information including distro and package manager
 #[derive(Debug, Clone)]
 pub struct DistroInfo {
     pub distro: Distro,
     pub version: Option<String>,
+    /// Parent distros from `ID_LIKE`, e.g. `["ubuntu", "debian"]` on Pop!_OS
+    pub id_like: Vec<String>,
     pub package_manager: PackageManager,
     pub has_snap: bool,
     pub has_flatpak: bool,
@@ -120,14 +203,19 @@ pub struct DistroInfo {
 impl DistroInfo {
     /// Detect the current Linux distribution
     pub fn detect() -> Self {
-        let (distro, version) = detect_distro();
-        let package_manager = detect_package_manager(&distro);
+        let OsRelease {
+            distro,
+            version,
+            id_like,
+        } = detect_distro();
+        let package_manager = detect_package_manager(&distro, &id_like);
         let has_snap = command_exists("snap");
         let has_flatpak = command_exists("flatpak");
 
         Self {
             distro,
             version,
+            id_like,
             package_manager,
             has_snap,
             has_flatpak,
@@ -137,6 +225,7 @@ impl DistroInfo {
     /// Check if this is a Debian-based distro
     pub fn is_debian_based(&self) -> bool {
         matches!(self.distro, Distro::Ubuntu | Distro::Debian)
+            || self.id_like.iter().any(|id| id == "ubuntu" || id == "debian")
     }
 
     /// Check if this is a Red Hat-based distro
@@ -150,13 +239,26 @@ impl DistroInfo {
     }
 }
 
-/// Detect the Linux distribution from /etc/os-release
-fn detect_distro() -> (Distro, Option<String>) {
-    // Try /etc/os-release first (most modern distros)
-    if let Ok(content) = fs::read_to_string("/etc/os-release") {
-        return parse_os_release(&content);
+/// Detect the Linux distribution from os-release
+fn detect_distro() -> OsRelease {
+    // Try os-release first (most modern distros). /usr/lib/os-release is the
+    // canonical copy on image-based systems where /etc may not carry one.
+    for path in ["/etc/os-release", "/usr/lib/os-release"] {
+        if let Ok(content) = fs::read_to_string(path) {
+            return parse_os_release(&content);
+        }
+    }
+
+    let (distro, version) = detect_distro_legacy();
+    OsRelease {
+        distro,
+        version,
+        id_like: Vec::new(),
     }
+}
 
+/// Detect the distribution on systems without an os-release file
+fn detect_distro_legacy() -> (Distro, Option<String>) {
     // Fallback to /etc/lsb-release (older Ubuntu)
     if let Ok(content) = fs::read_to_string("/etc/lsb-release") {
         if content.contains("Ubuntu") {
@@ -179,10 +281,13 @@ fn detect_distro() -> (Distro, Option<String>) {
     (Distro::Unknown("Linux".to_string()), None)
 }
 
-/// Parse /etc/os-release content
-fn parse_os_release(content: &str) -> (Distro, Option<String>) {
+/// Parse os-release content
+fn parse_os_release(content: &str) -> OsRelease {
     let id = extract_value(content, "ID").unwrap_or_default().to_lowercase();
     let version = extract_value(content, "VERSION_ID");
+    let id_like = extract_value(content, "ID_LIKE")
+        .map(|v| v.split_whitespace().map(|id| id.to_lowercase()).collect())
+        .unwrap_or_default();
 
     let distro = match id.as_str() {
         "ubuntu" => Distro::Ubuntu,
@@ -201,23 +306,23 @@ fn parse_os_release(content: &str) -> (Distro, Option<String>) {
         }
     };
 
-    (distro, version)
+    OsRelease {
+        distro,
+        version,
+        id_like,
+    }
 }
 
 /// Extract a value from key=value format
 fn extract_value(content: &str, key: &str) -> Option<String> {
-    for line in content.lines() {
-        if line.starts_with(key) {
-            if let Some(value) = line.split('=').nth(1) {
-                return Some(value.trim_matches('"').to_string());
-            }
-        }
-    }
-    None
+    content.lines().find_map(|line| {
+        let value = line.trim().strip_prefix(key)?.strip_prefix('=')?;
+        Some(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+    })
 }
 
-/// Detect the package manager based on distro or available commands
-fn detect_package_manager(distro: &Distro) -> PackageManager {
+/// Detect the package manager based on distro, its parents or available commands
+fn detect_package_manager(distro: &Distro, id_like: &[String]) -> PackageManager {
     match distro {
         Distro::Ubuntu | Distro::Debian => PackageManager::Apt,
         Distro::Fedora => PackageManager::Dnf,
@@ -233,8 +338,11 @@ fn detect_package_manager(distro: &Distro) -> PackageManager {
         Distro::Alpine => PackageManager::Apk,
         Distro::Gentoo => PackageManager::Portage,
         Distro::Unknown(_) => {
-            // Try to detect based on available commands
-            if command_exists("apt-get") {
+            // Derivatives name their parents in ID_LIKE; otherwise try to
+            // detect based on available commands
+            if let Some(manager) = id_like.iter().find_map(|id| family_package_manager(id)) {
+                manager
+            } else if command_exists("apt-get") {
                 PackageManager::Apt
             } else if command_exists("dnf") {
                 PackageManager::Dnf
@@ -253,6 +361,19 @@ fn detect_package_manager(distro: &Distro) -> PackageManager {
     }
 }
 
+/// Package manager used by a distro family named in `ID_LIKE`
+fn family_package_manager(id: &str) -> Option<PackageManager> {
+    match id {
+        "ubuntu" | "debian" => Some(PackageManager::Apt),
+        "fedora" | "rhel" | "centos" => Some(PackageManager::Dnf),
+        "arch" => Some(PackageManager::Pacman),
+        "suse" | "opensuse" => Some(PackageManager::Zypper),
+        "alpine" => Some(PackageManager::Apk),
+        "gentoo" => Some(PackageManager::Portage),
+        _ => None,
+    }
+}
+
 /// Check if a command exists
 pub fn command_exists(cmd: &str) -> bool {
     std::process::Command::new("which")
@@ -281,9 +402,9 @@ VERSION="22.04.3 LTS (Jammy Jellyfish)"
 ID=ubuntu
 VERSION_ID="22.04"
 "#;
-        let (distro, version) = parse_os_release(content);
-        assert_eq!(distro, Distro::Ubuntu);
-        assert_eq!(version, Some("22.04".to_string()));
+        let release = parse_os_release(content);
+        assert_eq!(release.distro, Distro::Ubuntu);
+        assert_eq!(release.version, Some("22.04".to_string()));
     }
 
     #[test]
@@ -294,9 +415,58 @@ VERSION="39 (Workstation Edition)"
 ID=fedora
 VERSION_ID=39
 "#;
-        let (distro, version) = parse_os_release(content);
-        assert_eq!(distro, Distro::Fedora);
-        assert_eq!(version, Some("39".to_string()));
+        let release = parse_os_release(content);
+        assert_eq!(release.distro, Distro::Fedora);
+        assert_eq!(release.version, Some("39".to_string()));
+    }
+
+    #[test]
+    fn test_parse_pop_os_release() {
+        let content = r#"
+NAME="Pop!_OS"
+VERSION="22.04 LTS"
+ID=pop
+ID_LIKE="ubuntu debian"
+PRETTY_NAME="Pop!_OS 22.04 LTS"
+VERSION_ID="22.04"
+"#;
+        let release = parse_os_release(content);
+        assert_eq!(release.distro, Distro::Unknown("Pop!_OS".to_string()));
+        assert_eq!(release.version, Some("22.04".to_string()));
+        assert_eq!(release.id_like, vec!["ubuntu", "debian"]);
+        assert_eq!(
+            detect_package_manager(&release.distro, &release.id_like),
+            PackageManager::Apt
+        );
+    }
+
+    #[test]
+    fn test_id_like_package_manager_fallback() {
+        let cases = [
+            ("ID=linuxmint\nNAME=\"Linux Mint\"\nID_LIKE=\"ubuntu debian\"", PackageManager::Apt),
+            ("ID=zorin\nNAME=\"Zorin OS\"\nID_LIKE=\"ubuntu\"", PackageManager::Apt),
+            ("ID=endeavouros\nNAME=\"EndeavourOS\"\nID_LIKE=arch", PackageManager::Pacman),
+            ("ID=garuda\nNAME=\"Garuda Linux\"\nID_LIKE=arch", PackageManager::Pacman),
+            ("ID=rocky\nNAME=\"Rocky Linux\"\nID_LIKE=\"rhel centos fedora\"", PackageManager::Dnf),
+            ("ID=opensuse-microos\nNAME=\"openSUSE MicroOS\"\nID_LIKE=\"suse opensuse opensuse-tumbleweed\"", PackageManager::Zypper),
+        ];
+
+        for (content, expected) in cases {
+            let release = parse_os_release(content);
+            assert_eq!(
+                detect_package_manager(&release.distro, &release.id_like),
+                expected,
+                "{}",
+                release.distro
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_value_exact_key() {
+        let content = "ID_LIKE=debian\nID=pop\nVERSION_ID=\"1\"\nVERSION=\"1 (x=y)\"";
+        assert_eq!(extract_value(content, "ID"), Some("pop".to_string()));
+        assert_eq!(extract_value(content, "VERSION"), Some("1 (x=y)".to_string()));
     }
 
     #[test]
@@ -308,4 +478,68 @@ VERSION_ID=39
         let dnf = PackageManager::Dnf;
         assert!(dnf.clean_cache_cmd().is_some());
     }
+
+    #[test]
+    fn test_autoremove_cmd_needs_no_shell() {
+        let managers = [
+            PackageManager::Apt,
+            PackageManager::Dnf,
+            PackageManager::Yum,
+            PackageManager::Pacman,
+            PackageManager::Zypper,
+            PackageManager::Apk,
+            PackageManager::Portage,
+        ];
+
+        for manager in managers {
+            let cmd = manager.autoremove_cmd().unwrap_or_default();
+            assert!(!cmd.iter().any(|arg| arg.contains('$')), "{:?}", manager);
+        }
+    }
+
+    #[test]
+    fn test_parse_pacman_orphans() {
+        let pacman = PackageManager::Pacman;
+        assert_eq!(pacman.parse_orphans("libfoo\npython-bar\n\n"), vec!["libfoo", "python-bar"]);
+        assert!(pacman.parse_orphans("").is_empty());
+
+        assert_eq!(
+            pacman.remove_orphans_cmd(&["libfoo".to_string()]),
+            Some(vec!["pacman", "-Rns", "--noconfirm", "libfoo"].into_iter().map(String::from).collect())
+        );
+        assert_eq!(pacman.remove_orphans_cmd(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_zypper_orphans() {
+        let output = "\
+S  | Repository | Name          | Version    | Arch
+---+------------+---------------+------------+-------
+i  | @System    | libfoo1       | 1.0-1.1    | x86_64
+i+ | @System    | wanted-tool   | 2.3-4.5    | noarch
+i  | @System    | python3-dep   | 0.9-2.1    | noarch
+";
+        assert_eq!(
+            PackageManager::Zypper.parse_orphans(output),
+            vec!["libfoo1", "python3-dep"]
+        );
+        assert_eq!(
+            PackageManager::Zypper.orphans_query_cmd(),
+            Some(vec!["zypper", "--quiet", "packages", "--unneeded"])
+        );
+        assert!(PackageManager::Apt.orphans_query_cmd().is_none());
+    }
+
+    #[test]
+    fn test_autoclean_cmd() {
+        assert_eq!(
+            PackageManager::Apt.autoclean_cmd(),
+            Some(vec!["apt-get", "autoclean"])
+        );
+        assert_ne!(
+            PackageManager::Apt.autoclean_cmd(),
+            PackageManager::Apt.clean_cache_cmd()
+        );
+        assert!(PackageManager::Dnf.autoclean_cmd().is_none());
+    }
 }
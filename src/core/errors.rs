@@ -2,6 +2,8 @@
 
 use thiserror::Error;
 
+use super::filesystem::format_size;
+
 pub type Result<T> = std::result::Result<T, MoleError>;
 
 #[derive(Error, Debug)]
@@ -27,6 +29,26 @@ pub enum MoleError {
     #[error("Command failed: {command} - {message}")]
     CommandFailed { command: String, message: String },
 
+    #[error(
+        "Not enough free space on {mount} for the backup: needs {}, {} available; nothing was deleted",
+        format_size(*.needed),
+        format_size(*.available)
+    )]
+    InsufficientSpace {
+        mount: String,
+        needed: u64,
+        available: u64,
+    },
+
+    #[error("Backup directory {dir} is inside {target}, which is being cleaned; nothing was deleted")]
+    BackupInsideTarget { dir: String, target: String },
+
+    #[error("Another mole-rs operation is running; wait for it or pass --wait")]
+    AlreadyRunning,
+
+    #[error("{flag} needs a terminal to ask on; nothing was deleted")]
+    NotInteractive { flag: &'static str },
+
     #[error("{0}")]
     Other(String),
 }
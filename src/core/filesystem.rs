@@ -1,29 +1,300 @@
 //! Filesystem operations with safety checks
 
+use crate::core::config::{Config, TmpPolicy};
 use crate::core::errors::{MoleError, Result};
-use crate::core::security::{SecurityValidator, PathValidation};
-use std::path::Path;
+use crate::core::glob;
+use crate::core::interrupt::interrupted;
+use crate::core::security::{find_mount, mountpoints, SecurityValidator, PathValidation};
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use walkdir::WalkDir;
 
+/// How file sizes are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeMode {
+    /// Logical file length, like `du --apparent-size` (default)
+    Apparent,
+    /// Blocks actually allocated on disk, which matches `df` deltas on
+    /// compressed (Btrfs/ZFS) and sparse files
+    DiskUsage,
+}
+
+impl SizeMode {
+    /// Size of a file according to this mode
+    pub fn measure(self, metadata: &Metadata) -> u64 {
+        match self {
+            SizeMode::Apparent => metadata.len(),
+            SizeMode::DiskUsage => metadata.blocks() * 512,
+        }
+    }
+}
+
+static DISK_USAGE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Set the size mode used by every size calculation in this process
+pub fn set_size_mode(mode: SizeMode) {
+    DISK_USAGE_MODE.store(mode == SizeMode::DiskUsage, Ordering::Relaxed);
+}
+
+/// Get the process-wide size mode
+pub fn size_mode() -> SizeMode {
+    if DISK_USAGE_MODE.load(Ordering::Relaxed) {
+        SizeMode::DiskUsage
+    } else {
+        SizeMode::Apparent
+    }
+}
+
+/// Size of a single file according to the process-wide size mode
+pub fn file_size(metadata: &Metadata) -> u64 {
+    size_mode().measure(metadata)
+}
+
+static SCAN_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(60);
+static SCAN_STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+static SCAN_TIMED_OUT: AtomicBool = AtomicBool::new(false);
+
+/// Set how long a scan may walk before giving up (zero disables the limit)
+pub fn set_scan_timeout(timeout: Duration) {
+    SCAN_TIMEOUT_SECS.store(timeout.as_secs(), Ordering::Relaxed);
+}
+
+pub fn scan_timeout() -> Duration {
+    Duration::from_secs(SCAN_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
+/// A running scan's clock, stopped when dropped
+#[must_use = "the scan ends when this is dropped"]
+pub struct ScanClock {
+    /// Whether this scan started the clock, rather than running inside
+    /// another one and sharing its deadline
+    owner: bool,
+}
+
+impl Drop for ScanClock {
+    fn drop(&mut self) {
+        if self.owner {
+            *SCAN_STARTED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+}
+
+/// Start the clock for a scan; every walk until the returned clock is
+/// dropped shares the same deadline
+///
+/// Inside a scan that is already running, the outer deadline is kept.
+pub fn begin_scan() -> ScanClock {
+    let mut started = SCAN_STARTED.lock().unwrap_or_else(|e| e.into_inner());
+    if started.is_some() {
+        return ScanClock { owner: false };
+    }
+    *started = Some(Instant::now());
+    SCAN_TIMED_OUT.store(false, Ordering::Relaxed);
+    ScanClock { owner: true }
+}
+
+/// Check whether the current scan ran past its deadline
+///
+/// Walks call this periodically and stop early once it returns true, so a
+/// hung network mount yields partial sizes instead of freezing the tool.
+/// Outside a scan nothing expires.
+pub fn scan_expired() -> bool {
+    let started = *SCAN_STARTED.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(started) = started else {
+        return false;
+    };
+    if SCAN_TIMED_OUT.load(Ordering::Relaxed) {
+        return true;
+    }
+
+    let timeout = scan_timeout();
+    let expired = !timeout.is_zero() && started.elapsed() >= timeout;
+
+    if expired {
+        SCAN_TIMED_OUT.store(true, Ordering::Relaxed);
+    }
+    expired
+}
+
+/// Whether the last scan was cut short by the timeout
+pub fn scan_timed_out() -> bool {
+    SCAN_TIMED_OUT.load(Ordering::Relaxed)
+}
+
+/// Size of a tree along with how much of it could not be read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirSize {
+    pub bytes: u64,
+    /// Entries skipped because of permission errors; `bytes` is a lower bound when non-zero
+    pub denied: usize,
+}
+
+/// Which parts of a tree a size walk counts
+#[derive(Debug, Clone, Default)]
+pub struct ScanFilter {
+    /// Only count files whose name matches one of these globs (all when empty)
+    pub include: Vec<String>,
+    /// Skip entries whose name or full path matches one of these globs,
+    /// without descending into them
+    pub exclude: Vec<String>,
+    /// Walk into symlinked directories and count symlinked files' targets
+    ///
+    /// Each symlinked directory is entered once per walk, but a link to a
+    /// directory that is also reached directly is counted twice.
+    pub follow_links: bool,
+}
+
+impl ScanFilter {
+    /// Whether a file with this name is counted
+    pub fn includes(&self, name: &str) -> bool {
+        self.include.is_empty() || glob::matches_any(&self.include, name)
+    }
+
+    /// Whether `path` is pruned from the walk
+    pub fn excludes(&self, path: &Path) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        glob::matches_any(&self.exclude, &name)
+            || glob::matches_any(&self.exclude, &path.to_string_lossy())
+    }
+}
+
 /// Calculate the size of a directory recursively
 pub fn dir_size(path: &Path) -> Result<u64> {
+    Ok(walk_size(path, false, &ScanFilter::default(), true).bytes)
+}
+
+/// Calculate the size of a directory, counting entries that could not be read
+///
+/// Only what `filter` lets through is counted. Excluded subtrees are not
+/// walked at all; included-only files still need the whole tree walked.
+pub fn measure_dir(path: &Path, filter: &ScanFilter) -> DirSize {
+    walk_size(path, false, filter, true)
+}
+
+/// Calculate the size of a directory, counting hardlinked files once
+///
+/// Files sharing a (device, inode) pair are the same bytes on disk, so this
+/// matches the space actually freed by deleting the tree much more closely
+/// than `dir_size` for hardlink-heavy stores such as pnpm and npm caches.
+pub fn dir_size_dedup(path: &Path) -> Result<u64> {
+    Ok(walk_size(path, true, &ScanFilter::default(), true).bytes)
+}
+
+/// With `bounded`, the walk stops early once the running scan expires
+fn walk_size(path: &Path, dedup_hardlinks: bool, filter: &ScanFilter, bounded: bool) -> DirSize {
+    let mut size = DirSize::default();
     if !path.exists() {
-        return Ok(0);
+        return size;
     }
 
-    let mut total = 0u64;
+    let mut seen = HashSet::new();
+    let mut visited_links = HashSet::new();
 
-    for entry in WalkDir::new(path)
-        .follow_links(false)
+    let mut walker = WalkDir::new(path)
+        .follow_links(filter.follow_links)
         .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if entry.file_type().is_file() {
-            total += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        .filter_entry(|e| e.depth() == 0 || !filter.excludes(e.path()));
+
+    while let Some(entry) = walker.next() {
+        if bounded && scan_expired() {
+            break;
+        }
+
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                if is_permission_denied(&e) {
+                    size.denied += 1;
+                }
+                continue;
+            }
+        };
+
+        // walkdir only catches links back to an ancestor; also skip
+        // symlinked directories whose target this walk already entered
+        if entry.path_is_symlink() && entry.file_type().is_dir() {
+            let target = std::fs::canonicalize(entry.path()).unwrap_or_default();
+            if !visited_links.insert(target) {
+                walker.skip_current_dir();
+                continue;
+            }
+        }
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if !filter.includes(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        if dedup_hardlinks && metadata.nlink() > 1 && !seen.insert((metadata.dev(), metadata.ino())) {
+            continue;
         }
+
+        size.bytes += file_size(&metadata);
     }
 
-    Ok(total)
+    size
+}
+
+/// Files under `root` that `filter` counts, for reports that look at each
+/// file rather than a total
+///
+/// These are the files `measure_dir` sizes: excluded subtrees are pruned,
+/// only included names are kept and symlinked directories are entered the
+/// same way. The walk ends once the running scan expires.
+pub fn filtered_files<'a>(
+    root: &Path,
+    filter: &'a ScanFilter,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+    let mut walker = WalkDir::new(root)
+        .follow_links(filter.follow_links)
+        .into_iter()
+        .filter_entry(move |e| e.depth() == 0 || !filter.excludes(e.path()));
+    let mut visited_links = HashSet::new();
+
+    std::iter::from_fn(move || loop {
+        if scan_expired() {
+            return None;
+        }
+        let Ok(entry) = walker.next()? else {
+            continue;
+        };
+
+        if entry.path_is_symlink() && entry.file_type().is_dir() {
+            let target = std::fs::canonicalize(entry.path()).unwrap_or_default();
+            if !visited_links.insert(target) {
+                walker.skip_current_dir();
+                continue;
+            }
+        }
+
+        if entry.file_type().is_file() && filter.includes(&entry.file_name().to_string_lossy()) {
+            return Some(entry);
+        }
+    })
+}
+
+fn is_permission_denied(err: &walkdir::Error) -> bool {
+    err.io_error()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::PermissionDenied)
 }
 
 /// Format bytes into human-readable string
@@ -31,6 +302,31 @@ pub fn format_size(bytes: u64) -> String {
     humansize::format_size(bytes, humansize::BINARY)
 }
 
+/// Parse a size such as `10G`, `512MiB`, `1.5GB` or `4096` into bytes
+///
+/// Units are binary (`K` = 1024) whether or not they carry the `i`.
+pub fn parse_size(input: &str) -> std::result::Result<u64, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size '{}'", input))?;
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return Err(format!("unknown size unit in '{}' (use K, M, G or T)", input)),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
 /// Check if we have permission to delete a path
 pub fn can_delete(path: &Path) -> bool {
     if !path.exists() {
@@ -90,7 +386,16 @@ pub fn safe_delete(path: &Path, dry_run: bool) -> Result<u64> {
         return Ok(0);
     }
 
-    let size = dir_size(path)?;
+    if path.is_dir() {
+        if let Some(mount) = find_mount(path, &mountpoints()) {
+            return Err(MoleError::PermissionDenied {
+                path: format!("{}: filesystem mounted at {}", path.display(), mount.display()),
+            });
+        }
+    }
+
+    // Always the full size: what is freed must not depend on a scan clock
+    let size = walk_size(path, false, &ScanFilter::default(), false).bytes;
 
     // Check for large deletion
     if validator.is_large_deletion(size) && !dry_run {
@@ -130,10 +435,87 @@ pub fn safe_delete(path: &Path, dry_run: bool) -> Result<u64> {
     Ok(size)
 }
 
+/// An entry cleanup left alone, and why
+#[derive(Debug, Clone)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Outcome of cleaning a directory
+#[derive(Debug, Default)]
+pub struct DeletionReport {
+    /// Bytes freed (or that would be freed in a dry run)
+    pub freed: u64,
+    /// Entries that were protected and not touched
+    pub skipped: Vec<SkippedEntry>,
+    /// Top-level entries deleted (or that would be) and their sizes
+    pub entries: Vec<(PathBuf, u64)>,
+    /// A signal stopped the cleanup before every entry was handled
+    pub interrupted: bool,
+}
+
+/// Whether an entry of a sticky-bit directory must be left alone, and why
+///
+/// Other users' files in `/tmp` may belong to a live session, so they are
+/// only removed under `TmpPolicy::Old` once unused for `max_age_days`.
+pub fn sticky_skip_reason(
+    owner: u32,
+    me: u32,
+    idle_days: u64,
+    policy: TmpPolicy,
+    max_age_days: u32,
+) -> Option<String> {
+    if owner == me {
+        return None;
+    }
+
+    match policy {
+        TmpPolicy::Mine => Some(format!("Owned by another user (uid {})", owner)),
+        TmpPolicy::Old if idle_days < u64::from(max_age_days) => Some(format!(
+            "Owned by another user (uid {}), used within {} days",
+            owner, max_age_days
+        )),
+        TmpPolicy::Old => None,
+    }
+}
+
+/// Days since an entry was last read or written
+fn idle_days(metadata: &Metadata) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let last_used = metadata.atime().max(metadata.mtime());
+
+    (now - last_used).max(0) as u64 / 86400
+}
+
 /// Delete contents of a directory but keep the directory itself
-pub fn clean_directory(path: &Path, dry_run: bool) -> Result<u64> {
+///
+/// In a sticky-bit directory such as `/tmp`, entries owned by other users
+/// are kept according to the configured `tmp_policy`.
+pub fn clean_directory(path: &Path, dry_run: bool) -> Result<DeletionReport> {
+    clean_directory_keeping(path, dry_run, false, &[])
+}
+
+/// Like `clean_directory`, but leave each path in `keep` in place, emptying
+/// the directories that lead to it instead of removing them
+///
+/// With `files_only`, only files and symlinks are deleted, leaving the whole
+/// directory tree in place for apps that expect their cache layout. Entries
+/// in the report are still the top-level ones, with the bytes freed below
+/// each.
+pub fn clean_directory_keeping(
+    path: &Path,
+    dry_run: bool,
+    files_only: bool,
+    keep: &[PathBuf],
+) -> Result<DeletionReport> {
+    let mut report = DeletionReport::default();
+
     if !path.exists() || !path.is_dir() {
-        return Ok(0);
+        return Ok(report);
     }
 
     // Validate the parent directory first
@@ -147,35 +529,146 @@ pub fn clean_directory(path: &Path, dry_run: bool) -> Result<u64> {
         _ => {}
     }
 
-    let mut total_freed = 0u64;
+    let mounts = mountpoints();
+    let sticky = std::fs::metadata(path).is_ok_and(|m| m.mode() & 0o1000 != 0);
+    let config = if sticky { Config::load() } else { Config::default() };
+    let me = unsafe { libc::geteuid() };
 
     for entry in std::fs::read_dir(path)? {
+        if !dry_run && interrupted() {
+            report.interrupted = true;
+            break;
+        }
+
         let entry = entry?;
         let entry_path = entry.path();
-        
+
+        if sticky {
+            let reason = entry.metadata().ok().and_then(|m| {
+                sticky_skip_reason(m.uid(), me, idle_days(&m), config.tmp_policy, config.tmp_max_age_days)
+            });
+            if let Some(reason) = reason {
+                tracing::debug!("Skipping {}: {}", entry_path.display(), reason);
+                report.skipped.push(SkippedEntry {
+                    path: entry_path,
+                    reason,
+                });
+                continue;
+            }
+        }
+
+        // Never recurse into another filesystem mounted inside the target
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            if let Some(mount) = find_mount(&entry_path, &mounts) {
+                tracing::debug!("Skipping mounted filesystem: {}", mount.display());
+                report.skipped.push(SkippedEntry {
+                    path: entry_path,
+                    reason: format!("Filesystem mounted at {}", mount.display()),
+                });
+                continue;
+            }
+        }
+
+        if keep.contains(&entry_path) {
+            report.skipped.push(SkippedEntry {
+                path: entry_path,
+                reason: "Kept warm (keep_warm)".to_string(),
+            });
+            continue;
+        }
+
         // Validate each entry before deletion
         match validator.validate_path(&entry_path) {
+            PathValidation::Safe | PathValidation::Caution { .. }
+                if entry.file_type().is_ok_and(|t| t.is_dir())
+                    && keep.iter().any(|k| k.starts_with(&entry_path)) =>
+            {
+                let inner = clean_directory_keeping(&entry_path, dry_run, files_only, keep)?;
+                report.freed += inner.freed;
+                report.entries.extend(inner.entries);
+                report.skipped.extend(inner.skipped);
+                if inner.interrupted {
+                    report.interrupted = true;
+                    break;
+                }
+            }
             PathValidation::Safe | PathValidation::Caution { .. } => {
-                total_freed += safe_delete(&entry_path, dry_run)?;
+                let size = if files_only && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    delete_files_below(&entry_path, dry_run)?
+                } else {
+                    safe_delete(&entry_path, dry_run)?
+                };
+                report.freed += size;
+                report.entries.push((entry_path, size));
             }
             PathValidation::Blocked { reason } => {
                 tracing::debug!("Skipping blocked path: {} - {}", entry_path.display(), reason);
+                report.skipped.push(SkippedEntry {
+                    path: entry_path,
+                    reason,
+                });
             }
             PathValidation::Symlink { target } => {
                 // Skip symlinks to protected paths
                 if let PathValidation::Blocked { .. } = validator.validate_path(&target) {
                     tracing::debug!("Skipping symlink to protected path: {}", entry_path.display());
+                    report.skipped.push(SkippedEntry {
+                        path: entry_path,
+                        reason: format!("Symlink to protected path {}", target.display()),
+                    });
                     continue;
                 }
-                total_freed += safe_delete(&entry_path, dry_run)?;
+                let size = safe_delete(&entry_path, dry_run)?;
+                report.freed += size;
+                report.entries.push((entry_path, size));
             }
             PathValidation::Invalid { reason } => {
                 tracing::debug!("Skipping invalid path: {} - {}", entry_path.display(), reason);
+                report.skipped.push(SkippedEntry {
+                    path: entry_path,
+                    reason,
+                });
             }
         }
     }
 
-    Ok(total_freed)
+    Ok(report)
+}
+
+/// Delete every file and symlink under `dir`, keeping `dir` and each
+/// directory below it
+///
+/// Filesystems mounted inside are not entered.
+fn delete_files_below(dir: &Path, dry_run: bool) -> Result<u64> {
+    let mut freed = 0;
+
+    for entry in WalkDir::new(dir).follow_links(false).same_file_system(true) {
+        if !dry_run && interrupted() {
+            break;
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if entry.file_type().is_dir() {
+            continue;
+        }
+
+        let size = entry.metadata().map(|m| file_size(&m)).unwrap_or(0);
+        if !dry_run {
+            std::fs::remove_file(entry.path()).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::PermissionDenied {
+                    MoleError::PermissionDenied {
+                        path: entry.path().display().to_string(),
+                    }
+                } else {
+                    MoleError::Io(e)
+                }
+            })?;
+        }
+        freed += size;
+    }
+
+    Ok(freed)
 }
 
 /// Count files in a directory
@@ -207,3 +700,25 @@ pub fn symlink_target(path: &Path) -> Option<std::path::PathBuf> {
         None
     }
 }
+
+/// Check if a path is a symlink whose target does not exist
+///
+/// Targets that exist but can't be read (permissions) don't count.
+pub fn is_broken_symlink(path: &Path) -> bool {
+    symlink_target(path).is_some()
+        && matches!(std::fs::metadata(path), Err(e) if e.kind() == std::io::ErrorKind::NotFound)
+}
+
+/// Broken symlinks under `root`, at most `max_depth` levels down
+///
+/// Links are never followed, so only links inside `root` are reported.
+pub fn find_broken_symlinks(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .max_depth(max_depth)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path_is_symlink() && is_broken_symlink(e.path()))
+        .map(|e| e.into_path())
+        .collect()
+}
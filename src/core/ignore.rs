@@ -0,0 +1,114 @@
+//! `.moleignore` files: per-project, gitignore-style exclusions for purge
+//!
+//! Supports the common gitignore syntax: `#` comments, `!` negation, a
+//! trailing `/` for directories only, and a leading or inner `/` anchoring
+//! the pattern to the directory holding the file. Patterns without a slash
+//! match a name at any depth. The last matching rule wins, and anything
+//! under an ignored directory stays ignored.
+
+use std::path::Path;
+
+use super::glob;
+
+/// Name of the per-project ignore file
+pub const IGNORE_FILE_NAME: &str = ".moleignore";
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    /// Match against the whole relative path instead of just the name
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let line = line.strip_prefix("**/").unwrap_or(line);
+        let anchored = line.contains('/');
+        let pattern = line.strip_prefix('/').unwrap_or(line);
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            glob::matches(&self.pattern, path)
+        } else {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            glob::matches(&self.pattern, name)
+        }
+    }
+}
+
+/// Parsed rules of one `.moleignore` file
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFile {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreFile {
+    pub fn parse(text: &str) -> Self {
+        Self {
+            rules: text.lines().filter_map(Rule::parse).collect(),
+        }
+    }
+
+    /// Read `dir/.moleignore`, if there is one
+    pub fn load(dir: &Path) -> Option<Self> {
+        std::fs::read_to_string(dir.join(IGNORE_FILE_NAME))
+            .ok()
+            .map(|text| Self::parse(&text))
+    }
+
+    /// Whether `relative`, a path below the directory holding this file, is
+    /// ignored
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        // A parent directory that is ignored takes everything under it along
+        for end in 1..=components.len() {
+            let path = components[..end].join("/");
+            let last = end == components.len();
+            if self.verdict(&path, !last || is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn verdict(&self, path: &str, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .is_some_and(|rule| !rule.negated)
+    }
+}
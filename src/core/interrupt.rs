@@ -0,0 +1,29 @@
+//! Stop destructive commands cleanly on Ctrl+C or SIGTERM
+//!
+//! Once deletion starts, a signal only sets a flag. Deletion loops check it
+//! between entries, so the entry being removed is finished, the run's
+//! record is saved and a partial summary is printed before `mo` exits with
+//! `EXIT_INTERRUPTED`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Exit code after stopping early on a signal (128 + SIGINT, as shells use)
+pub const EXIT_INTERRUPTED: u8 = 130;
+
+/// Catch SIGINT and SIGTERM for the rest of the process
+///
+/// Call it right before deleting, not earlier: until then Ctrl+C should
+/// still abort a prompt immediately.
+pub fn install_handler() {
+    if let Err(e) = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst)) {
+        tracing::debug!("Could not install signal handler: {}", e);
+    }
+}
+
+/// Whether a signal asked the current run to stop
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
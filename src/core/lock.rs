@@ -0,0 +1,103 @@
+//! Single-instance lock for commands that delete or change things
+//!
+//! Two overlapping runs (say a manual `mo clean --apply` and a cron job)
+//! would race on the same files and both report the bytes as freed, so
+//! destructive commands hold an exclusive `flock` for their whole run.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use super::errors::{MoleError, Result};
+
+/// Held lock; released when dropped (and by the kernel if the process dies)
+#[derive(Debug)]
+pub struct InstanceLock {
+    file: File,
+}
+
+impl InstanceLock {
+    /// Take the lock at `path`
+    ///
+    /// If another process holds it, block until it is released when `wait`
+    /// is set and fail with `MoleError::AlreadyRunning` otherwise.
+    pub fn acquire(path: &Path, wait: bool) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        if !flock(&file, libc::LOCK_EX | libc::LOCK_NB)? {
+            if !wait {
+                return Err(MoleError::AlreadyRunning);
+            }
+            crate::outln!("Waiting for another mole-rs operation to finish...");
+            flock(&file, libc::LOCK_EX)?;
+        }
+
+        // The holder's pid, for anyone wondering who has the lock
+        file.set_len(0)?;
+        writeln!(file, "{}", std::process::id())?;
+
+        Ok(Self { file })
+    }
+
+    /// Default lock location, next to the state file
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("mole-rs")
+            .join("lock")
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = flock(&self.file, libc::LOCK_UN);
+    }
+}
+
+/// `flock(2)`; false when a non-blocking request found the lock taken
+fn flock(file: &File, operation: libc::c_int) -> Result<bool> {
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+            return Ok(true);
+        }
+        let err = std::io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::EINTR) => continue,
+            Some(libc::EWOULDBLOCK) => return Ok(false),
+            _ => return Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_lock_is_exclusive() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("mole-rs").join("lock");
+
+        let held = InstanceLock::acquire(&path, false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap().trim(),
+            std::process::id().to_string()
+        );
+        assert!(matches!(
+            InstanceLock::acquire(&path, false),
+            Err(MoleError::AlreadyRunning)
+        ));
+
+        drop(held);
+        assert!(InstanceLock::acquire(&path, false).is_ok());
+    }
+}
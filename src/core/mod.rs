@@ -1,12 +1,24 @@
 //! Core module - shared utilities and types
 
+pub mod backup;
 pub mod config;
+pub mod csv;
 pub mod distro;
 pub mod errors;
 pub mod filesystem;
+pub mod glob;
+pub mod ignore;
+pub mod interrupt;
+pub mod json;
+pub mod lock;
+pub mod output;
 pub mod paths;
+pub mod report;
 pub mod security;
+pub mod state;
 pub mod system;
+pub mod time;
+pub mod trash;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,382 @@
+//! Console output that honours the global `--quiet` and `--log-file` flags
+//!
+//! Commands print progress and decoration through `outln!`/`out!`, which are
+//! silenced in quiet mode, results through `sayln!`/`say!` and failures
+//! through `warnln!`, which always print. Everything printed through these
+//! macros is also copied, without colors, to the `--log-file` if one is set.
+
+use colored::Colorize;
+use std::fs::{File, OpenOptions};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use super::errors::MoleError;
+use super::filesystem::{scan_timed_out, scan_timeout, SkippedEntry};
+use super::filesystem::format_size;
+use super::security::{ConfirmPolicy, PathValidation, SecurityValidator};
+use super::system::is_freed_discrepancy;
+
+/// How a command prints its results with `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable listing
+    #[default]
+    Text,
+    Json,
+    /// Header row and one row per entry, for spreadsheets
+    Csv,
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+static NOTHING_TO_DO: AtomicBool = AtomicBool::new(false);
+
+/// Exit code for a run that succeeded but found nothing to clean
+pub const EXIT_NOTHING_TO_DO: u8 = 4;
+
+static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Suppress everything but warnings and errors for the rest of the process
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Record that the command found nothing to work on, so `mo` exits with
+/// `EXIT_NOTHING_TO_DO` instead of 0
+pub fn mark_nothing_to_do() {
+    NOTHING_TO_DO.store(true, Ordering::Relaxed);
+}
+
+pub fn nothing_to_do() -> bool {
+    NOTHING_TO_DO.load(Ordering::Relaxed)
+}
+
+/// Append an uncolored copy of all further output to `path`
+pub fn set_log_file(path: &Path) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    Ok(())
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement) from `text`
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1B' {
+            stripped.push(c);
+            continue;
+        }
+
+        // CSI sequences run until a final byte in '@'..='~'; other escapes
+        // are a single character
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    stripped
+}
+
+/// Print `args` (when `show`) and copy it to the log file; used by the macros
+#[doc(hidden)]
+pub fn emit(args: std::fmt::Arguments, newline: bool, show: bool) {
+    let mut text = args.to_string();
+    if newline {
+        text.push('\n');
+    }
+
+    if show {
+        print!("{}", text);
+    }
+
+    if let Some(file) = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        let _ = file.write_all(strip_ansi(&text).as_bytes());
+    }
+}
+
+/// `println!` that is skipped in quiet mode
+#[macro_export]
+macro_rules! outln {
+    () => {
+        $crate::outln!("")
+    };
+    ($($arg:tt)*) => {
+        $crate::core::output::emit(format_args!($($arg)*), true, !$crate::core::output::is_quiet())
+    };
+}
+
+/// `print!` that is skipped in quiet mode
+#[macro_export]
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::core::output::emit(format_args!($($arg)*), false, !$crate::core::output::is_quiet())
+    };
+}
+
+/// `println!` for a command's results, printed even in quiet mode
+#[macro_export]
+macro_rules! sayln {
+    () => {
+        $crate::sayln!("")
+    };
+    ($($arg:tt)*) => {
+        $crate::core::output::emit(format_args!($($arg)*), true, true)
+    };
+}
+
+/// `print!` for a command's results, printed even in quiet mode
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        $crate::core::output::emit(format_args!($($arg)*), false, true)
+    };
+}
+
+/// `println!` for warnings and failures, printed even in quiet mode
+#[macro_export]
+macro_rules! warnln {
+    () => {
+        $crate::warnln!("")
+    };
+    ($($arg:tt)*) => {
+        $crate::core::output::emit(format_args!($($arg)*), true, true)
+    };
+}
+
+/// List entries cleanup left alone, so it's clear why something didn't shrink
+pub fn print_skipped(skipped: &[SkippedEntry]) {
+    if skipped.is_empty() {
+        return;
+    }
+
+    outln!();
+    outln!("{}", "Protected/skipped:".bold());
+    for entry in skipped {
+        outln!(
+            "  {} {} {}",
+            "○".dimmed(),
+            entry.path.display(),
+            format!("({})", entry.reason).dimmed()
+        );
+    }
+}
+
+/// Warn that the last scan hit `--timeout` and its sizes are lower bounds
+pub fn warn_if_timed_out() {
+    if scan_timed_out() {
+        warnln!(
+            "{} Scan stopped after {}s (--timeout); sizes shown are partial.",
+            "!".yellow(),
+            scan_timeout().as_secs()
+        );
+    }
+}
+
+/// Say that a signal stopped deletion with `finished` of `total` items done
+pub fn warn_interrupted(finished: usize, total: usize, what: &str) {
+    warnln!(
+        "{} Interrupted: {} of {} {} done before stopping; run again to finish.",
+        "!".yellow(),
+        finished,
+        total,
+        what
+    );
+}
+
+/// Warn that `--no-recent-protection` lets recently used files be deleted
+///
+/// This is a safety override, so it is shown even in quiet mode.
+pub fn warn_no_recent_protection() {
+    warnln!(
+        "{} {} recently used caches and artifacts are selected too and will be deleted.",
+        "!".red().bold(),
+        "--no-recent-protection:".red().bold()
+    );
+}
+
+/// Ask a yes/no question on the terminal, defaulting to no
+///
+/// Without a terminal on stdin (cron, pipes) there is nobody to ask, so the
+/// answer is yes: passing `--apply` there is the confirmation.
+pub fn confirm(prompt: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return true;
+    }
+
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush().ok();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Reply to a `--confirm-each` prompt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemAnswer {
+    Yes,
+    No,
+    /// Approve this item and every one after it
+    All,
+    /// Decline this item and every one after it
+    Quit,
+}
+
+impl ItemAnswer {
+    /// Parse a typed reply; anything unrecognised, including Enter, is no
+    pub fn parse(reply: &str) -> Self {
+        match reply.trim().to_lowercase().as_str() {
+            "y" | "yes" => ItemAnswer::Yes,
+            "a" | "all" => ItemAnswer::All,
+            "q" | "quit" => ItemAnswer::Quit,
+            _ => ItemAnswer::No,
+        }
+    }
+}
+
+/// Asks about each item in turn for `--confirm-each`
+#[derive(Debug, Default)]
+pub struct ItemConfirmer {
+    /// Set once the user answered for all remaining items
+    rest: Option<bool>,
+    /// What approving does, e.g. "Delete {}"
+    action: &'static str,
+}
+
+impl ItemConfirmer {
+    /// Fail unless there is a terminal to ask on: without one, deleting
+    /// everything would defeat the point of the flag
+    pub fn new() -> super::errors::Result<Self> {
+        Self::asking("--confirm-each", "Delete {}")
+    }
+
+    /// Like `new`, for another `flag`; `action` is the question with `{}`
+    /// standing for the item
+    pub fn asking(flag: &'static str, action: &'static str) -> super::errors::Result<Self> {
+        if !std::io::stdin().is_terminal() {
+            return Err(MoleError::NotInteractive { flag });
+        }
+        Ok(Self { rest: None, action })
+    }
+
+    /// Ask whether to go ahead with `name`
+    pub fn approve(&mut self, name: &str, size: u64) -> bool {
+        if let Some(approved) = self.rest {
+            return approved;
+        }
+
+        print!("{} ({})? [y/N/a/q] ", self.action.replace("{}", name), format_size(size));
+        std::io::stdout().flush().ok();
+
+        let mut reply = String::new();
+        let answer = match std::io::stdin().read_line(&mut reply) {
+            Ok(n) if n > 0 => ItemAnswer::parse(&reply),
+            _ => ItemAnswer::Quit,
+        };
+        self.record(answer)
+    }
+
+    /// Apply `answer` to the current item, remembering it for the rest on
+    /// `All`/`Quit`
+    pub fn record(&mut self, answer: ItemAnswer) -> bool {
+        match answer {
+            ItemAnswer::Yes => true,
+            ItemAnswer::No => false,
+            ItemAnswer::All => *self.rest.insert(true),
+            ItemAnswer::Quit => *self.rest.insert(false),
+        }
+    }
+}
+
+/// Reasons `policy` gives to ask before deleting `targets`, `size` bytes
+/// and `count` files in total
+///
+/// `count` is only looked at for policies that ask about large deletions,
+/// so callers can skip counting otherwise.
+pub fn deletion_prompts(
+    policy: ConfirmPolicy,
+    validator: &SecurityValidator,
+    targets: &[&PathBuf],
+    size: u64,
+    count: impl FnOnce() -> u64,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    if policy.asks_for_caution() {
+        for target in targets {
+            if let PathValidation::Caution { reason } = validator.validate_path(target) {
+                reasons.push(format!("{} is a caution path: {}.", target.display(), reason));
+            }
+        }
+    }
+
+    if policy.asks_for_large() {
+        if validator.is_large_deletion(size) {
+            reasons.push(format!("This will delete {}.", format_size(size)));
+        }
+        let count = count();
+        if validator.is_large_file_count(count) {
+            reasons.push(format!("This will delete {} files, which can take a long time.", count));
+        }
+    }
+
+    reasons
+}
+
+/// Warn about, and confirm, a deletion `policy` wants a prompt for
+pub fn confirm_deletion(
+    policy: ConfirmPolicy,
+    targets: &[&PathBuf],
+    size: u64,
+    count: impl FnOnce() -> u64,
+) -> bool {
+    let reasons = deletion_prompts(policy, &SecurityValidator::new(), targets, size, count);
+    if reasons.is_empty() {
+        return true;
+    }
+
+    for reason in reasons {
+        warnln!("{} {}", "!".yellow(), reason);
+    }
+    confirm("Continue?")
+}
+
+/// Compare the freed bytes a command computed with what the disk reports
+pub fn print_verification(computed: u64, actual: i64) {
+    let actual_str = if actual < 0 {
+        format!("-{}", format_size(actual.unsigned_abs()))
+    } else {
+        format_size(actual as u64)
+    };
+
+    outln!(
+        "{}: {} (computed {})",
+        "Disk free space change".bold(),
+        actual_str.cyan(),
+        format_size(computed)
+    );
+
+    if is_freed_discrepancy(computed, actual) {
+        warnln!(
+            "{} The disk gained a different amount than computed. Hardlinks, files still held \
+             open by running programs, filesystem compression or other processes writing \
+             can cause this.",
+            "!".yellow()
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Ubuntu-specific cleanup paths
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 /// All cleanup target paths for Ubuntu systems
 #[derive(Debug, Clone)]
@@ -12,6 +13,8 @@ pub struct CleanupPaths {
     pub system_logs: PathBuf,
     pub tmp: PathBuf,
     pub var_tmp: PathBuf,
+    pub snapd_cache: PathBuf,
+    pub crash_reports: PathBuf,
 
     // User caches (no sudo needed)
     pub user_cache: PathBuf,
@@ -19,8 +22,14 @@ pub struct CleanupPaths {
     pub trash: PathBuf,
     pub pip_cache: PathBuf,
     pub npm_cache: PathBuf,
+    pub pnpm_store: PathBuf,
     pub yarn_cache: PathBuf,
 
+    // GPU shader caches, rebuilt by the driver or Steam on next launch
+    pub mesa_shader_cache: PathBuf,
+    pub mesa_shader_cache_db: PathBuf,
+    pub steam_shader_caches: Vec<PathBuf>,
+
     // Browser caches
     pub firefox_cache: PathBuf,
     pub chrome_cache: PathBuf,
@@ -29,8 +38,47 @@ pub struct CleanupPaths {
     // Package manager caches
     pub snap_cache: PathBuf,
     pub flatpak_cache: PathBuf,
+
+    // Per-app config dirs (Electron/Chromium apps keep caches here)
+    pub user_config: PathBuf,
+
+    // User data, only ever reviewed file by file and moved to the Trash
+    pub downloads: PathBuf,
+}
+
+/// Kind of data a cleanup path holds, used to clean one group at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum CategoryGroup {
+    #[value(alias = "caches")]
+    Cache,
+    #[value(alias = "logs")]
+    Log,
+    Temp,
+    Trash,
+    PackageManager,
+}
+
+impl CategoryGroup {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CategoryGroup::Cache => "cache",
+            CategoryGroup::Log => "log",
+            CategoryGroup::Temp => "temp",
+            CategoryGroup::Trash => "trash",
+            CategoryGroup::PackageManager => "package-manager",
+        }
+    }
 }
 
+/// Cache subdirectories Electron/Chromium-based apps create in their config dir
+const ELECTRON_CACHE_DIRS: &[(&str, &str)] = &[
+    ("Cache", "Cache"),
+    ("Code Cache", "Code Cache"),
+    ("GPUCache", "GPU Cache"),
+    ("Service Worker/CacheStorage", "Service Worker Cache"),
+    ("Service Worker/ScriptCache", "Service Worker Scripts"),
+];
+
 impl CleanupPaths {
     /// Create paths for the current user
     pub fn new() -> Self {
@@ -44,6 +92,10 @@ impl CleanupPaths {
             system_logs: PathBuf::from("/var/log"),
             tmp: PathBuf::from("/tmp"),
             var_tmp: PathBuf::from("/var/tmp"),
+            // Old and partial snap downloads; snapd refetches anything it needs
+            snapd_cache: PathBuf::from("/var/lib/snapd/cache"),
+            // Apport writes a .crash file per crash; once reported it is junk
+            crash_reports: PathBuf::from("/var/crash"),
 
             // User cache paths
             user_cache: home.join(".cache"),
@@ -51,8 +103,18 @@ impl CleanupPaths {
             trash: home.join(".local/share/Trash"),
             pip_cache: home.join(".cache/pip"),
             npm_cache: home.join(".npm/_cacache"),
+            pnpm_store: home.join(".local/share/pnpm/store"),
             yarn_cache: home.join(".cache/yarn"),
 
+            // Shader caches; `~/.steam/steam` is usually a symlink to the
+            // `~/.local/share/Steam` install
+            mesa_shader_cache: home.join(".cache/mesa_shader_cache"),
+            mesa_shader_cache_db: home.join(".cache/mesa_shader_cache_db"),
+            steam_shader_caches: vec![
+                home.join(".local/share/Steam/steamapps/shadercache"),
+                home.join(".steam/steam/steamapps/shadercache"),
+            ],
+
             // Browser caches
             firefox_cache: home.join(".cache/mozilla/firefox"),
             chrome_cache: home.join(".cache/google-chrome"),
@@ -61,37 +123,174 @@ impl CleanupPaths {
             // Package manager caches
             snap_cache: home.join("snap"),
             flatpak_cache: home.join(".var/app"),
+
+            user_config: home.join(".config"),
+
+            // XDG_DOWNLOAD_DIR, which may be localized
+            downloads: dirs::download_dir().unwrap_or_else(|| home.join("Downloads")),
         }
     }
 
     /// Get all user-level cache paths (no sudo required)
-    pub fn user_caches(&self) -> Vec<(&str, &PathBuf)> {
+    pub fn user_caches(&self) -> Vec<(&str, &PathBuf, CategoryGroup)> {
+        use CategoryGroup::*;
         vec![
-            ("User Cache", &self.user_cache),
-            ("Thumbnails", &self.thumbnails),
-            ("Trash", &self.trash),
-            ("Pip Cache", &self.pip_cache),
-            ("NPM Cache", &self.npm_cache),
-            ("Yarn Cache", &self.yarn_cache),
-            ("Firefox Cache", &self.firefox_cache),
-            ("Chrome Cache", &self.chrome_cache),
-            ("Chromium Cache", &self.chromium_cache),
+            ("User Cache", &self.user_cache, Cache),
+            ("Thumbnails", &self.thumbnails, Cache),
+            ("Trash", &self.trash, Trash),
+            ("Pip Cache", &self.pip_cache, PackageManager),
+            ("NPM Cache", &self.npm_cache, PackageManager),
+            ("PNPM Store", &self.pnpm_store, PackageManager),
+            ("Yarn Cache", &self.yarn_cache, PackageManager),
+            ("Mesa Shader Cache", &self.mesa_shader_cache, Cache),
+            ("Mesa Shader Cache DB", &self.mesa_shader_cache_db, Cache),
+            ("Firefox Cache", &self.firefox_cache, Cache),
+            ("Chrome Cache", &self.chrome_cache, Cache),
+            ("Chromium Cache", &self.chromium_cache, Cache),
         ]
     }
 
+    /// Check if a path is one of the browser caches, which are expensive to rebuild
+    pub fn is_browser_cache(&self, path: &Path) -> bool {
+        [&self.firefox_cache, &self.chrome_cache, &self.chromium_cache]
+            .iter()
+            .any(|browser| path == browser.as_path())
+    }
+
+    /// Check if a path is a store that hardlinks files heavily, so its size
+    /// should be computed with hardlinks counted once
+    pub fn is_hardlink_heavy(&self, path: &Path) -> bool {
+        path == self.npm_cache.as_path() || path == self.pnpm_store.as_path()
+    }
+
+    /// Electron/Chromium app caches under `~/.config`, labelled per app
+    ///
+    /// All of these belong to [`CategoryGroup::Cache`].
+    pub fn electron_caches(&self) -> Vec<(String, PathBuf)> {
+        find_electron_caches(&self.user_config)
+    }
+
+    /// Per-app Flatpak caches (`~/.var/app/<id>/cache`), keyed by app id
+    pub fn flatpak_app_caches(&self) -> Vec<(String, PathBuf)> {
+        find_flatpak_caches(&self.flatpak_cache)
+    }
+
+    /// Per-game Steam/Proton shader caches, labelled by Steam app id
+    ///
+    /// All of these belong to [`CategoryGroup::Cache`].
+    pub fn steam_shader_caches(&self) -> Vec<(String, PathBuf)> {
+        find_steam_shader_caches(&self.steam_shader_caches)
+    }
+
+    /// Directories of the crash reports category: Crashpad databases of
+    /// browsers and Electron apps, plus apport's `/var/crash` with sudo
+    pub fn crash_report_dirs(&self, include_system: bool) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if include_system && self.crash_reports.is_dir() {
+            dirs.push(self.crash_reports.clone());
+        }
+        dirs.extend(find_crashpad_dirs(&self.user_config));
+        dirs
+    }
+
     /// Get all system-level cache paths (require sudo)
-    pub fn system_caches(&self) -> Vec<(&str, &PathBuf)> {
+    pub fn system_caches(&self) -> Vec<(&str, &PathBuf, CategoryGroup)> {
+        use CategoryGroup::*;
         vec![
-            ("APT Cache", &self.apt_cache),
-            ("APT Lists", &self.apt_lists),
-            ("Journal Logs", &self.journal_logs),
-            ("System Logs", &self.system_logs),
-            ("Temp Files", &self.tmp),
-            ("Var Temp", &self.var_tmp),
+            ("APT Cache", &self.apt_cache, PackageManager),
+            ("APT Lists", &self.apt_lists, PackageManager),
+            ("Journal Logs", &self.journal_logs, Log),
+            ("System Logs", &self.system_logs, Log),
+            ("Temp Files", &self.tmp, Temp),
+            ("Var Temp", &self.var_tmp, Temp),
+            ("Snap Download Cache", &self.snapd_cache, PackageManager),
         ]
     }
 }
 
+/// Find Electron-style cache directories in each app dir under `config_dir`
+///
+/// Labels look like `Slack Code Cache`; results are sorted by label.
+pub fn find_electron_caches(config_dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(config_dir) else {
+        return Vec::new();
+    };
+
+    let mut caches: Vec<(String, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .flat_map(|app_dir| {
+            let app = app_dir.file_name().to_string_lossy().to_string();
+            let base = app_dir.path();
+
+            ELECTRON_CACHE_DIRS
+                .iter()
+                .map(move |(rel, label)| (format!("{} {}", app, label), base.join(rel)))
+                .filter(|(_, path)| path.is_dir())
+        })
+        .collect();
+
+    caches.sort();
+    caches
+}
+
+/// Find the `cache` dir of every Flatpak app under `var_app`, sorted by app id
+pub fn find_flatpak_caches(var_app: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(var_app) else {
+        return Vec::new();
+    };
+
+    let mut caches: Vec<(String, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| (e.file_name().to_string_lossy().to_string(), e.path().join("cache")))
+        .filter(|(_, cache)| cache.is_dir())
+        .collect();
+
+    caches.sort();
+    caches
+}
+
+/// Find `Crashpad` dirs of apps under `config_dir`, sorted
+///
+/// Apps keep them at `<app>/Crashpad` or, for vendor dirs like
+/// `BraveSoftware/Brave-Browser`, one level deeper.
+pub fn find_crashpad_dirs(config_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = WalkDir::new(config_dir)
+        .min_depth(2)
+        .max_depth(3)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.file_name() == "Crashpad")
+        .map(|e| e.into_path())
+        .collect();
+
+    dirs.sort();
+    dirs
+}
+
+/// Find the per-game dirs under each Steam `shadercache` root
+///
+/// Roots reached through symlinks are listed once. Labels look like
+/// `Steam Shader Cache 570`; results are sorted by label.
+pub fn find_steam_shader_caches(roots: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    let mut seen = std::collections::HashSet::new();
+    let mut caches: Vec<(String, PathBuf)> = roots
+        .iter()
+        .filter(|root| std::fs::canonicalize(root).is_ok_and(|real| seen.insert(real)))
+        .filter_map(|root| std::fs::read_dir(root).ok())
+        .flat_map(|entries| entries.filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| {
+            let app_id = e.file_name().to_string_lossy().to_string();
+            (format!("Steam Shader Cache {}", app_id), e.path())
+        })
+        .collect();
+
+    caches.sort();
+    caches
+}
+
 impl Default for CleanupPaths {
     fn default() -> Self {
         Self::new()
@@ -165,6 +364,23 @@ impl DevArtifacts {
     }
 }
 
+impl DevArtifacts {
+    /// Package caches under `home` that every project on the machine shares
+    ///
+    /// Clearing one forces re-downloads for all projects, so purge only
+    /// touches these when asked to.
+    pub fn global_caches(home: &Path) -> Vec<(&'static str, PathBuf)> {
+        vec![
+            ("Cargo registry", home.join(".cargo/registry")),
+            ("Cargo git", home.join(".cargo/git")),
+            ("npm cache", home.join(".npm/_cacache")),
+            ("pip cache", home.join(".cache/pip")),
+            ("Gradle caches", home.join(".gradle/caches")),
+            ("Maven repository", home.join(".m2/repository")),
+        ]
+    }
+}
+
 impl Default for DevArtifacts {
     fn default() -> Self {
         Self::new()
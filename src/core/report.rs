@@ -0,0 +1,166 @@
+//! Plan reports written with `--report` for change management
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::core::csv;
+use crate::core::errors::{MoleError, Result};
+use crate::core::json;
+
+/// Report file format, picked from the file extension (JSON unless `.toml`
+/// or `.csv`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Toml,
+    /// Selected items only, as `category,path,size_bytes,requires_sudo`
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ReportFormat::Toml,
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ReportFormat::Csv,
+            _ => ReportFormat::Json,
+        }
+    }
+}
+
+/// What a command found and intends to do
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub command: String,
+    pub generated_at: String,
+    pub dry_run: bool,
+    /// Bytes covered by the selected items
+    pub total_bytes: u64,
+    pub items: Vec<ReportItem>,
+}
+
+/// One category, artifact, task, package or leftover in a report
+#[derive(Debug, Serialize)]
+pub struct ReportItem {
+    pub name: String,
+    /// Category-specific type (artifact type, package type, leftover type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub size: u64,
+    pub selected: bool,
+    pub requires_sudo: bool,
+}
+
+impl Report {
+    pub fn new(command: &str, dry_run: bool, items: Vec<ReportItem>) -> Self {
+        Self {
+            command: command.to_string(),
+            generated_at: super::time::local_timestamp(),
+            dry_run,
+            total_bytes: items.iter().filter(|i| i.selected).map(|i| i.size).sum(),
+            items,
+        }
+    }
+
+    /// Render the report in the given format
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Json => json::to_string_pretty(self)
+                .map(|s| s + "\n")
+                .map_err(|e| MoleError::Other(format!("Cannot encode report: {}", e))),
+            ReportFormat::Toml => toml::to_string_pretty(self)
+                .map_err(|e| MoleError::Other(format!("Cannot encode report: {}", e))),
+            ReportFormat::Csv => Ok(self.to_csv()),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut out = csv::row(&["category", "path", "size_bytes", "requires_sudo"]);
+        for item in self.items.iter().filter(|i| i.selected) {
+            let path = item
+                .path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            out.push_str(&csv::row(&[
+                item.name.clone(),
+                path,
+                item.size.to_string(),
+                item.requires_sudo.to_string(),
+            ]));
+        }
+        out
+    }
+
+    /// Write the report, choosing the format from the file extension
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = self.render(ReportFormat::from_path(path))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Report {
+        Report::new(
+            "clean",
+            true,
+            vec![
+                ReportItem {
+                    name: "Pip Cache".to_string(),
+                    kind: None,
+                    path: Some(PathBuf::from("/home/u/.cache/pip")),
+                    size: 300,
+                    selected: true,
+                    requires_sudo: false,
+                },
+                ReportItem {
+                    name: "Chrome Cache".to_string(),
+                    kind: None,
+                    path: Some(PathBuf::from("/home/u/.cache/google-chrome")),
+                    size: 700,
+                    selected: false,
+                    requires_sudo: false,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_report_format_from_path() {
+        assert_eq!(ReportFormat::from_path(Path::new("plan.toml")), ReportFormat::Toml);
+        assert_eq!(ReportFormat::from_path(Path::new("plan.TOML")), ReportFormat::Toml);
+        assert_eq!(ReportFormat::from_path(Path::new("plan.json")), ReportFormat::Json);
+        assert_eq!(ReportFormat::from_path(Path::new("plan")), ReportFormat::Json);
+        assert_eq!(ReportFormat::from_path(Path::new("plan.csv")), ReportFormat::Csv);
+    }
+
+    #[test]
+    fn test_report_total_counts_selected_only() {
+        assert_eq!(sample().total_bytes, 300);
+    }
+
+    #[test]
+    fn test_report_renders_every_format() {
+        let report = sample();
+
+        let json = report.render(ReportFormat::Json).unwrap();
+        assert!(json.contains("\"command\": \"clean\""));
+        assert!(json.contains("\"path\": \"/home/u/.cache/pip\""));
+
+        let toml = report.render(ReportFormat::Toml).unwrap();
+        assert!(toml.contains("command = \"clean\""));
+        assert!(toml.contains("[[items]]"));
+
+        let csv = report.render(ReportFormat::Csv).unwrap();
+        assert_eq!(
+            csv,
+            "category,path,size_bytes,requires_sudo\n\
+             Pip Cache,/home/u/.cache/pip,300,false\n"
+        );
+    }
+}
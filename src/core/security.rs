@@ -2,6 +2,9 @@
 
 use std::path::{Path, PathBuf};
 use std::os::unix::fs::MetadataExt;
+use std::sync::OnceLock;
+
+use crate::core::config::Config;
 
 /// Critical system paths that should NEVER be deleted
 /// These form an "Iron Dome" around the system
@@ -61,27 +64,69 @@ pub enum PathValidation {
     Invalid { reason: String },
 }
 
+/// Which conditions make clean and purge ask before deleting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ConfirmPolicy {
+    /// Ask for caution paths and for large deletions
+    All,
+    /// Ask only before large deletions, by size or file count
+    #[default]
+    Large,
+    /// Ask only when a target is a caution path such as /tmp
+    Caution,
+    /// Never ask
+    None,
+}
+
+impl ConfirmPolicy {
+    pub fn asks_for_caution(self) -> bool {
+        matches!(self, ConfirmPolicy::All | ConfirmPolicy::Caution)
+    }
+
+    pub fn asks_for_large(self) -> bool {
+        matches!(self, ConfirmPolicy::All | ConfirmPolicy::Large)
+    }
+}
+
 /// Security validator for filesystem operations
+#[derive(Clone)]
 pub struct SecurityValidator {
     /// User-defined whitelist (protected paths)
     whitelist: Vec<PathBuf>,
     /// Maximum size for automatic deletion (bytes)
     large_deletion_threshold: u64,
+    /// Maximum number of files for automatic deletion
+    large_file_count_threshold: u64,
     /// Whether to allow symlink following
     allow_symlinks: bool,
 }
 
 impl SecurityValidator {
     /// Create a new security validator
+    ///
+    /// The config and whitelist file are read on the first call only; one
+    /// validator is made per deleted path, far too often to re-read them.
     pub fn new() -> Self {
+        static SHARED: OnceLock<SecurityValidator> = OnceLock::new();
+        SHARED
+            .get_or_init(|| Self::with_config(&Config::load()))
+            .clone()
+    }
+
+    /// Create a validator for `config`, protecting both its `whitelist` and
+    /// the paths in the whitelist file
+    pub fn with_config(config: &Config) -> Self {
+        let mut whitelist = Self::load_whitelist();
+        whitelist.extend(config.whitelist.iter().cloned());
         Self {
-            whitelist: Self::load_whitelist(),
+            whitelist,
             large_deletion_threshold: 1024 * 1024 * 1024, // 1GB
+            large_file_count_threshold: config.large_file_count,
             allow_symlinks: false,
         }
     }
 
-    /// Load whitelist from config file
+    /// Load whitelist from `~/.config/mole-rs/whitelist`, one path per line
     fn load_whitelist() -> Vec<PathBuf> {
         let whitelist_path = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -188,9 +233,15 @@ impl SecurityValidator {
             "/var/cache/apt/archives",
             "/var/cache/apt/pkgcache.bin",
             "/var/cache/apt/srcpkgcache.bin",
+            // Apport crash reports
+            "/var/crash",
+            // Snap downloads, under otherwise protected /var/lib
+            "/var/lib/snapd/cache",
         ];
 
-        safe_patterns.iter().any(|p| path_str.starts_with(p))
+        safe_patterns
+            .iter()
+            .any(|p| path_str == *p || path_str.starts_with(&format!("{}/", p)))
     }
 
     /// Check if deletion exceeds size threshold
@@ -198,6 +249,14 @@ impl SecurityValidator {
         size >= self.large_deletion_threshold
     }
 
+    /// Check if deletion removes more files than the threshold
+    ///
+    /// Millions of tiny files are slow to delete and easy to get wrong even
+    /// when their total size is below `is_large_deletion`.
+    pub fn is_large_file_count(&self, count: u64) -> bool {
+        count >= self.large_file_count_threshold
+    }
+
     /// Validate path for deletion with sudo
     pub fn validate_sudo_operation(&self, path: &Path) -> PathValidation {
         let base_validation = self.validate_path(path);
@@ -245,6 +304,79 @@ impl Default for SecurityValidator {
     }
 }
 
+/// Check whether another filesystem is mounted at `path`
+///
+/// A mountpoint lives on a different device than its parent directory.
+/// Bind mounts of the same device don't show up this way; `find_mount`
+/// also consults the mount table for those.
+pub fn is_mountpoint(path: &Path) -> bool {
+    let Some(parent) = path.parent() else {
+        return true;
+    };
+
+    match (std::fs::symlink_metadata(path), std::fs::symlink_metadata(parent)) {
+        (Ok(meta), Ok(parent_meta)) => {
+            !meta.file_type().is_symlink() && meta.dev() != parent_meta.dev()
+        }
+        _ => false,
+    }
+}
+
+/// Mountpoints listed in `/proc/self/mounts`
+pub fn mountpoints() -> Vec<PathBuf> {
+    std::fs::read_to_string("/proc/self/mounts")
+        .map(|mounts| parse_mountpoints(&mounts))
+        .unwrap_or_default()
+}
+
+/// Extract the mountpoint column from mount table content
+pub fn parse_mountpoints(mounts: &str) -> Vec<PathBuf> {
+    mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|field| PathBuf::from(unescape_mount_field(field)))
+        .collect()
+}
+
+/// Find a filesystem mounted at or anywhere below `path`
+///
+/// Deleting such a tree would recurse into the mounted filesystem (an
+/// external disk under a cache dir, say) and wipe it.
+pub fn find_mount(path: &Path, mountpoints: &[PathBuf]) -> Option<PathBuf> {
+    if is_mountpoint(path) {
+        return Some(path.to_path_buf());
+    }
+
+    mountpoints.iter().find(|m| m.starts_with(path)).cloned()
+}
+
+/// Undo the octal escapes (`\040` for space, ...) used in `/proc/self/mounts`
+pub fn unescape_mount_field(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let code = rest
+            .get(pos + 1..pos + 4)
+            .and_then(|oct| u8::from_str_radix(oct, 8).ok());
+
+        match code {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
 /// Check for potentially dangerous characters in path
 pub fn contains_dangerous_chars(path: &Path) -> bool {
     let path_str = path.to_string_lossy();
@@ -319,6 +451,60 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_crash_reports_allowed() {
+        let validator = SecurityValidator::new();
+
+        assert!(!matches!(
+            validator.validate_path(Path::new("/var/crash/_usr_bin_gedit.1000.crash")),
+            PathValidation::Blocked { .. }
+        ));
+        assert!(matches!(
+            validator.validate_path(Path::new("/var/lib/apport")),
+            PathValidation::Blocked { .. }
+        ));
+    }
+
+    #[test]
+    fn test_config_whitelist_protects() {
+        let config = Config {
+            whitelist: vec![PathBuf::from("/home/u/.cache/keep")],
+            ..Config::default()
+        };
+        let validator = SecurityValidator::with_config(&config);
+
+        assert!(matches!(
+            validator.validate_path(Path::new("/home/u/.cache/keep/model.bin")),
+            PathValidation::Blocked { .. }
+        ));
+        assert!(!matches!(
+            validator.validate_path(Path::new("/home/u/.cache/other")),
+            PathValidation::Blocked { .. }
+        ));
+    }
+
+    #[test]
+    fn test_snapd_cache_allowed() {
+        let validator = SecurityValidator::new();
+
+        assert!(!matches!(
+            validator.validate_path(Path::new("/var/lib/snapd/cache")),
+            PathValidation::Blocked { .. }
+        ));
+        assert!(!matches!(
+            validator.validate_path(Path::new("/var/lib/snapd/cache/3f2a9c")),
+            PathValidation::Blocked { .. }
+        ));
+        assert!(matches!(
+            validator.validate_path(Path::new("/var/lib/snapd/snaps")),
+            PathValidation::Blocked { .. }
+        ));
+        assert!(matches!(
+            validator.validate_path(Path::new("/var/lib/snapd/cache-other")),
+            PathValidation::Blocked { .. }
+        ));
+    }
+
     #[test]
     fn test_relative_path_rejected() {
         let validator = SecurityValidator::new();
@@ -355,6 +541,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_find_mount() {
+        let mounts = parse_mountpoints(
+            "/dev/sda1 / ext4 rw 0 0\n\
+             /dev/sdb1 /home/u/.cache/backup\\040drive ext4 rw 0 0\n\
+             tmpfs /tmp tmpfs rw 0 0\n",
+        );
+
+        assert_eq!(mounts[1], PathBuf::from("/home/u/.cache/backup drive"));
+        assert_eq!(
+            find_mount(Path::new("/home/u/.cache"), &mounts),
+            Some(PathBuf::from("/home/u/.cache/backup drive"))
+        );
+        assert_eq!(find_mount(Path::new("/home/u/.cache/pip"), &mounts), None);
+        assert!(is_mountpoint(Path::new("/")));
+    }
+
     #[test]
     fn test_dangerous_chars() {
         assert!(contains_dangerous_chars(Path::new("/path/with\nnewline")));
@@ -368,5 +571,7 @@ mod tests {
         
         assert!(!validator.is_large_deletion(500 * 1024 * 1024)); // 500MB
         assert!(validator.is_large_deletion(2 * 1024 * 1024 * 1024)); // 2GB
+        assert!(!validator.is_large_file_count(1_000));
+        assert!(validator.is_large_file_count(1_000_000));
     }
 }
@@ -0,0 +1,72 @@
+//! Small bits of state remembered between runs
+//!
+//! Unlike `Config`, nothing here is meant to be edited by hand; it lives in
+//! the data dir and is rewritten whenever it changes.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::time::format_local;
+
+/// State persisted in `~/.local/share/mole-rs/state.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// Path given to the last `mo analyze` run
+    pub last_analyze_path: Option<PathBuf>,
+
+    /// When each clean category was last cleaned, as ISO-8601 local time
+    pub last_cleaned: BTreeMap<String, String>,
+}
+
+impl State {
+    /// Load state from the default location, or defaults if it's missing or unreadable
+    pub fn load() -> Self {
+        Self::load_from(&Self::state_path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save state to the default location
+    pub fn save(&self) -> std::io::Result<()> {
+        self.save_to(&Self::state_path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = toml::to_string_pretty(self).unwrap_or_default();
+        std::fs::write(path, content)
+    }
+
+    /// Record that `category` was cleaned at `now`
+    pub fn mark_cleaned(&mut self, category: &str, now: DateTime<Local>) {
+        self.last_cleaned.insert(category.to_string(), format_local(now));
+    }
+
+    /// How long before `now` `category` was cleaned, if it has a record
+    ///
+    /// A record in the future (clock changes) counts as just cleaned.
+    pub fn cleaned_ago(&self, category: &str, now: DateTime<Local>) -> Option<Duration> {
+        let cleaned = DateTime::parse_from_rfc3339(self.last_cleaned.get(category)?).ok()?;
+        Some((now.fixed_offset() - cleaned).to_std().unwrap_or_default())
+    }
+
+    /// Get state file path
+    pub fn state_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("mole-rs")
+            .join("state.toml")
+    }
+}
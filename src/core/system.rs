@@ -1,6 +1,11 @@
 //! System information wrapper using sysinfo
 
-use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, Networks, System, RefreshKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use sysinfo::{
+    Components, CpuRefreshKind, Disks, MemoryRefreshKind, Networks, System, RefreshKind,
+};
 
 /// System information snapshot
 #[derive(Debug)]
@@ -8,6 +13,7 @@ pub struct SystemInfo {
     system: System,
     disks: Disks,
     networks: Networks,
+    components: Components,
 }
 
 impl SystemInfo {
@@ -19,6 +25,7 @@ impl SystemInfo {
             system,
             disks: Disks::new_with_refreshed_list(),
             networks: Networks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
         }
     }
 
@@ -29,6 +36,12 @@ impl SystemInfo {
         self.system.refresh_processes();
         self.disks.refresh();
         self.networks.refresh();
+        self.components.refresh();
+    }
+
+    /// Re-read disk usage only, e.g. between deletions
+    pub fn refresh_disks(&mut self) {
+        self.disks.refresh();
     }
 
     /// Get CPU usage percentage (0-100)
@@ -55,6 +68,21 @@ impl SystemInfo {
         self.system.used_memory()
     }
 
+    /// Get memory available for new allocations (free plus reclaimable cache) in bytes
+    pub fn available_memory(&self) -> u64 {
+        self.system.available_memory()
+    }
+
+    /// Get total swap in bytes
+    pub fn total_swap(&self) -> u64 {
+        self.system.total_swap()
+    }
+
+    /// Get used swap in bytes
+    pub fn used_swap(&self) -> u64 {
+        self.system.used_swap()
+    }
+
     /// Get memory usage percentage
     pub fn memory_usage(&self) -> f32 {
         let total = self.total_memory() as f32;
@@ -64,6 +92,26 @@ impl SystemInfo {
         (self.used_memory() as f32 / total) * 100.0
     }
 
+    /// Memory in use if zram data were stored uncompressed, as a percentage
+    /// of RAM; can exceed 100 when zram is holding the system together
+    pub fn memory_pressure(&self) -> f32 {
+        memory_pressure(self.used_memory(), self.total_memory(), zram_total(&self.zram()).as_ref())
+    }
+
+    /// zram devices holding data, read from `/sys/block`
+    pub fn zram(&self) -> Vec<ZramStats> {
+        zram_devices(Path::new("/sys/block"))
+    }
+
+    /// Get swap usage percentage, or `None` when there is no swap
+    pub fn swap_usage(&self) -> Option<f32> {
+        let total = self.total_swap();
+        if total == 0 {
+            return None;
+        }
+        Some((self.used_swap() as f32 / total as f32) * 100.0)
+    }
+
     /// Get disk information
     pub fn disk_info(&self) -> Vec<DiskInfo> {
         self.disks
@@ -78,6 +126,14 @@ impl SystemInfo {
             .collect()
     }
 
+    /// Get the disk whose mount point contains `path` (longest match wins)
+    pub fn disk_for_path(&self, path: &Path) -> Option<DiskInfo> {
+        self.disk_info()
+            .into_iter()
+            .filter(|disk| path.starts_with(&disk.mount_point))
+            .max_by_key(|disk| disk.mount_point.len())
+    }
+
     /// Get network I/O
     pub fn network_io(&self) -> (u64, u64) {
         let mut received = 0u64;
@@ -91,6 +147,61 @@ impl SystemInfo {
         (received, transmitted)
     }
 
+    /// Get total bytes received and transmitted since boot
+    pub fn network_totals(&self) -> (u64, u64) {
+        self.networks
+            .iter()
+            .fold((0, 0), |(rx, tx), (_name, data)| {
+                (rx + data.total_received(), tx + data.total_transmitted())
+            })
+    }
+
+    /// Get thermal sensor labels and temperatures in °C
+    ///
+    /// Empty on systems without readable sensors (VMs, containers).
+    pub fn components(&self) -> Vec<(String, f32)> {
+        self.components
+            .iter()
+            .map(|c| (c.label().to_string(), c.temperature()))
+            .filter(|(_, temp)| temp.is_finite() && *temp > 0.0)
+            .collect()
+    }
+
+    /// Get fan labels and speeds in RPM from hwmon
+    pub fn fans(&self) -> Vec<(String, u32)> {
+        let Ok(entries) = std::fs::read_dir("/sys/class/hwmon") else {
+            return Vec::new();
+        };
+
+        let mut fans = Vec::new();
+        for hwmon in entries.flatten().map(|e| e.path()) {
+            let chip = std::fs::read_to_string(hwmon.join("name")).unwrap_or_default();
+            let Ok(files) = std::fs::read_dir(&hwmon) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let name = file.file_name().to_string_lossy().to_string();
+                let Some(index) = name.strip_prefix("fan").and_then(|n| n.strip_suffix("_input"))
+                else {
+                    continue;
+                };
+                let Some(rpm) = std::fs::read_to_string(file.path())
+                    .ok()
+                    .and_then(|v| v.trim().parse().ok())
+                else {
+                    continue;
+                };
+                let label = std::fs::read_to_string(hwmon.join(format!("fan{}_label", index)))
+                    .map(|l| l.trim().to_string())
+                    .unwrap_or_else(|_| format!("{} fan{}", chip.trim(), index));
+                fans.push((label, rpm));
+            }
+        }
+
+        fans.sort();
+        fans
+    }
+
     /// Get system uptime in seconds
     pub fn uptime(&self) -> u64 {
         System::uptime()
@@ -163,7 +274,146 @@ impl DiskInfo {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Free space on the filesystems holding a set of paths
+///
+/// Taken before and after a deletion, the difference is what the disk
+/// actually gained, as opposed to what the file sizes added up to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FreeSpaceSnapshot {
+    /// Available bytes per mount point
+    pub available: HashMap<String, u64>,
+}
+
+impl FreeSpaceSnapshot {
+    pub fn take<P: AsRef<Path>>(paths: &[P]) -> Self {
+        let disks = Disks::new_with_refreshed_list();
+        let mut available = HashMap::new();
+
+        for path in paths {
+            let disk = disks
+                .iter()
+                .filter(|d| path.as_ref().starts_with(d.mount_point()))
+                .max_by_key(|d| d.mount_point().as_os_str().len());
+
+            if let Some(disk) = disk {
+                available.insert(
+                    disk.mount_point().to_string_lossy().to_string(),
+                    disk.available_space(),
+                );
+            }
+        }
+
+        Self { available }
+    }
+
+    /// Bytes gained since `before` on the filesystems both snapshots saw
+    ///
+    /// Negative when something else wrote to the disk in the meantime.
+    pub fn freed_since(&self, before: &Self) -> i64 {
+        self.available
+            .iter()
+            .filter_map(|(mount, after)| {
+                before
+                    .available
+                    .get(mount)
+                    .map(|before| *after as i64 - *before as i64)
+            })
+            .sum()
+    }
+}
+
+/// Whether the measured free-space gain is too far from the computed one to trust
+///
+/// Small differences are normal (metadata, reserved blocks, other writers),
+/// so only gaps over 5% and 1 MiB are flagged.
+pub fn is_freed_discrepancy(computed: u64, actual: i64) -> bool {
+    let diff = (computed as i64 - actual).unsigned_abs();
+    diff > 1024 * 1024 && diff * 20 > computed
+}
+
+/// Compressed in-RAM block device, usually swap, on one `zram` device
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZramStats {
+    pub device: String,
+    /// Size of the stored data before compression
+    pub original: u64,
+    /// Size of the stored data after compression
+    pub compressed: u64,
+    /// RAM the device takes, allocator overhead included
+    pub mem_used: u64,
+}
+
+impl ZramStats {
+    /// How many times smaller the data got
+    pub fn ratio(&self) -> f64 {
+        if self.compressed == 0 {
+            return 0.0;
+        }
+        self.original as f64 / self.compressed as f64
+    }
+}
+
+/// Parse a zram `mm_stat` file: `orig_data_size compr_data_size
+/// mem_used_total ...`, all in bytes
+pub fn parse_mm_stat(device: &str, content: &str) -> Option<ZramStats> {
+    let mut fields = content.split_whitespace().map(|f| f.parse::<u64>());
+    Some(ZramStats {
+        device: device.to_string(),
+        original: fields.next()?.ok()?,
+        compressed: fields.next()?.ok()?,
+        mem_used: fields.next()?.ok()?,
+    })
+}
+
+/// zram devices under `sys_block` that hold any data, by name
+pub fn zram_devices(sys_block: &Path) -> Vec<ZramStats> {
+    let Ok(entries) = std::fs::read_dir(sys_block) else {
+        return Vec::new();
+    };
+
+    let mut devices: Vec<ZramStats> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("zram") {
+                return None;
+            }
+            let content = std::fs::read_to_string(entry.path().join("mm_stat")).ok()?;
+            parse_mm_stat(&name, &content)
+        })
+        .filter(|stats| stats.original > 0)
+        .collect();
+    devices.sort_by(|a, b| a.device.cmp(&b.device));
+    devices
+}
+
+/// All zram devices added up, or `None` without any
+pub fn zram_total(devices: &[ZramStats]) -> Option<ZramStats> {
+    if devices.is_empty() {
+        return None;
+    }
+    Some(ZramStats {
+        device: "zram".to_string(),
+        original: devices.iter().map(|d| d.original).sum(),
+        compressed: devices.iter().map(|d| d.compressed).sum(),
+        mem_used: devices.iter().map(|d| d.mem_used).sum(),
+    })
+}
+
+/// Percentage of `total` RAM that `used` would be with zram data counted
+/// at its original size instead of the RAM it takes compressed
+pub fn memory_pressure(used: u64, total: u64, zram: Option<&ZramStats>) -> f32 {
+    if total == 0 {
+        return 0.0;
+    }
+    let used = match zram {
+        Some(zram) => used.saturating_sub(zram.mem_used) + zram.original,
+        None => used,
+    };
+    (used as f64 / total as f64 * 100.0) as f32
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ProcessInfo {
     pub name: String,
     pub cpu_usage: f32,
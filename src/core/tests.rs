@@ -10,6 +10,34 @@ mod tests {
         use std::io::Write;
         use tempfile::TempDir;
 
+        #[test]
+        fn test_find_broken_symlinks() {
+            let temp = TempDir::new().unwrap();
+            let apps = temp.path().join("share/applications");
+            fs::create_dir_all(&apps).unwrap();
+            File::create(temp.path().join("real.desktop")).unwrap();
+
+            let link = |target: std::path::PathBuf, link: std::path::PathBuf| {
+                std::os::unix::fs::symlink(target, link).unwrap()
+            };
+            link(temp.path().join("real.desktop"), apps.join("ok.desktop"));
+            link(temp.path().join("gone.desktop"), apps.join("old.desktop"));
+            link("../missing".into(), temp.path().join("share/relative"));
+
+            assert!(is_broken_symlink(&apps.join("old.desktop")));
+            assert!(!is_broken_symlink(&apps.join("ok.desktop")));
+            assert!(!is_broken_symlink(&temp.path().join("real.desktop")));
+
+            let mut broken = find_broken_symlinks(temp.path(), 4);
+            broken.sort();
+            assert_eq!(
+                broken,
+                [apps.join("old.desktop"), temp.path().join("share/relative")]
+            );
+
+            assert_eq!(find_broken_symlinks(temp.path(), 2), [temp.path().join("share/relative")]);
+        }
+
         #[test]
         fn test_format_size_bytes() {
             assert_eq!(format_size(0), "0 B");
@@ -34,6 +62,49 @@ mod tests {
             assert_eq!(format_size(1024 * 1024 * 1024), "1 GiB");
         }
 
+        #[test]
+        fn test_sticky_skip_reason() {
+            use crate::core::config::TmpPolicy;
+
+            assert_eq!(sticky_skip_reason(1000, 1000, 0, TmpPolicy::Mine, 10), None);
+            assert!(sticky_skip_reason(1001, 1000, 365, TmpPolicy::Mine, 10).is_some());
+            assert!(sticky_skip_reason(1001, 0, 3, TmpPolicy::Old, 10).is_some());
+            assert_eq!(sticky_skip_reason(1001, 0, 30, TmpPolicy::Old, 10), None);
+        }
+
+        #[test]
+        fn test_clean_directory_keeps_others_files_in_sticky_dir() {
+            use std::os::unix::fs::{chown, PermissionsExt};
+
+            if !is_root() {
+                return;
+            }
+
+            let temp = TempDir::new().unwrap();
+            fs::set_permissions(temp.path(), fs::Permissions::from_mode(0o1777)).unwrap();
+            let mine = temp.path().join("mine.txt");
+            let theirs = temp.path().join("theirs.txt");
+            fs::write(&mine, "a").unwrap();
+            fs::write(&theirs, "b").unwrap();
+            chown(&theirs, Some(65534), Some(65534)).unwrap();
+
+            let report = clean_directory(temp.path(), false).unwrap();
+
+            assert!(!mine.exists());
+            assert!(theirs.exists());
+            assert_eq!(report.skipped.len(), 1);
+        }
+
+        #[test]
+        fn test_parse_size() {
+            assert_eq!(parse_size("4096"), Ok(4096));
+            assert_eq!(parse_size("512M"), Ok(512 * 1024 * 1024));
+            assert_eq!(parse_size("10GiB"), Ok(10 * 1024 * 1024 * 1024));
+            assert_eq!(parse_size("1.5gb"), Ok(3 * 512 * 1024 * 1024));
+            assert!(parse_size("10X").is_err());
+            assert!(parse_size("G").is_err());
+        }
+
         #[test]
         fn test_dir_size_empty() {
             let temp = TempDir::new().unwrap();
@@ -54,6 +125,80 @@ mod tests {
             assert_eq!(size, 13);
         }
 
+        #[test]
+        fn test_measure_dir_with_filter() {
+            let temp = TempDir::new().unwrap();
+            fs::create_dir(temp.path().join("isos")).unwrap();
+            fs::write(temp.path().join("isos/ubuntu.iso"), b"1234567890").unwrap();
+            fs::write(temp.path().join("isos/notes.txt"), b"123").unwrap();
+            fs::write(temp.path().join("clip.MP4"), b"12345").unwrap();
+
+            let filter = ScanFilter {
+                include: vec!["*.iso".to_string(), "*.[mM][pP]4".to_string()],
+                ..Default::default()
+            };
+            assert_eq!(measure_dir(temp.path(), &filter).bytes, 15);
+            assert_eq!(measure_dir(temp.path(), &ScanFilter::default()).bytes, 18);
+
+            let filter = ScanFilter {
+                exclude: vec!["isos".to_string()],
+                ..Default::default()
+            };
+            assert_eq!(measure_dir(temp.path(), &filter).bytes, 5);
+
+            let filter = ScanFilter {
+                exclude: vec!["*/isos/*.txt".to_string()],
+                ..Default::default()
+            };
+            assert_eq!(measure_dir(temp.path(), &filter).bytes, 15);
+        }
+
+        #[test]
+        fn test_measure_dir_follow_links() {
+            let temp = TempDir::new().unwrap();
+            let elsewhere = temp.path().join("elsewhere");
+            let tree = temp.path().join("tree");
+            fs::create_dir_all(&elsewhere).unwrap();
+            fs::create_dir_all(&tree).unwrap();
+            fs::write(elsewhere.join("blob"), vec![0u8; 100]).unwrap();
+            fs::write(tree.join("own"), vec![0u8; 10]).unwrap();
+            std::os::unix::fs::symlink(&elsewhere, tree.join("cache")).unwrap();
+            std::os::unix::fs::symlink(&elsewhere, tree.join("cache2")).unwrap();
+            std::os::unix::fs::symlink(&tree, tree.join("loop")).unwrap();
+
+            assert_eq!(measure_dir(&tree, &ScanFilter::default()).bytes, 10);
+
+            let follow = ScanFilter {
+                follow_links: true,
+                ..Default::default()
+            };
+            // Both links share a target, counted once; the loop is not entered
+            assert_eq!(measure_dir(&tree, &follow).bytes, 110);
+        }
+
+        #[test]
+        fn test_measure_dir_counts_denied_entries() {
+            use std::os::unix::fs::PermissionsExt;
+
+            // Root can read anything, so there is nothing to deny
+            if is_root() {
+                return;
+            }
+
+            let temp = TempDir::new().unwrap();
+            fs::write(temp.path().join("open.txt"), b"12345").unwrap();
+            let locked = temp.path().join("locked");
+            fs::create_dir(&locked).unwrap();
+            fs::write(locked.join("hidden.txt"), b"1234567890").unwrap();
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+            let size = measure_dir(temp.path(), &ScanFilter::default());
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+            assert_eq!(size.bytes, 5);
+            assert_eq!(size.denied, 1);
+        }
+
         #[test]
         fn test_dir_size_nested() {
             let temp = TempDir::new().unwrap();
@@ -79,6 +224,31 @@ mod tests {
             assert_eq!(size, 0);
         }
 
+        #[test]
+        fn test_dir_size_dedup_hardlinks() {
+            let temp = TempDir::new().unwrap();
+
+            let mut file = File::create(temp.path().join("original.bin")).unwrap();
+            file.write_all(&[0u8; 100]).unwrap();
+            fs::hard_link(temp.path().join("original.bin"), temp.path().join("link.bin")).unwrap();
+
+            assert_eq!(dir_size(temp.path()).unwrap(), 200);
+            assert_eq!(dir_size_dedup(temp.path()).unwrap(), 100);
+        }
+
+        #[test]
+        fn test_size_mode_sparse_file() {
+            let temp = TempDir::new().unwrap();
+            let file_path = temp.path().join("sparse.img");
+
+            let file = File::create(&file_path).unwrap();
+            file.set_len(10 * 1024 * 1024).unwrap();
+
+            let metadata = fs::metadata(&file_path).unwrap();
+            assert_eq!(SizeMode::Apparent.measure(&metadata), 10 * 1024 * 1024);
+            assert!(SizeMode::DiskUsage.measure(&metadata) < 1024 * 1024);
+        }
+
         #[test]
         fn test_safe_delete_file() {
             let temp = TempDir::new().unwrap();
@@ -119,13 +289,76 @@ mod tests {
             fs::create_dir(&subdir).unwrap();
             File::create(subdir.join("file3.txt")).unwrap();
             
-            clean_directory(temp.path(), false).unwrap();
+            let preview = clean_directory(temp.path(), true).unwrap();
+            assert_eq!(preview.entries.len(), 3);
+            assert!(preview.entries.iter().any(|(path, _)| path == &subdir));
+
+            let report = clean_directory(temp.path(), false).unwrap();
+            assert!(report.skipped.is_empty());
             
             // Directory should still exist but be empty
             assert!(temp.path().exists());
             assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
         }
 
+        #[test]
+        fn test_clean_directory_files_only_keeps_tree() {
+            let temp = TempDir::new().unwrap();
+            fs::write(temp.path().join("top.bin"), "12345").unwrap();
+            let nested = temp.path().join("cache/index/v2");
+            fs::create_dir_all(&nested).unwrap();
+            fs::write(nested.join("blob"), "1234567890").unwrap();
+            std::os::unix::fs::symlink(nested.join("blob"), nested.join("link")).unwrap();
+
+            let preview = clean_directory_keeping(temp.path(), true, true, &[]).unwrap();
+            assert_eq!(preview.entries.len(), 2);
+            assert!(nested.join("blob").exists());
+
+            let report = clean_directory_keeping(temp.path(), false, true, &[]).unwrap();
+            assert!(report.freed >= 15);
+            assert!(!temp.path().join("top.bin").exists());
+            assert!(nested.is_dir());
+            assert_eq!(fs::read_dir(&nested).unwrap().count(), 0);
+        }
+
+        #[test]
+        fn test_clean_directory_keeping_nested_path() {
+            let temp = TempDir::new().unwrap();
+            let pip = temp.path().join("pip");
+            let go_build = temp.path().join("tools/go-build");
+            fs::create_dir_all(&pip).unwrap();
+            fs::create_dir_all(&go_build).unwrap();
+            fs::write(pip.join("wheel"), "whl").unwrap();
+            fs::write(go_build.join("obj"), "obj").unwrap();
+            fs::write(temp.path().join("tools/other"), "other").unwrap();
+            fs::write(temp.path().join("junk"), "junk").unwrap();
+
+            let keep = [pip.clone(), go_build.clone()];
+            let report = clean_directory_keeping(temp.path(), false, false, &keep).unwrap();
+
+            assert!(pip.join("wheel").exists());
+            assert!(go_build.join("obj").exists());
+            assert!(!temp.path().join("tools/other").exists());
+            assert!(!temp.path().join("junk").exists());
+            assert_eq!(report.skipped.len(), 2);
+            assert_eq!(report.freed, 9);
+        }
+
+        #[test]
+        fn test_clean_directory_reports_protected_symlink() {
+            let temp = TempDir::new().unwrap();
+            File::create(temp.path().join("junk.tmp")).unwrap();
+            std::os::unix::fs::symlink("/etc", temp.path().join("etc-link")).unwrap();
+
+            let report = clean_directory(temp.path(), false).unwrap();
+
+            assert_eq!(report.skipped.len(), 1);
+            assert_eq!(report.skipped[0].path, temp.path().join("etc-link"));
+            assert!(report.skipped[0].reason.contains("/etc"));
+            assert!(temp.path().join("etc-link").symlink_metadata().is_ok());
+            assert!(!temp.path().join("junk.tmp").exists());
+        }
+
         #[test]
         fn test_is_root() {
             // This test will pass on non-root systems
@@ -151,6 +384,15 @@ mod tests {
             assert!(paths.trash.to_string_lossy().contains("Trash"));
         }
 
+        #[test]
+        fn test_global_caches_under_home() {
+            let home = std::path::Path::new("/home/dev");
+            let caches = DevArtifacts::global_caches(home);
+
+            assert!(caches.iter().all(|(_, path)| path.starts_with(home)));
+            assert!(caches.iter().any(|(_, path)| path.ends_with(".cargo/registry")));
+        }
+
         #[test]
         fn test_user_caches_not_empty() {
             let paths = CleanupPaths::new();
@@ -169,6 +411,77 @@ mod tests {
             assert!(system_caches.len() >= 4);
         }
 
+        #[test]
+        fn test_find_electron_caches() {
+            let temp = tempfile::TempDir::new().unwrap();
+            std::fs::create_dir_all(temp.path().join("Slack/Cache")).unwrap();
+            std::fs::create_dir_all(temp.path().join("Slack/Service Worker/CacheStorage")).unwrap();
+            std::fs::create_dir_all(temp.path().join("Code/Code Cache")).unwrap();
+            std::fs::create_dir_all(temp.path().join("git")).unwrap();
+            std::fs::write(temp.path().join("Slack/Cache2"), "").unwrap();
+
+            let caches = find_electron_caches(temp.path());
+            let labels: Vec<_> = caches.iter().map(|(label, _)| label.as_str()).collect();
+
+            assert_eq!(
+                labels,
+                vec!["Code Code Cache", "Slack Cache", "Slack Service Worker Cache"]
+            );
+            assert_eq!(caches[1].1, temp.path().join("Slack/Cache"));
+        }
+
+        #[test]
+        fn test_find_flatpak_caches() {
+            let temp = tempfile::TempDir::new().unwrap();
+            std::fs::create_dir_all(temp.path().join("org.mozilla.firefox/cache")).unwrap();
+            std::fs::create_dir_all(temp.path().join("com.slack.Slack/cache")).unwrap();
+            std::fs::create_dir_all(temp.path().join("org.gimp.GIMP/config")).unwrap();
+
+            let caches = find_flatpak_caches(temp.path());
+
+            assert_eq!(caches.len(), 2);
+            assert_eq!(caches[0].0, "com.slack.Slack");
+            assert_eq!(caches[1].1, temp.path().join("org.mozilla.firefox/cache"));
+        }
+
+        #[test]
+        fn test_find_crashpad_dirs() {
+            let temp = tempfile::TempDir::new().unwrap();
+            std::fs::create_dir_all(temp.path().join("google-chrome/Crashpad/completed")).unwrap();
+            std::fs::create_dir_all(temp.path().join("BraveSoftware/Brave-Browser/Crashpad")).unwrap();
+            std::fs::create_dir_all(temp.path().join("Slack/Cache")).unwrap();
+            std::fs::create_dir_all(temp.path().join("Crashpad")).unwrap();
+
+            assert_eq!(
+                find_crashpad_dirs(temp.path()),
+                vec![
+                    temp.path().join("BraveSoftware/Brave-Browser/Crashpad"),
+                    temp.path().join("google-chrome/Crashpad"),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_find_steam_shader_caches() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let root = temp.path().join("Steam/steamapps/shadercache");
+            std::fs::create_dir_all(root.join("570")).unwrap();
+            std::fs::create_dir_all(root.join("1091500")).unwrap();
+            std::fs::write(root.join("notes.txt"), "").unwrap();
+            std::fs::create_dir_all(temp.path().join(".steam")).unwrap();
+            std::os::unix::fs::symlink(temp.path().join("Steam"), temp.path().join(".steam/steam")).unwrap();
+
+            let caches = find_steam_shader_caches(&[
+                root.clone(),
+                temp.path().join(".steam/steam/steamapps/shadercache"),
+                temp.path().join("missing"),
+            ]);
+            let labels: Vec<_> = caches.iter().map(|(label, _)| label.as_str()).collect();
+
+            assert_eq!(labels, vec!["Steam Shader Cache 1091500", "Steam Shader Cache 570"]);
+            assert_eq!(caches[1].1, root.join("570"));
+        }
+
         #[test]
         fn test_dev_artifacts_patterns() {
             let artifacts = DevArtifacts::new();
@@ -182,6 +495,159 @@ mod tests {
         }
     }
 
+    mod state_tests {
+        use crate::core::state::*;
+        use chrono::{Local, TimeZone};
+        use std::path::PathBuf;
+        use std::time::Duration;
+
+        #[test]
+        fn test_state_round_trip() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let path = temp.path().join("nested/state.toml");
+
+            assert_eq!(State::load_from(&path), State::default());
+
+            let mut state = State {
+                last_analyze_path: Some(PathBuf::from("/srv/media")),
+                ..Default::default()
+            };
+            state.mark_cleaned("Thumbnails", chrono::Local::now());
+            state.save_to(&path).unwrap();
+
+            assert_eq!(State::load_from(&path), state);
+        }
+
+        #[test]
+        fn test_cleaned_ago() {
+            let now = Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+            let mut state = State::default();
+            assert_eq!(state.cleaned_ago("Thumbnails", now), None);
+
+            state.mark_cleaned("Thumbnails", now - chrono::Duration::minutes(12));
+            assert_eq!(state.cleaned_ago("Thumbnails", now), Some(Duration::from_secs(720)));
+
+            // A clock set back since the last clean reads as just cleaned
+            state.mark_cleaned("Thumbnails", now + chrono::Duration::hours(1));
+            assert_eq!(state.cleaned_ago("Thumbnails", now), Some(Duration::ZERO));
+
+            state.last_cleaned.insert("Broken".to_string(), "yesterday".to_string());
+            assert_eq!(state.cleaned_ago("Broken", now), None);
+        }
+
+        #[test]
+        fn test_state_path() {
+            assert!(State::state_path().ends_with("mole-rs/state.toml"));
+        }
+    }
+
+    mod time_tests {
+        use crate::core::time::*;
+        use chrono::{Local, TimeZone};
+        use std::time::{Duration, SystemTime};
+
+        #[test]
+        fn test_format_local() {
+            let time = Local.with_ymd_and_hms(2024, 5, 1, 14, 3, 22).unwrap();
+            let stamp = format_local(time);
+
+            assert!(stamp.starts_with("2024-05-01T14:03:22"));
+            assert!(chrono::DateTime::parse_from_rfc3339(&stamp).is_ok());
+        }
+
+        #[test]
+        fn test_parse_duration() {
+            assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+            assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+            assert_eq!(parse_duration("6h"), Ok(Duration::from_secs(6 * 3600)));
+            assert_eq!(parse_duration("2d"), Ok(Duration::from_secs(2 * 86400)));
+            assert!(parse_duration("1y").is_err());
+            assert!(parse_duration("h").is_err());
+            assert!(parse_duration("99999999999999999w").is_err());
+        }
+
+        #[test]
+        fn test_format_duration() {
+            assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+            assert_eq!(format_duration(Duration::from_secs(720)), "12m");
+            assert_eq!(format_duration(Duration::from_secs(3 * 86400 + 5)), "3d");
+        }
+
+        #[test]
+        fn test_age_days() {
+            let now = SystemTime::now();
+
+            assert_eq!(age_days(now - Duration::from_secs(3 * 86400 + 60), now), 3);
+            assert_eq!(age_days(now, now), 0);
+            // Future mtimes (clock skew, restored archives) clamp to zero
+            assert_eq!(age_days(now + Duration::from_secs(86400 * 10), now), 0);
+        }
+    }
+
+    mod output_tests {
+        use crate::core::output::*;
+
+        #[test]
+        fn test_strip_ansi() {
+            assert_eq!(strip_ansi("\x1B[1;32m3.2 GiB\x1B[0m freed"), "3.2 GiB freed");
+            assert_eq!(strip_ansi("\x1B[2J\x1B[Hplain ✓"), "plain ✓");
+            assert_eq!(strip_ansi("no escapes"), "no escapes");
+        }
+
+        #[test]
+        fn test_deletion_prompts_follow_policy() {
+            use crate::core::security::{ConfirmPolicy, SecurityValidator};
+            use std::path::PathBuf;
+
+            let validator = SecurityValidator::new();
+            let tmp = PathBuf::from("/tmp");
+            let cache = PathBuf::from("/home/u/.cache/pip");
+            let targets = [&tmp, &cache];
+            const GIB: u64 = 1024 * 1024 * 1024;
+
+            let prompts = |policy, size| deletion_prompts(policy, &validator, &targets, size, || 10).len();
+            assert_eq!(prompts(ConfirmPolicy::All, 2 * GIB), 2);
+            assert_eq!(prompts(ConfirmPolicy::Large, 2 * GIB), 1);
+            assert_eq!(prompts(ConfirmPolicy::Large, 1024), 0);
+            assert_eq!(prompts(ConfirmPolicy::Caution, 2 * GIB), 1);
+            assert_eq!(prompts(ConfirmPolicy::None, 2 * GIB), 0);
+
+            // Files are only counted when the policy cares about them
+            let counted = std::cell::Cell::new(false);
+            deletion_prompts(ConfirmPolicy::Caution, &validator, &targets, 0, || {
+                counted.set(true);
+                0
+            });
+            assert!(!counted.get());
+            assert_eq!(
+                deletion_prompts(ConfirmPolicy::Large, &validator, &[&cache], 0, || u64::MAX).len(),
+                1
+            );
+        }
+
+        #[test]
+        fn test_item_answer_parse() {
+            assert_eq!(ItemAnswer::parse("y\n"), ItemAnswer::Yes);
+            assert_eq!(ItemAnswer::parse(" A "), ItemAnswer::All);
+            assert_eq!(ItemAnswer::parse("q"), ItemAnswer::Quit);
+            assert_eq!(ItemAnswer::parse(""), ItemAnswer::No);
+            assert_eq!(ItemAnswer::parse("maybe"), ItemAnswer::No);
+        }
+
+        #[test]
+        fn test_item_confirmer_remembers_all_and_quit() {
+            let mut confirmer = ItemConfirmer::default();
+            assert!(!confirmer.record(ItemAnswer::No));
+            assert!(confirmer.record(ItemAnswer::Yes));
+            assert!(confirmer.record(ItemAnswer::All));
+            assert!(confirmer.approve("next", 10));
+
+            let mut confirmer = ItemConfirmer::default();
+            assert!(!confirmer.record(ItemAnswer::Quit));
+            assert!(!confirmer.approve("next", 10));
+        }
+    }
+
     mod config_tests {
         use crate::core::config::*;
 
@@ -190,9 +656,96 @@ mod tests {
             let config = Config::default();
             
             assert!(config.whitelist.is_empty());
+            assert!(config.keep_warm.is_empty());
             assert!(!config.project_paths.is_empty());
             assert_eq!(config.skip_recent_days, 7);
             assert_eq!(config.journal_max_size, "100M");
+            assert_eq!(config.high_water_mark, 85);
+            assert_eq!(config.app_log_max_age_days, 14);
+            assert!(config.app_log_patterns.contains(&"*.log".to_string()));
+            assert_eq!(config.tmp_policy, TmpPolicy::Mine);
+        }
+
+        #[test]
+        fn test_config_tmp_policy() {
+            let config: Config = toml::from_str("tmp_policy = \"old\"").unwrap();
+
+            assert_eq!(config.tmp_policy, TmpPolicy::Old);
+            assert_eq!(config.tmp_max_age_days, 10);
+        }
+
+        #[test]
+        fn test_config_keep_warm() {
+            let config: Config = toml::from_str("keep_warm = [\"pip\", \"~/.cache/go-build\"]").unwrap();
+
+            assert_eq!(config.keep_warm, ["pip", "~/.cache/go-build"]);
+            assert!(config.whitelist.is_empty());
+        }
+
+        #[test]
+        fn test_config_stale_downloads() {
+            let config: Config = toml::from_str("stale_download_min_size = \"1G\"").unwrap();
+
+            assert_eq!(config.stale_download_min_size, "1G");
+            assert_eq!(config.stale_download_days, 90);
+        }
+
+        #[test]
+        fn test_config_check() {
+            let temp = tempfile::TempDir::new().unwrap();
+            let content = format!(
+                "whitelsit = [\"/data\"]\njournal_max_size = \"lots\"\nproject_paths = [{:?}, \"~/Projects\"]\n",
+                temp.path().display().to_string()
+            );
+
+            let issues = Config::check(&content);
+            let messages: Vec<_> = issues.iter().map(|i| i.message.as_str()).collect();
+            assert_eq!(issues.len(), 3, "{:?}", messages);
+            assert_eq!(issues[0].severity, Severity::Error);
+            assert!(messages[0].contains("unknown key `whitelsit`"));
+            assert!(messages[1].starts_with("journal_max_size:"));
+            assert_eq!(issues[2].severity, Severity::Warning);
+            assert!(messages[2].contains("~/Projects does not exist (`~` is not expanded"));
+
+            assert!(Config::check("high_water_mark = 90\n").is_empty());
+        }
+
+        #[test]
+        fn test_config_check_reports_parse_errors() {
+            let syntax = Config::check("skip_recent_days = \n");
+            assert_eq!(syntax.len(), 1);
+            assert_eq!(syntax[0].severity, Severity::Error);
+
+            let types = Config::check("skip_recent_days = \"a week\"\n");
+            assert_eq!(types.len(), 1);
+            assert!(types[0].message.contains("skip_recent_days"));
+        }
+
+        #[test]
+        fn test_config_missing_fields_use_defaults() {
+            let config: Config = toml::from_str("skip_recent_days = 3").unwrap();
+
+            assert_eq!(config.skip_recent_days, 3);
+            assert_eq!(config.high_water_mark, 85);
+        }
+
+        #[test]
+        fn test_validate_profile_name() {
+            assert_eq!(validate_profile_name("server"), Ok("server".to_string()));
+            assert!(validate_profile_name("work-laptop").is_ok());
+            assert!(validate_profile_name("").is_err());
+            assert!(validate_profile_name("../config").is_err());
+            assert!(validate_profile_name("a/b").is_err());
+            assert!(validate_profile_name("..").is_err());
+        }
+
+        #[test]
+        fn test_load_profile() {
+            assert!(Config::profile_path("server").ends_with("mole-rs/profiles/server.toml"));
+
+            // A profile that doesn't exist falls back to defaults
+            let config = Config::load_profile("no-such-profile-here");
+            assert_eq!(config.high_water_mark, 85);
         }
 
         #[test]
@@ -212,9 +765,122 @@ mod tests {
         }
     }
 
+    mod csv_tests {
+        use crate::core::csv::*;
+
+        #[test]
+        fn test_csv_quotes_only_when_needed() {
+            assert_eq!(field("/home/u/.cache"), "/home/u/.cache");
+            assert_eq!(field("/data/a,b"), "\"/data/a,b\"");
+            assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+            assert_eq!(field("two\nlines"), "\"two\nlines\"");
+        }
+
+        #[test]
+        fn test_csv_row() {
+            assert_eq!(row(&["path", "size_bytes"]), "path,size_bytes\n");
+            assert_eq!(
+                row(&["/x,y".to_string(), "10".to_string()]),
+                "\"/x,y\",10\n"
+            );
+        }
+    }
+
+    mod glob_tests {
+        use crate::core::glob::*;
+
+        #[test]
+        fn test_glob_star() {
+            assert!(matches("*.log", "app.log"));
+            assert!(matches("*.log", ".log"));
+            assert!(!matches("*.log", "app.log.1"));
+            assert!(matches("*.log.*", "app.log.1"));
+            assert!(matches("*", ""));
+        }
+
+        #[test]
+        fn test_glob_question_and_literal() {
+            assert!(matches("core.?", "core.1"));
+            assert!(!matches("core.?", "core.12"));
+            assert!(matches("node_modules", "node_modules"));
+            assert!(!matches("node_modules", "node_module"));
+        }
+
+        #[test]
+        fn test_glob_class() {
+            assert!(matches("file[0-9].txt", "file3.txt"));
+            assert!(!matches("file[!0-9].txt", "file3.txt"));
+            assert!(matches("file[!0-9].txt", "fileX.txt"));
+            assert!(matches("[]]", "]"));
+            assert!(matches("a[b", "a[b"));
+        }
+
+        #[test]
+        fn test_glob_backtracking() {
+            assert!(matches("*a*b", "xxaxxab"));
+            assert!(!matches("*a*b", "xxaxxa"));
+            assert!(matches_any(&["*.old", "*.log"], "x.old"));
+        }
+    }
+
+    mod ignore_tests {
+        use crate::core::ignore::*;
+        use std::path::Path;
+
+        #[test]
+        fn test_ignore_names_at_any_depth() {
+            let file = IgnoreFile::parse("# comment\n\ntarget\n*.cache/\n");
+
+            assert!(file.is_ignored(Path::new("target"), true));
+            assert!(file.is_ignored(Path::new("crates/core/target"), true));
+            assert!(file.is_ignored(Path::new("web/.parcel.cache"), true));
+            assert!(!file.is_ignored(Path::new("web/.parcel.cache"), false));
+            assert!(!file.is_ignored(Path::new("node_modules"), true));
+        }
+
+        #[test]
+        fn test_ignore_anchored_and_negated() {
+            let file = IgnoreFile::parse("/build\npackages/*/node_modules\n!packages/cli/node_modules\n");
+
+            assert!(file.is_ignored(Path::new("build"), true));
+            assert!(!file.is_ignored(Path::new("sub/build"), true));
+            assert!(file.is_ignored(Path::new("packages/web/node_modules"), true));
+            assert!(!file.is_ignored(Path::new("packages/cli/node_modules"), true));
+        }
+
+        #[test]
+        fn test_ignore_covers_contents_of_ignored_dir() {
+            let file = IgnoreFile::parse("vendor/\n!vendor/keep\n");
+
+            assert!(file.is_ignored(Path::new("vendor/lib/target"), true));
+            assert!(file.is_ignored(Path::new("vendor/keep"), true));
+        }
+    }
+
     mod system_tests {
         use crate::core::system::*;
 
+        #[test]
+        fn test_freed_since() {
+            let snapshot = |pairs: &[(&str, u64)]| FreeSpaceSnapshot {
+                available: pairs.iter().map(|(m, a)| (m.to_string(), *a)).collect(),
+            };
+            let before = snapshot(&[("/", 1000), ("/home", 5000)]);
+            let after = snapshot(&[("/", 900), ("/home", 8000), ("/mnt", 10)]);
+
+            assert_eq!(after.freed_since(&before), 2900);
+        }
+
+        #[test]
+        fn test_is_freed_discrepancy() {
+            const MIB: u64 = 1024 * 1024;
+
+            assert!(!is_freed_discrepancy(100 * MIB, 99 * MIB as i64));
+            assert!(!is_freed_discrepancy(1000, 0));
+            assert!(is_freed_discrepancy(100 * MIB, 50 * MIB as i64));
+            assert!(is_freed_discrepancy(100 * MIB, -(10 * MIB as i64)));
+        }
+
         #[test]
         fn test_system_info_new() {
             let sysinfo = SystemInfo::new();
@@ -250,6 +916,67 @@ mod tests {
             assert!(sysinfo.used_memory() <= sysinfo.total_memory());
         }
 
+        #[test]
+        fn test_parse_mm_stat() {
+            let stats = parse_mm_stat(
+                "zram0",
+                "  3145728   1048576   1179648        0  1179648      12      0      0\n",
+            )
+            .unwrap();
+
+            assert_eq!(stats.original, 3 * 1024 * 1024);
+            assert_eq!(stats.compressed, 1024 * 1024);
+            assert_eq!(stats.mem_used, 1179648);
+            assert_eq!(stats.ratio(), 3.0);
+            assert!(parse_mm_stat("zram0", "123").is_none());
+        }
+
+        #[test]
+        fn test_zram_devices() {
+            let temp = tempfile::TempDir::new().unwrap();
+            for (name, stat) in [
+                ("zram1", "2000 500 600 0 600 0 0 0"),
+                ("zram0", "0 0 0 0 0 0 0 0"),
+                ("sda", "1 1 1"),
+                ("zram2", "1000 250 300 0 300 0 0 0"),
+            ] {
+                std::fs::create_dir(temp.path().join(name)).unwrap();
+                std::fs::write(temp.path().join(name).join("mm_stat"), stat).unwrap();
+            }
+
+            let devices = zram_devices(temp.path());
+            let names: Vec<_> = devices.iter().map(|d| d.device.as_str()).collect();
+            assert_eq!(names, ["zram1", "zram2"]);
+
+            let total = zram_total(&devices).unwrap();
+            assert_eq!((total.original, total.compressed, total.mem_used), (3000, 750, 900));
+            assert!(zram_total(&[]).is_none());
+        }
+
+        #[test]
+        fn test_memory_pressure_counts_zram_uncompressed() {
+            let zram = ZramStats {
+                device: "zram".to_string(),
+                original: 3000,
+                compressed: 900,
+                mem_used: 1000,
+            };
+
+            assert_eq!(memory_pressure(5000, 10000, None), 50.0);
+            assert_eq!(memory_pressure(5000, 10000, Some(&zram)), 70.0);
+            assert_eq!(memory_pressure(5000, 0, Some(&zram)), 0.0);
+        }
+
+        #[test]
+        fn test_swap_usage_range() {
+            let sysinfo = SystemInfo::new();
+
+            match sysinfo.swap_usage() {
+                Some(swap) => assert!((0.0..=100.0).contains(&swap)),
+                None => assert_eq!(sysinfo.total_swap(), 0),
+            }
+        }
+
         #[test]
         fn test_disk_info_not_empty() {
             let sysinfo = SystemInfo::new();
@@ -270,6 +997,15 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_disk_for_path() {
+            let sysinfo = SystemInfo::new();
+
+            if let Some(disk) = sysinfo.disk_for_path(std::path::Path::new("/usr/bin")) {
+                assert!(std::path::Path::new("/usr/bin").starts_with(&disk.mount_point));
+            }
+        }
+
         #[test]
         fn test_uptime() {
             let sysinfo = SystemInfo::new();
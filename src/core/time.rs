@@ -0,0 +1,63 @@
+//! Timestamps and ages shown to the user or written to files
+
+use chrono::{DateTime, Local};
+use std::time::{Duration, SystemTime};
+
+/// Format a time as an ISO-8601 local timestamp, e.g. `2024-05-01T14:03:22+02:00`
+pub fn format_local(time: DateTime<Local>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S%:z").to_string()
+}
+
+/// The current local time as an ISO-8601 timestamp
+pub fn local_timestamp() -> String {
+    format_local(Local::now())
+}
+
+/// Whole days between `modified` and `now`
+///
+/// Times in the future (clock skew, files restored from an archive) count
+/// as zero days old rather than failing.
+pub fn age_days(modified: SystemTime, now: SystemTime) -> u64 {
+    now.duration_since(modified)
+        .map(|age| age.as_secs() / 86400)
+        .unwrap_or(0)
+}
+
+/// Parse a duration such as `90s`, `30m`, `6h`, `2d` or `1w`
+///
+/// A bare number is taken as seconds.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", input))?;
+    let seconds = match unit.trim() {
+        "" | "s" => 1,
+        "m" | "min" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => return Err(format!("unknown duration unit in '{}' (use s, m, h, d or w)", input)),
+    };
+
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration '{}' is too long", input))
+}
+
+/// Short form of a duration in its largest whole unit, e.g. `12m` or `3d`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        s if s >= 86400 => format!("{}d", s / 86400),
+        s if s >= 3600 => format!("{}h", s / 3600),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
@@ -0,0 +1,332 @@
+//! freedesktop.org Trash support
+//!
+//! Items live in `Trash/files/<name>` with a matching
+//! `Trash/info/<name>.trashinfo` that records the original path and the
+//! deletion time.
+
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+
+use super::errors::{MoleError, Result};
+
+/// A trashed file or directory
+#[derive(Debug, Clone)]
+pub struct TrashedItem {
+    /// Where the item was before it was trashed
+    pub original_path: PathBuf,
+    pub deleted_at: Option<NaiveDateTime>,
+    /// Location of the item under `Trash/files`
+    pub file_path: PathBuf,
+    pub info_path: PathBuf,
+}
+
+/// Parse the contents of a `.trashinfo` file
+///
+/// Returns the (decoded) original path and the deletion date, if present.
+pub fn parse_trash_info(content: &str) -> Option<(PathBuf, Option<NaiveDateTime>)> {
+    let mut lines = content.lines().map(str::trim);
+
+    if !lines.any(|l| l == "[Trash Info]") {
+        return None;
+    }
+
+    let mut path = None;
+    let mut deleted_at = None;
+
+    for line in lines {
+        if line.starts_with('[') {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("Path=") {
+            path = Some(PathBuf::from(percent_decode(value)));
+        } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+            deleted_at = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").ok();
+        }
+    }
+
+    Some((path?, deleted_at))
+}
+
+/// Move `path` into the trash at `trash`, recording where it came from so
+/// `mo restore` (or a file manager) can put it back
+///
+/// Only a rename is attempted: a file on another filesystem than the trash
+/// fails instead of being copied. Returns the location under `Trash/files`.
+pub fn move_to_trash(path: &Path, trash: &Path) -> Result<PathBuf> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| MoleError::Other(format!("{} has no file name", path.display())))?;
+    let files = trash.join("files");
+    let info = trash.join("info");
+    std::fs::create_dir_all(&files)?;
+    std::fs::create_dir_all(&info)?;
+
+    let content = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(&path.to_string_lossy()),
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+    // Claim a free name by creating its info file first, as the spec asks;
+    // only a taken name moves on to the next one
+    let mut n = 1;
+    let (info_path, file_path) = loop {
+        let trashed = if n == 1 {
+            name.clone()
+        } else {
+            format!("{}.{}", name, n)
+        };
+        let (info_path, file_path) = (info.join(format!("{}.trashinfo", trashed)), files.join(trashed));
+        n += 1;
+        if file_path.symlink_metadata().is_ok() {
+            continue;
+        }
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(_) => break (info_path, file_path),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    std::fs::write(&info_path, content)?;
+    if let Err(e) = std::fs::rename(path, &file_path) {
+        let _ = std::fs::remove_file(&info_path);
+        return Err(e.into());
+    }
+
+    Ok(file_path)
+}
+
+/// Encode a path for the `Path=` key, keeping `/` and unreserved characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes as used by the `Path=` key
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// List trashed items under `trash`, most recently deleted first
+///
+/// Info files whose payload has disappeared are skipped.
+pub fn list_trashed(trash: &Path) -> Vec<TrashedItem> {
+    let Ok(entries) = std::fs::read_dir(trash.join("info")) else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<TrashedItem> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let info_path = entry.path();
+            let name = info_path.file_name()?.to_str()?.strip_suffix(".trashinfo")?;
+            let file_path = trash.join("files").join(name);
+
+            if file_path.symlink_metadata().is_err() {
+                return None;
+            }
+
+            let content = std::fs::read_to_string(&info_path).ok()?;
+            let (original_path, deleted_at) = parse_trash_info(&content)?;
+
+            Some(TrashedItem {
+                original_path,
+                deleted_at,
+                file_path,
+                info_path,
+            })
+        })
+        .collect();
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    items
+}
+
+/// Move a trashed item back to its original location
+///
+/// If something already exists there, the item is only restored when
+/// `rename` is set, under the first free `<name>.restored[-N]` path.
+/// Returns where the item ended up.
+pub fn restore(item: &TrashedItem, rename: bool) -> Result<PathBuf> {
+    let mut target = item.original_path.clone();
+
+    if target.symlink_metadata().is_ok() {
+        if !rename {
+            return Err(MoleError::Other(format!(
+                "{} already exists",
+                target.display()
+            )));
+        }
+        target = free_suffixed_path(&item.original_path);
+    }
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::rename(&item.file_path, &target)?;
+    std::fs::remove_file(&item.info_path)?;
+
+    Ok(target)
+}
+
+fn free_suffixed_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| {
+            let suffix = if n == 1 {
+                ".restored".to_string()
+            } else {
+                format!(".restored-{}", n)
+            };
+            path.with_file_name(format!("{}{}", name, suffix))
+        })
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .expect("an unused suffix always exists")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn trash_file(trash: &Path, name: &str, original: &Path, date: &str) {
+        fs::create_dir_all(trash.join("files")).unwrap();
+        fs::create_dir_all(trash.join("info")).unwrap();
+        fs::write(trash.join("files").join(name), name).unwrap();
+        fs::write(
+            trash.join("info").join(format!("{}.trashinfo", name)),
+            format!(
+                "[Trash Info]\nPath={}\nDeletionDate={}\n",
+                original.display(),
+                date
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_parse_trash_info() {
+        let (path, date) = parse_trash_info(
+            "[Trash Info]\nPath=/home/user/My%20Notes.txt\nDeletionDate=2024-03-01T10:20:30\n",
+        )
+        .unwrap();
+
+        assert_eq!(path, PathBuf::from("/home/user/My Notes.txt"));
+        assert_eq!(date.unwrap().to_string(), "2024-03-01 10:20:30");
+    }
+
+    #[test]
+    fn test_parse_trash_info_rejects_garbage() {
+        assert!(parse_trash_info("Path=/tmp/x").is_none());
+        assert!(parse_trash_info("[Trash Info]\nDeletionDate=2024-03-01T10:20:30").is_none());
+    }
+
+    #[test]
+    fn test_percent_decode_keeps_invalid_escapes() {
+        assert_eq!(percent_decode("a%2Fb%zz%4"), "a/b%zz%4");
+    }
+
+    #[test]
+    fn test_list_trashed_newest_first() {
+        let temp = TempDir::new().unwrap();
+        let trash = temp.path().join("Trash");
+        trash_file(&trash, "old.txt", &temp.path().join("old.txt"), "2024-01-01T00:00:00");
+        trash_file(&trash, "new.txt", &temp.path().join("new.txt"), "2024-06-01T00:00:00");
+
+        let items = list_trashed(&trash);
+        assert_eq!(items.len(), 2);
+        assert!(items[0].original_path.ends_with("new.txt"));
+    }
+
+    #[test]
+    fn test_move_to_trash_round_trips() {
+        let temp = TempDir::new().unwrap();
+        let trash = temp.path().join("Trash");
+        let original = temp.path().join("old image.iso");
+        fs::write(&original, "iso").unwrap();
+
+        let trashed = move_to_trash(&original, &trash).unwrap();
+        assert!(!original.exists());
+        assert_eq!(trashed, trash.join("files/old image.iso"));
+
+        // A second file of the same name gets its own slot
+        fs::write(&original, "iso 2").unwrap();
+        let second = move_to_trash(&original, &trash).unwrap();
+        assert_eq!(second, trash.join("files/old image.iso.2"));
+
+        let items = list_trashed(&trash);
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| i.original_path == original));
+
+        restore(&items[0], true).unwrap();
+        assert!(original.exists());
+    }
+
+    #[test]
+    fn test_move_to_trash_reports_errors() {
+        let temp = TempDir::new().unwrap();
+        let trash = temp.path().join("Trash");
+        // Fits as a file name, but not with .trashinfo appended
+        let original = temp.path().join("x".repeat(250));
+        fs::write(&original, "data").unwrap();
+
+        assert!(move_to_trash(&original, &trash).is_err());
+        assert!(original.exists());
+    }
+
+    #[test]
+    fn test_restore_conflict_needs_rename() {
+        let temp = TempDir::new().unwrap();
+        let trash = temp.path().join("Trash");
+        let original = temp.path().join("notes.txt");
+        trash_file(&trash, "notes.txt", &original, "2024-01-01T00:00:00");
+        fs::write(&original, "new version").unwrap();
+
+        let item = &list_trashed(&trash)[0];
+        assert!(restore(item, false).is_err());
+
+        let restored = restore(item, true).unwrap();
+        assert_eq!(restored, temp.path().join("notes.txt.restored"));
+        assert_eq!(fs::read_to_string(&original).unwrap(), "new version");
+        assert!(list_trashed(&trash).is_empty());
+    }
+}
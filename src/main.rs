@@ -2,8 +2,11 @@
 //!
 //! A Rust-based system cleanup tool inspired by tw93/Mole
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tracing_subscriber::filter::LevelFilter;
 
 mod cli;
 mod commands;
@@ -12,36 +15,251 @@ mod tui;
 
 use cli::Args;
 
-fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
-
+/// Exit codes: 0 when work was done (or shown), `EXIT_NOTHING_TO_DO` (4)
+/// when clean/purge found nothing, `EXIT_INTERRUPTED` (130) when a signal
+/// stopped a deletion, and 1 on errors
+fn main() -> Result<ExitCode> {
     let args = Args::parse();
 
-    match args.command {
-        Some(cli::Command::Clean { dry_run, debug }) => {
-            commands::clean::run(dry_run, debug)?;
-        }
-        Some(cli::Command::Analyze { path }) => {
-            commands::analyze::run(path)?;
-        }
-        Some(cli::Command::Status) => {
-            commands::status::run()?;
-        }
-        Some(cli::Command::Purge { paths, dry_run }) => {
-            commands::purge::run(paths, dry_run)?;
-        }
-        Some(cli::Command::Optimize { dry_run }) => {
-            commands::optimize::run(dry_run)?;
-        }
-        Some(cli::Command::Uninstall { app_name, dry_run, list }) => {
-            commands::uninstall::run(app_name, dry_run, list)?;
+    // Initialize logging; `clean --debug` counts as one level of verbosity too
+    let debug = args.debug || matches!(args.command, Some(cli::Command::Clean { debug: true, .. }));
+    tracing_subscriber::fmt()
+        .with_max_level(log_level(debug, args.verbose, args.quiet))
+        .with_writer(std::io::stderr)
+        .init();
+
+    core::output::set_quiet(args.quiet);
+
+    if let Some(path) = &args.log_file {
+        core::output::set_log_file(path)
+            .with_context(|| format!("cannot open log file {}", path.display()))?;
+    }
+
+    if let Some(name) = &args.profile {
+        core::config::Config::set_profile(name).map_err(anyhow::Error::msg)?;
+        let path = core::config::Config::profile_path(name);
+        if !path.exists() {
+            warnln!(
+                "Profile '{}' not found at {}; using defaults.",
+                name,
+                path.display()
+            );
         }
+    }
+
+    if args.disk_usage {
+        core::filesystem::set_size_mode(core::filesystem::SizeMode::DiskUsage);
+    }
+
+    let timeout = args
+        .timeout
+        .unwrap_or_else(|| core::config::Config::load().scan_timeout_secs);
+    core::filesystem::set_scan_timeout(std::time::Duration::from_secs(timeout));
+
+    if args.version_json {
+        commands::version::run_json()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Held until the command finishes, even if it fails
+    let _lock = match &args.command {
+        Some(command) if command.is_destructive() => Some(core::lock::InstanceLock::acquire(
+            &core::lock::InstanceLock::default_path(),
+            args.wait,
+        )?),
+        _ => None,
+    };
+
+    match args.command {
+        Some(command) => run_command(command, args.report)?,
         None => {
             // Launch interactive TUI
             tui::run()?;
         }
     }
 
-    Ok(())
+    if core::interrupt::interrupted() {
+        return Ok(ExitCode::from(core::interrupt::EXIT_INTERRUPTED));
+    }
+    if core::output::nothing_to_do() {
+        return Ok(ExitCode::from(core::output::EXIT_NOTHING_TO_DO));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Pick the tracing level from the flags, letting `RUST_LOG=<level>` override it
+///
+/// Default is info; `--debug`/`-v` raise it to debug and `-vv` to trace.
+fn log_level(debug: bool, verbose: u8, quiet: bool) -> LevelFilter {
+    if let Some(level) = std::env::var("RUST_LOG").ok().and_then(|v| v.parse().ok()) {
+        return level;
+    }
+
+    match verbose.max(u8::from(debug)) {
+        _ if quiet => LevelFilter::WARN,
+        0 => LevelFilter::INFO,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Dispatch a parsed subcommand to its implementation
+fn run_command(command: cli::Command, report: Option<PathBuf>) -> Result<()> {
+    match command {
+        cli::Command::Clean {
+            apply,
+            dry_run,
+            debug,
+            categories,
+            include_system,
+            groups,
+            verify,
+            keep_free,
+            backup,
+            parallel,
+            skip_if_cleaned_within,
+            list_files,
+            no_recent_protection,
+            preset,
+            confirm_each,
+            confirm_policy,
+            stale_downloads,
+            files_only,
+        } => {
+            warn_deprecated_dry_run(dry_run);
+            commands::clean::run(commands::clean::CleanOptions {
+                dry_run: !apply,
+                debug,
+                list_only: categories,
+                include_system,
+                report,
+                groups,
+                verify,
+                keep_free,
+                backup,
+                parallel: parallel.map(usize::from),
+                skip_if_cleaned_within,
+                list_files,
+                no_recent_protection,
+                preset,
+                confirm_each,
+                confirm_policy,
+                stale_downloads,
+                files_only,
+            })
+        }
+        cli::Command::Analyze {
+            paths,
+            age_histogram,
+            by_type,
+            suggest_compress,
+            duplicates,
+            jobs,
+            min_size,
+            filters,
+            excludes,
+            summary_only,
+            dereference,
+            group_by_fs,
+            min_percent,
+            format,
+        } => commands::analyze::run(
+            paths,
+            commands::analyze::AnalyzeOptions {
+                age_histogram,
+                by_type,
+                suggest_compress,
+                duplicates,
+                jobs: jobs.map(usize::from),
+                min_size,
+                filters,
+                excludes,
+                summary_only,
+                dereference,
+                group_by_fs,
+                min_percent,
+                format,
+            },
+        ),
+        cli::Command::Scan { format, json } => {
+            commands::scan::run(if json { core::output::OutputFormat::Json } else { format })
+        }
+        cli::Command::Status { once, json } => commands::status::run(once, json),
+        cli::Command::Purge {
+            paths,
+            max_depth,
+            apply,
+            dry_run,
+            verify,
+            backup,
+            include_global_caches,
+            no_recent_protection,
+            confirm_each,
+            confirm_policy,
+        } => {
+            warn_deprecated_dry_run(dry_run);
+            commands::purge::run(commands::purge::PurgeOptions {
+                paths,
+                max_depth: usize::from(max_depth),
+                dry_run: !apply,
+                verify,
+                backup,
+                include_global_caches,
+                no_recent_protection,
+                confirm_each,
+                confirm_policy,
+                report,
+            })
+        }
+        cli::Command::Optimize {
+            dry_run,
+            autoclean,
+            clear_recent,
+            only,
+            skip,
+        } => commands::optimize::run(
+            dry_run,
+            autoclean,
+            clear_recent,
+            commands::optimize::TaskSelection { only, skip },
+            report,
+        ),
+        cli::Command::Config { action } => match action {
+            cli::ConfigAction::Check => commands::config::run_check(),
+        },
+        cli::Command::Restore { index, rename } => commands::restore::run(index, rename),
+        cli::Command::Uninstall {
+            app_name,
+            dry_run,
+            list,
+            depth,
+            paths,
+            from,
+            all_matches,
+            app_type,
+            exact,
+        } => commands::uninstall::run(
+            app_name,
+            commands::uninstall::UninstallOptions {
+                dry_run,
+                list_only: list,
+                search: commands::uninstall::LeftoverSearch {
+                    depth: depth.into(),
+                    paths,
+                },
+                from,
+                all_matches,
+                app_type,
+                exact,
+                report,
+            },
+        ),
+    }
+}
+
+/// `clean`/`purge` preview by default; `--dry-run` is kept so old scripts still parse
+fn warn_deprecated_dry_run(dry_run: bool) {
+    if dry_run {
+        eprintln!("warning: --dry-run is the default now and will be removed; pass --apply to delete");
+    }
 }
@@ -1,21 +1,77 @@
 //! Application state
 
-use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use std::sync::mpsc::{self, Receiver};
 
+use super::purge::PurgeSelection;
+use crate::cli::{self, Args};
 use crate::commands;
+use crate::commands::purge::FoundArtifact;
+use crate::core::config::Config;
 
 /// Application state enum
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AppState {
     Menu,
+    /// Keybinding overlay shown over the menu
+    Help,
+    /// Checklist of purge artifacts to pick from
+    PurgeSelection,
     Exiting,
 }
 
-/// Menu item
+/// Menu item, one per CLI subcommand
 pub struct MenuItem {
-    pub name: &'static str,
-    pub description: &'static str,
-    pub shortcut: char,
+    pub name: String,
+    pub description: String,
+    /// Number key that selects the item (only the first nine have one)
+    pub shortcut: Option<char>,
+    /// Arguments passed to `mo` when the item is chosen
+    pub args: Vec<String>,
+    /// Space the command could free, for commands that can estimate it
+    pub reclaimable: Option<Reclaimable>,
+}
+
+/// Reclaimable-space estimate shown next to a menu item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reclaimable {
+    Scanning,
+    Bytes(u64),
+}
+
+/// Background scan that estimates how much a subcommand would free
+fn estimator(subcommand: &str) -> Option<fn() -> u64> {
+    match subcommand {
+        "clean" => Some(|| {
+            commands::clean::scan_categories(true)
+                .iter()
+                .filter(|c| c.selected)
+                .map(|c| c.size)
+                .sum()
+        }),
+        "purge" => Some(|| {
+            commands::purge::scan_artifacts(
+                &Config::load().project_paths,
+                commands::purge::DEFAULT_MAX_DEPTH,
+            )
+            .iter()
+            .filter(|a| a.selected)
+            .map(|a| a.size)
+            .sum()
+        }),
+        _ => None,
+    }
+}
+
+/// Extra arguments a subcommand gets when launched from the menu
+fn menu_args(subcommand: &str) -> &'static [&'static str] {
+    match subcommand {
+        // There's no app name to act on from the menu, so just list them
+        "uninstall" => &["--list"],
+        // The only config action so far
+        "config" => &["check"],
+        _ => &[],
+    }
 }
 
 /// Main application
@@ -23,47 +79,80 @@ pub struct App {
     pub state: AppState,
     pub selection: usize,
     pub menu_items: Vec<MenuItem>,
-    pub selected_action: Option<Box<dyn FnOnce() -> Result<()>>>,
+    pub selected_action: Option<cli::Command>,
+    estimates: Option<Receiver<(usize, u64)>>,
+    /// Purge checklist, `None` while its scan is still running
+    pub purge: Option<PurgeSelection>,
+    purge_scan: Option<Receiver<Vec<FoundArtifact>>>,
+    /// Whether the user confirmed deleting the checked artifacts
+    pub purge_confirmed: bool,
 }
 
 impl App {
     pub fn new() -> Self {
+        let menu_items = Args::command()
+            .get_subcommands()
+            .filter(|sub| !sub.is_hide_set())
+            .enumerate()
+            .map(|(i, sub)| {
+                let name = sub.get_name();
+                let mut args = vec![name.to_string()];
+                args.extend(menu_args(name).iter().map(|a| a.to_string()));
+
+                MenuItem {
+                    name: capitalize(name),
+                    description: sub.get_about().map(|a| a.to_string()).unwrap_or_default(),
+                    shortcut: char::from_digit(i as u32 + 1, 10).filter(|_| i < 9),
+                    args,
+                    reclaimable: None,
+                }
+            })
+            .collect();
+
         Self {
             state: AppState::Menu,
             selection: 0,
-            menu_items: vec![
-                MenuItem {
-                    name: "Clean",
-                    description: "Free up disk space by cleaning caches",
-                    shortcut: '1',
-                },
-                MenuItem {
-                    name: "Uninstall",
-                    description: "Remove apps and leftover files",
-                    shortcut: '2',
-                },
-                MenuItem {
-                    name: "Analyze",
-                    description: "Explore disk usage visually",
-                    shortcut: '3',
-                },
-                MenuItem {
-                    name: "Status",
-                    description: "Monitor system health in real-time",
-                    shortcut: '4',
-                },
-                MenuItem {
-                    name: "Purge",
-                    description: "Clean development project artifacts",
-                    shortcut: '5',
-                },
-                MenuItem {
-                    name: "Optimize",
-                    description: "Run system maintenance tasks",
-                    shortcut: '6',
-                },
-            ],
+            menu_items,
             selected_action: None,
+            estimates: None,
+            purge: None,
+            purge_scan: None,
+            purge_confirmed: false,
+        }
+    }
+
+    /// Start sizing reclaimable space in the background
+    ///
+    /// Items that support it show "scanning…" until `poll_estimates` picks
+    /// up their result.
+    pub fn start_estimates(&mut self) {
+        let (tx, rx) = mpsc::channel();
+
+        for (index, item) in self.menu_items.iter_mut().enumerate() {
+            let Some(estimate) = item.args.first().and_then(|name| estimator(name)) else {
+                continue;
+            };
+
+            item.reclaimable = Some(Reclaimable::Scanning);
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send((index, estimate()));
+            });
+        }
+
+        self.estimates = Some(rx);
+    }
+
+    /// Apply any estimates that finished since the last call
+    pub fn poll_estimates(&mut self) {
+        let Some(rx) = &self.estimates else {
+            return;
+        };
+
+        for (index, size) in rx.try_iter() {
+            if let Some(item) = self.menu_items.get_mut(index) {
+                item.reclaimable = Some(Reclaimable::Bytes(size));
+            }
         }
     }
 
@@ -73,22 +162,86 @@ impl App {
         self.selection = new_sel as usize;
     }
 
-    pub fn select_action(&mut self) {
-        self.selected_action = match self.selection {
-            0 => Some(Box::new(|| commands::clean::run(false, false))),
-            1 => Some(Box::new(|| commands::uninstall::run(None, false, true))), // List mode
-            2 => Some(Box::new(|| {
-                let home = dirs::home_dir()
-                    .map(|p| p.to_string_lossy().to_string())
-                    .unwrap_or_else(|| ".".to_string());
-                commands::analyze::run(home)
-            })),
-            3 => Some(Box::new(|| commands::status::run())),
-            4 => Some(Box::new(|| commands::purge::run(None, false))),
-            5 => Some(Box::new(|| commands::optimize::run(false))),
-            _ => None,
+    /// Jump to the item bound to a number key, returning whether one matched
+    pub fn select_shortcut(&mut self, key: char) -> bool {
+        match self
+            .menu_items
+            .iter()
+            .position(|item| item.shortcut == Some(key))
+        {
+            Some(index) => {
+                self.selection = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Show or hide the keybinding overlay
+    pub fn toggle_help(&mut self) {
+        self.state = match self.state {
+            AppState::Help => AppState::Menu,
+            _ => AppState::Help,
         };
     }
+
+    /// Act on the highlighted item: purge opens its checklist, everything
+    /// else leaves the TUI to run the command
+    pub fn choose(&mut self) {
+        let command = self.menu_items.get(self.selection).and_then(|item| item.args.first());
+        if command.is_some_and(|name| name == "purge") {
+            self.start_purge_selection();
+            return;
+        }
+
+        self.select_action();
+        if self.selected_action.is_some() {
+            self.state = AppState::Exiting;
+        }
+    }
+
+    /// Switch to the purge checklist and scan for artifacts in the background
+    pub fn start_purge_selection(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(commands::purge::scan_artifacts(
+                &Config::load().project_paths,
+                commands::purge::DEFAULT_MAX_DEPTH,
+            ));
+        });
+
+        self.purge = None;
+        self.purge_scan = Some(rx);
+        self.state = AppState::PurgeSelection;
+    }
+
+    /// Fill in the purge checklist once its scan is done
+    pub fn poll_purge_scan(&mut self) {
+        let Some(rx) = &self.purge_scan else {
+            return;
+        };
+
+        if let Ok(artifacts) = rx.try_recv() {
+            self.purge = Some(PurgeSelection::new(artifacts));
+            self.purge_scan = None;
+        }
+    }
+
+    /// Leave the checklist, deleting the checked artifacts after the TUI closes
+    pub fn confirm_purge(&mut self) {
+        if self.purge.as_ref().is_some_and(|p| p.selected_total().0 > 0) {
+            self.purge_confirmed = true;
+            self.state = AppState::Exiting;
+        }
+    }
+
+    pub fn select_action(&mut self) {
+        self.selected_action = self.menu_items.get(self.selection).and_then(|item| {
+            Args::try_parse_from(std::iter::once("mo".to_string()).chain(item.args.clone()))
+                .ok()
+                .and_then(|args| args.command)
+        });
+    }
 }
 
 impl Default for App {
@@ -96,3 +249,83 @@ impl Default for App {
         Self::new()
     }
 }
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_covers_every_subcommand() {
+        let app = App::new();
+        let subcommands = Args::command().get_subcommands().count();
+
+        assert_eq!(app.menu_items.len(), subcommands);
+        assert_eq!(app.menu_items[0].shortcut, Some('1'));
+    }
+
+    #[test]
+    fn test_every_menu_item_parses() {
+        let mut app = App::new();
+
+        for i in 0..app.menu_items.len() {
+            app.selection = i;
+            app.select_action();
+            assert!(app.selected_action.is_some(), "{}", app.menu_items[i].name);
+        }
+    }
+
+    #[test]
+    fn test_start_estimates_marks_sizable_items() {
+        let mut app = App::new();
+        app.start_estimates();
+
+        let item = |name: &str| app.menu_items.iter().find(|i| i.args[0] == name).unwrap();
+        assert_eq!(item("clean").reclaimable, Some(Reclaimable::Scanning));
+        assert_eq!(item("purge").reclaimable, Some(Reclaimable::Scanning));
+        assert_eq!(item("status").reclaimable, None);
+    }
+
+    #[test]
+    fn test_select_shortcut() {
+        let mut app = App::new();
+
+        assert!(app.select_shortcut('3'));
+        assert_eq!(app.selection, 2);
+        assert!(!app.select_shortcut('0'));
+        assert_eq!(app.selection, 2);
+    }
+
+    #[test]
+    fn test_choose_purge_opens_checklist() {
+        let mut app = App::new();
+        app.selection = app.menu_items.iter().position(|i| i.args[0] == "purge").unwrap();
+
+        app.choose();
+        assert_eq!(app.state, AppState::PurgeSelection);
+        assert!(app.selected_action.is_none());
+
+        // Nothing checked yet, so there is nothing to confirm
+        app.purge = Some(PurgeSelection::default());
+        app.confirm_purge();
+        assert_eq!(app.state, AppState::PurgeSelection);
+        assert!(!app.purge_confirmed);
+    }
+
+    #[test]
+    fn test_toggle_help() {
+        let mut app = App::new();
+
+        app.toggle_help();
+        assert_eq!(app.state, AppState::Help);
+        app.toggle_help();
+        assert_eq!(app.state, AppState::Menu);
+    }
+}
@@ -1,14 +1,15 @@
 //! Main menu rendering
 
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
-use super::app::App;
+use super::app::{App, Reclaimable};
+use crate::core::filesystem::format_size;
 
 /// Render the main menu
 pub fn render_menu(f: &mut Frame, app: &App) {
@@ -24,7 +25,7 @@ pub fn render_menu(f: &mut Frame, app: &App) {
 
     render_header(f, chunks[0]);
     render_menu_items(f, chunks[1], app);
-    render_footer(f, chunks[2]);
+    render_footer(f, chunks[2], app);
 }
 
 fn render_header(f: &mut Frame, area: ratatui::layout::Rect) {
@@ -68,15 +69,36 @@ fn render_menu_items(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
             };
 
             let prefix = if is_selected { "▶ " } else { "  " };
-            let shortcut = format!("[{}] ", item.shortcut);
+            let shortcut = item
+                .shortcut
+                .map(|c| format!("[{}] ", c))
+                .unwrap_or_else(|| "    ".to_string());
 
-            let content = Line::from(vec![
+            let mut spans = vec![
                 Span::styled(prefix, style),
                 Span::styled(shortcut, Style::default().fg(Color::Yellow)),
-                Span::styled(item.name, style.add_modifier(Modifier::BOLD)),
-                Span::raw("  "),
-                Span::styled(item.description, Style::default().fg(Color::DarkGray)),
-            ]);
+                Span::styled(item.name.as_str(), style.add_modifier(Modifier::BOLD)),
+            ];
+
+            match item.reclaimable {
+                Some(Reclaimable::Scanning) => spans.push(Span::styled(
+                    " — scanning…",
+                    Style::default().fg(Color::DarkGray),
+                )),
+                Some(Reclaimable::Bytes(size)) => spans.push(Span::styled(
+                    format!(" — {} reclaimable", format_size(size)),
+                    Style::default().fg(Color::Green),
+                )),
+                None => {}
+            }
+
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                item.description.as_str(),
+                Style::default().fg(Color::DarkGray),
+            ));
+
+            let content = Line::from(spans);
 
             ListItem::new(content)
         })
@@ -94,14 +116,23 @@ fn render_menu_items(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
     f.render_widget(menu, area);
 }
 
-fn render_footer(f: &mut Frame, area: ratatui::layout::Rect) {
+fn render_footer(f: &mut Frame, area: ratatui::layout::Rect, app: &App) {
+    let last_shortcut = app
+        .menu_items
+        .iter()
+        .rev()
+        .find_map(|item| item.shortcut)
+        .unwrap_or('1');
+
     let help = Line::from(vec![
         Span::styled("↑↓", Style::default().fg(Color::Yellow)),
         Span::raw(" Navigate   "),
         Span::styled("Enter", Style::default().fg(Color::Yellow)),
         Span::raw(" Select   "),
-        Span::styled("1-5", Style::default().fg(Color::Yellow)),
+        Span::styled(format!("1-{}", last_shortcut), Style::default().fg(Color::Yellow)),
         Span::raw(" Quick select   "),
+        Span::styled("?", Style::default().fg(Color::Yellow)),
+        Span::raw(" Help   "),
         Span::styled("q", Style::default().fg(Color::Yellow)),
         Span::raw(" Quit"),
     ]);
@@ -112,3 +143,52 @@ fn render_footer(f: &mut Frame, area: ratatui::layout::Rect) {
 
     f.render_widget(footer, area);
 }
+
+/// Keys shown in the help overlay
+const KEYBINDINGS: &[(&str, &str)] = &[
+    ("↑/↓, j/k", "Move selection"),
+    ("Enter, Space", "Run the selected action"),
+    ("1-9", "Run an action by number"),
+    ("?", "Toggle this help"),
+    ("Esc", "Close help / quit"),
+    ("q", "Quit"),
+];
+
+/// Render the keybinding help as a popup over the menu
+pub fn render_help(f: &mut Frame) {
+    let width = 44;
+    let height = KEYBINDINGS.len() as u16 + 4;
+    let area = centered_rect(width, height, f.size());
+
+    let mut lines = vec![Line::from("")];
+    lines.extend(KEYBINDINGS.iter().map(|(keys, action)| {
+        Line::from(vec![
+            Span::styled(format!("  {:<14}", keys), Style::default().fg(Color::Yellow)),
+            Span::raw(*action),
+        ])
+    }));
+
+    let help = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(" Keybindings ")
+            .title_alignment(Alignment::Center),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
+}
+
+/// A `width` x `height` rect centered in `area`, clamped to fit
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
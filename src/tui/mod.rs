@@ -2,8 +2,11 @@
 
 mod app;
 mod menu;
+mod picker;
+mod purge;
 
 pub use app::App;
+pub use picker::pick;
 
 use anyhow::Result;
 use crossterm::{
@@ -14,6 +17,8 @@ use crossterm::{
 use ratatui::prelude::*;
 use std::io;
 
+use crate::core::lock::InstanceLock;
+use crate::core::security::ConfirmPolicy;
 use app::AppState;
 
 /// Run the interactive TUI
@@ -27,6 +32,7 @@ pub fn run() -> Result<()> {
 
     // Create app state
     let mut app = App::new();
+    app.start_estimates();
 
     // Run main loop
     let result = run_app(&mut terminal, &mut app);
@@ -47,18 +53,54 @@ pub fn run() -> Result<()> {
     }
 
     // Execute selected action if any
-    if let Some(action) = app.selected_action.take() {
-        action()?;
+    if let Some(command) = app.selected_action.take() {
+        crate::run_command(command, None)?;
+    }
+
+    if app.purge_confirmed {
+        if let Some(selection) = app.purge.take() {
+            delete_purge_selection(selection)?;
+        }
     }
 
     Ok(())
 }
 
+/// Delete what was checked in the purge checklist, once confirmed on the terminal
+fn delete_purge_selection(selection: purge::PurgeSelection) -> Result<()> {
+    let (count, size) = selection.selected_total();
+    let prompt = format!(
+        "Delete {} selected artifacts ({})?",
+        count,
+        crate::core::filesystem::format_size(size)
+    );
+    if !crate::core::output::confirm(&prompt) {
+        crate::outln!("Aborted, nothing was deleted.");
+        return Ok(());
+    }
+
+    let _lock = InstanceLock::acquire(&InstanceLock::default_path(), false)?;
+    crate::commands::purge::delete_artifacts(
+        &selection.artifacts,
+        false,
+        None,
+        ConfirmPolicy::default(),
+    )
+}
+
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
+        app.poll_estimates();
+        app.poll_purge_scan();
+
         terminal.draw(|f| {
             match app.state {
                 AppState::Menu => menu::render_menu(f, app),
+                AppState::Help => {
+                    menu::render_menu(f, app);
+                    menu::render_help(f);
+                }
+                AppState::PurgeSelection => purge::render(f, app.purge.as_ref()),
                 AppState::Exiting => {}
             }
         })?;
@@ -70,8 +112,18 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
         // Handle events
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+                if key.kind == KeyEventKind::Press && app.state == AppState::Help {
+                    // The overlay swallows everything but its own dismiss keys
+                    if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+                        app.toggle_help();
+                    }
+                } else if key.kind == KeyEventKind::Press && app.state == AppState::PurgeSelection {
+                    handle_purge_key(app, key.code);
+                } else if key.kind == KeyEventKind::Press {
                     match key.code {
+                        KeyCode::Char('?') => {
+                            app.toggle_help();
+                        }
                         KeyCode::Char('q') | KeyCode::Esc => {
                             app.state = AppState::Exiting;
                         }
@@ -82,52 +134,10 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
                             app.move_selection(1);
                         }
                         KeyCode::Enter | KeyCode::Char(' ') => {
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
+                            app.choose();
                         }
-                        KeyCode::Char('1') => {
-                            app.selection = 0;
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
-                        }
-                        KeyCode::Char('2') => {
-                            app.selection = 1;
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
-                        }
-                        KeyCode::Char('3') => {
-                            app.selection = 2;
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
-                        }
-                        KeyCode::Char('4') => {
-                            app.selection = 3;
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
-                        }
-                        KeyCode::Char('5') => {
-                            app.selection = 4;
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
-                        }
-                        KeyCode::Char('6') => {
-                            app.selection = 5;
-                            app.select_action();
-                            if app.selected_action.is_some() {
-                                app.state = AppState::Exiting;
-                            }
+                        KeyCode::Char(c) if app.select_shortcut(c) => {
+                            app.choose();
                         }
                         _ => {}
                     }
@@ -136,3 +146,29 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
         }
     }
 }
+
+fn handle_purge_key(app: &mut App, code: KeyCode) {
+    if matches!(code, KeyCode::Char('q') | KeyCode::Esc) {
+        app.purge = None;
+        app.state = AppState::Menu;
+        return;
+    }
+    if code == KeyCode::Enter {
+        app.confirm_purge();
+        return;
+    }
+
+    // Keys below need the scan to have finished
+    let Some(selection) = app.purge.as_mut() else {
+        return;
+    };
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => selection.move_cursor(-1),
+        KeyCode::Down | KeyCode::Char('j') => selection.move_cursor(1),
+        KeyCode::PageUp => selection.move_cursor(-10),
+        KeyCode::PageDown => selection.move_cursor(10),
+        KeyCode::Char(' ') => selection.toggle(),
+        KeyCode::Char('s') => selection.cycle_sort(),
+        _ => {}
+    }
+}
@@ -0,0 +1,286 @@
+//! Fuzzy-searchable list for picking one or more items
+
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    prelude::*,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use std::collections::BTreeSet;
+use std::io;
+
+/// Score how well `query` fuzzy-matches `candidate`, higher is better
+///
+/// Every query character must appear in order (case-insensitively).
+/// Consecutive runs and matches at the start or after a separator score
+/// higher, so `vlc` ranks `vlc` above `libvlc5`.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = candidate[pos..].iter().position(|&c| c == q)? + pos;
+
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(candidate[found - 1], '-' | '_' | '.' | ' ' | '/') {
+            score += 3;
+        }
+        // Gaps cost a little, so tighter matches win ties
+        score -= (found - last_match.map_or(0, |last| last + 1)) as i64 / 4;
+
+        last_match = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score)
+}
+
+/// State of the picker: the query typed so far and what it narrows to
+#[derive(Debug, Default)]
+pub struct Picker {
+    items: Vec<String>,
+    query: String,
+    /// Indices into `items` that match `query`, best first
+    visible: Vec<usize>,
+    /// Position of the highlight within `visible`
+    cursor: usize,
+    /// Items marked with Tab
+    marked: BTreeSet<usize>,
+}
+
+impl Picker {
+    pub fn new(items: Vec<String>) -> Self {
+        let mut picker = Self {
+            items,
+            ..Default::default()
+        };
+        picker.refilter();
+        picker
+    }
+
+    fn refilter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| fuzzy_score(&self.query, item).map(|s| (s, i)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        self.visible = scored.into_iter().map(|(_, i)| i).collect();
+        self.cursor = 0;
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.refilter();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.refilter();
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let last = self.visible.len() as isize - 1;
+        self.cursor = (self.cursor as isize + delta).clamp(0, last) as usize;
+    }
+
+    /// Mark or unmark the highlighted item
+    pub fn toggle(&mut self) {
+        if let Some(&index) = self.visible.get(self.cursor) {
+            if !self.marked.remove(&index) {
+                self.marked.insert(index);
+            }
+        }
+    }
+
+    /// Indices to act on: the marked items, or else the highlighted one
+    pub fn chosen(&self) -> Vec<usize> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().copied().collect();
+        }
+        self.visible.get(self.cursor).copied().into_iter().collect()
+    }
+}
+
+/// Let the user pick items from `items` in a full-screen list
+///
+/// Typing filters, arrows move, Tab marks several, Enter accepts and Esc
+/// cancels. Returns the chosen indices, empty when cancelled.
+pub fn pick(title: &str, items: Vec<String>) -> Result<Vec<usize>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut picker = Picker::new(items);
+    let result = run_picker(&mut terminal, &mut picker, title);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_picker<B: Backend>(
+    terminal: &mut Terminal<B>,
+    picker: &mut Picker,
+    title: &str,
+) -> Result<Vec<usize>> {
+    let mut list_state = ListState::default();
+
+    loop {
+        list_state.select((!picker.visible.is_empty()).then_some(picker.cursor));
+        terminal.draw(|f| render(f, picker, title, &mut list_state))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Ok(Vec::new()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(Vec::new())
+            }
+            KeyCode::Enter => return Ok(picker.chosen()),
+            KeyCode::Up => picker.move_cursor(-1),
+            KeyCode::Down => picker.move_cursor(1),
+            KeyCode::PageUp => picker.move_cursor(-10),
+            KeyCode::PageDown => picker.move_cursor(10),
+            KeyCode::Tab => {
+                picker.toggle();
+                picker.move_cursor(1);
+            }
+            KeyCode::Backspace => picker.pop(),
+            KeyCode::Char(c) => picker.push(c),
+            _ => {}
+        }
+    }
+}
+
+fn render(f: &mut Frame, picker: &Picker, title: &str, list_state: &mut ListState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3), // Query
+            Constraint::Min(5),    // Matches
+            Constraint::Length(1), // Keys
+        ])
+        .split(f.size());
+
+    let query = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Cyan)),
+        Span::raw(picker.query.as_str()),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(format!(" {} ", title)),
+    );
+    f.render_widget(query, chunks[0]);
+
+    let items: Vec<ListItem> = picker
+        .visible
+        .iter()
+        .map(|&i| {
+            let mark = if picker.marked.contains(&i) { "● " } else { "  " };
+            ListItem::new(Line::from(vec![
+                Span::styled(mark, Style::default().fg(Color::Green)),
+                Span::raw(picker.items[i].as_str()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(format!(" {}/{} ", picker.visible.len(), picker.items.len())),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    f.render_stateful_widget(list, chunks[1], list_state);
+
+    let keys = Paragraph::new(Span::styled(
+        "type to filter · ↑↓ move · Tab mark · Enter select · Esc cancel",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(keys, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("ffx", "firefox").is_some());
+        assert!(fuzzy_score("FIRE", "firefox").is_some());
+        assert!(fuzzy_score("xf", "firefox").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+
+        // Prefix and consecutive matches beat scattered ones
+        assert!(fuzzy_score("vlc", "vlc") > fuzzy_score("vlc", "libvlc5"));
+        assert!(fuzzy_score("code", "code") > fuzzy_score("code", "c-o-d-e"));
+        assert!(fuzzy_score("gimp", "gimp") > fuzzy_score("gimp", "libgimp2.0"));
+    }
+
+    #[test]
+    fn test_picker_filter_and_choose() {
+        let mut picker = Picker::new(vec![
+            "vlc".to_string(),
+            "gimp".to_string(),
+            "libvlc5".to_string(),
+        ]);
+        assert_eq!(picker.visible, [0, 1, 2]);
+
+        picker.push('v');
+        picker.push('l');
+        assert_eq!(picker.visible, [0, 2]);
+        assert_eq!(picker.chosen(), [0]);
+
+        picker.move_cursor(1);
+        assert_eq!(picker.chosen(), [2]);
+        picker.move_cursor(5);
+        assert_eq!(picker.chosen(), [2]);
+
+        picker.toggle();
+        picker.move_cursor(-1);
+        picker.toggle();
+        assert_eq!(picker.chosen(), [0, 2]);
+
+        picker.pop();
+        picker.pop();
+        picker.push('z');
+        assert!(picker.visible.is_empty());
+        assert_eq!(picker.chosen(), [0, 2]);
+    }
+}
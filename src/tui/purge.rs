@@ -0,0 +1,256 @@
+//! Purge artifact checklist
+
+use std::cmp::Reverse;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::commands::purge::FoundArtifact;
+use crate::core::filesystem::format_size;
+
+/// Order of the artifact list
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SortKey {
+    /// Largest first
+    #[default]
+    Size,
+    /// Oldest first
+    Age,
+    /// Project name, A-Z
+    Name,
+}
+
+impl SortKey {
+    fn next(self) -> Self {
+        match self {
+            SortKey::Size => SortKey::Age,
+            SortKey::Age => SortKey::Name,
+            SortKey::Name => SortKey::Size,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortKey::Size => "size",
+            SortKey::Age => "age",
+            SortKey::Name => "name",
+        }
+    }
+}
+
+/// Artifacts found by the scan, with the user's choice of which to delete
+#[derive(Debug, Default)]
+pub struct PurgeSelection {
+    pub artifacts: Vec<FoundArtifact>,
+    pub cursor: usize,
+    pub sort: SortKey,
+}
+
+impl PurgeSelection {
+    pub fn new(artifacts: Vec<FoundArtifact>) -> Self {
+        let mut selection = Self {
+            artifacts,
+            ..Default::default()
+        };
+        selection.apply_sort();
+        selection
+    }
+
+    fn apply_sort(&mut self) {
+        match self.sort {
+            SortKey::Size => self.artifacts.sort_by_key(|a| Reverse(a.size)),
+            SortKey::Age => self.artifacts.sort_by_key(|a| Reverse(a.age_days)),
+            SortKey::Name => self.artifacts.sort_by(|a, b| {
+                a.project_name
+                    .to_lowercase()
+                    .cmp(&b.project_name.to_lowercase())
+                    .then_with(|| a.path.cmp(&b.path))
+            }),
+        }
+    }
+
+    /// Switch to the next sort order, keeping the highlight on the same artifact
+    pub fn cycle_sort(&mut self) {
+        let current = self.artifacts.get(self.cursor).map(|a| a.path.clone());
+        self.sort = self.sort.next();
+        self.apply_sort();
+        self.cursor = current
+            .and_then(|path| self.artifacts.iter().position(|a| a.path == path))
+            .unwrap_or(0);
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        if self.artifacts.is_empty() {
+            return;
+        }
+        let last = self.artifacts.len() as isize - 1;
+        self.cursor = (self.cursor as isize + delta).clamp(0, last) as usize;
+    }
+
+    /// Flip whether the highlighted artifact will be deleted
+    pub fn toggle(&mut self) {
+        if let Some(artifact) = self.artifacts.get_mut(self.cursor) {
+            artifact.selected = !artifact.selected;
+        }
+    }
+
+    /// Number and total size of the artifacts marked for deletion
+    pub fn selected_total(&self) -> (usize, u64) {
+        self.artifacts
+            .iter()
+            .filter(|a| a.selected)
+            .fold((0, 0), |(count, size), a| (count + 1, size + a.size))
+    }
+}
+
+/// Render the checklist, or a scanning note while `selection` is pending
+pub fn render(f: &mut Frame, selection: Option<&PurgeSelection>) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(5),    // Artifacts
+            Constraint::Length(1), // Totals
+            Constraint::Length(1), // Keys
+        ])
+        .split(f.size());
+
+    let block = |title: String| {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(title)
+    };
+
+    let Some(selection) = selection else {
+        let scanning = Paragraph::new(Span::styled(
+            "Scanning for development artifacts…",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(block(" Purge ".to_string()));
+        f.render_widget(scanning, chunks[0]);
+        return;
+    };
+
+    let items: Vec<ListItem> = selection
+        .artifacts
+        .iter()
+        .map(|a| {
+            let (mark, mark_style) = if a.selected {
+                ("● ", Style::default().fg(Color::Green))
+            } else {
+                ("○ ", Style::default().fg(Color::DarkGray))
+            };
+            let kind_style = if a.shared {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(mark, mark_style),
+                Span::styled(
+                    format!("{:<24}", a.project_name),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{:>10}  ", format_size(a.size)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!("{:>5}d  ", a.age_days),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(a.artifact_type.as_str(), kind_style),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    list_state.select((!selection.artifacts.is_empty()).then_some(selection.cursor));
+
+    let list = List::new(items)
+        .block(block(format!(
+            " Purge · {} artifacts · sorted by {} ",
+            selection.artifacts.len(),
+            selection.sort.label()
+        )))
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    let (count, size) = selection.selected_total();
+    let totals = Paragraph::new(Line::from(vec![
+        Span::raw(" Selected: "),
+        Span::styled(count.to_string(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" artifacts, "),
+        Span::styled(
+            format_size(size),
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    f.render_widget(totals, chunks[1]);
+
+    let keys = Paragraph::new(Span::styled(
+        " ↑↓ move · Space toggle · s sort · Enter delete selected · Esc back",
+        Style::default().fg(Color::DarkGray),
+    ));
+    f.render_widget(keys, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn artifact(name: &str, size: u64, age_days: u64) -> FoundArtifact {
+        FoundArtifact {
+            project_name: name.to_string(),
+            artifact_type: "Rust target".to_string(),
+            path: PathBuf::from(format!("/src/{}/target", name)),
+            size,
+            age_days,
+            selected: age_days > 7,
+            shared: false,
+        }
+    }
+
+    #[test]
+    fn test_purge_selection_sort_and_toggle() {
+        let mut selection = PurgeSelection::new(vec![
+            artifact("beta", 10, 30),
+            artifact("alpha", 300, 1),
+            artifact("gamma", 50, 90),
+        ]);
+        let names = |s: &PurgeSelection| {
+            s.artifacts.iter().map(|a| a.project_name.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(&selection), ["alpha", "gamma", "beta"]);
+        assert_eq!(selection.selected_total(), (2, 60));
+
+        // The highlight follows its artifact across sorts
+        selection.move_cursor(1);
+        selection.cycle_sort();
+        assert_eq!(selection.sort, SortKey::Age);
+        assert_eq!(names(&selection), ["gamma", "beta", "alpha"]);
+        assert_eq!(selection.cursor, 0);
+
+        selection.cycle_sort();
+        assert_eq!(names(&selection), ["alpha", "beta", "gamma"]);
+        assert_eq!(selection.cursor, 2);
+
+        selection.toggle();
+        selection.move_cursor(-5);
+        selection.toggle();
+        assert_eq!(selection.selected_total(), (2, 310));
+
+        selection.cycle_sort();
+        assert_eq!(selection.sort, SortKey::Size);
+    }
+}
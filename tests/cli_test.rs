@@ -23,6 +23,18 @@ fn test_version_command() {
         .stdout(predicate::str::contains("mo"));
 }
 
+/// Test --version-json prints build details
+#[test]
+fn test_version_json() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.arg("--version-json");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"mole-rs\""))
+        .stdout(predicate::str::contains("\"commit\""))
+        .stdout(predicate::str::contains("\"package_manager\""));
+}
+
 /// Test clean command with dry-run
 #[test]
 fn test_clean_dry_run() {
@@ -42,6 +54,112 @@ fn test_clean_debug() {
         .success();
 }
 
+/// Test clean category listing skips the size scan
+#[test]
+fn test_clean_categories() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--categories"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("User Cache"))
+        .stdout(predicate::str::contains("APT Cache"))
+        .stdout(predicate::str::contains("Total space to free").not());
+}
+
+/// Test analyze lists several paths as one row each with a grand total
+#[test]
+fn test_analyze_multiple_paths() {
+    let small = tempfile::TempDir::new().unwrap();
+    let large = tempfile::TempDir::new().unwrap();
+    std::fs::write(small.path().join("a.bin"), vec![0u8; 100]).unwrap();
+    std::fs::write(large.path().join("b.bin"), vec![0u8; 5000]).unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["analyze", "--summary-only"])
+        .arg(small.path())
+        .arg(large.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("Total: 4.98 KiB (2 items)\n"));
+}
+
+/// Test --log-file appends an uncolored copy of the output
+#[test]
+fn test_log_file() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("file.txt"), "hello").unwrap();
+    let log_dir = tempfile::TempDir::new().unwrap();
+    let log = log_dir.path().join("mo.log");
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.env("CLICOLOR_FORCE", "1")
+        .args(["analyze", temp.path().to_str().unwrap(), "--log-file"])
+        .arg(&log);
+    cmd.assert().success();
+
+    let content = std::fs::read_to_string(&log).unwrap();
+    assert!(content.contains("Mole-RS Disk Analyzer"));
+    assert!(content.contains("Total: 5 B (1 items)"));
+    assert!(!content.contains('\x1B'));
+}
+
+/// Test analyze --summary-only prints just the total
+#[test]
+fn test_analyze_summary_only() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("file.txt"), "hello").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["analyze", temp.path().to_str().unwrap(), "--summary-only"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("Total: 5 B (1 items)\n"));
+}
+
+/// Test analyze --filter only lists entries with matching files
+#[test]
+fn test_analyze_filter() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir(temp.path().join("images")).unwrap();
+    std::fs::write(temp.path().join("images/disk.iso"), "iso").unwrap();
+    std::fs::create_dir(temp.path().join("notes")).unwrap();
+    std::fs::write(temp.path().join("notes/todo.txt"), "todo").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["analyze", temp.path().to_str().unwrap(), "--filter", "*.iso"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("images"))
+        .stdout(predicate::str::contains("notes").not());
+}
+
+/// Test the global --timeout flag still reports results
+#[test]
+fn test_analyze_with_timeout() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("file.txt"), "hello").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["analyze", temp.path().to_str().unwrap(), "--timeout", "30"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Total"))
+        .stdout(predicate::str::contains("partial").not());
+}
+
+/// Test clean --group limits the categories shown
+#[test]
+fn test_clean_group_filter() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--categories", "--group", "log", "--group", "trash"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Journal Logs"))
+        .stdout(predicate::str::contains("Trash"))
+        .stdout(predicate::str::contains("APT Cache").not())
+        .stdout(predicate::str::contains("User Cache").not());
+}
+
 /// Test analyze command on temp directory
 #[test]
 fn test_analyze_temp_dir() {
@@ -71,13 +189,68 @@ fn test_analyze_empty_dir() {
         .stdout(predicate::str::contains("No files found"));
 }
 
+/// Test analyze lists entries largest first
+#[test]
+fn test_analyze_sorted_by_size() {
+    let temp = tempfile::TempDir::new().unwrap();
+
+    std::fs::write(temp.path().join("small.txt"), "a").unwrap();
+    std::fs::create_dir(temp.path().join("big_dir")).unwrap();
+    std::fs::write(temp.path().join("big_dir/data.bin"), vec![0u8; 4096]).unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.arg("analyze").arg(temp.path());
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8_lossy(&output);
+
+    let big = stdout.find("big_dir").unwrap();
+    let small = stdout.find("small.txt").unwrap();
+    assert!(big < small);
+}
+
+/// Test analyze buckets files by modification age
+#[test]
+fn test_analyze_age_histogram() {
+    let temp = tempfile::TempDir::new().unwrap();
+
+    let old = std::fs::File::create(temp.path().join("old.log")).unwrap();
+    old.set_len(2048).unwrap();
+    old.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(200 * 86400))
+        .unwrap();
+    std::fs::write(temp.path().join("fresh.txt"), "a").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.arg("analyze").arg(temp.path()).arg("--age-histogram");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8_lossy(&output);
+
+    let today = stdout.lines().find(|l| l.contains("today")).unwrap();
+    let older = stdout.lines().find(|l| l.contains("older")).unwrap();
+    assert!(today.contains("(1 files)"));
+    assert!(older.contains("2 KiB"));
+}
+
+/// Test status snapshot as JSON
+#[test]
+fn test_status_once_json() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["status", "--once", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("{"))
+        .stdout(predicate::str::contains("\"cpu_percent\""))
+        .stdout(predicate::str::contains("\"top_processes\""))
+        .stdout(predicate::str::contains("\x1B").not());
+}
+
 /// Test purge command with dry-run
 #[test]
 fn test_purge_dry_run() {
     let mut cmd = Command::cargo_bin("mo").unwrap();
     cmd.args(["purge", "--dry-run"]);
+    // 4 when the default project dirs hold no artifacts
     cmd.assert()
-        .success();
+        .code(predicate::in_iter([0, 4]));
 }
 
 /// Test purge command with custom paths
@@ -87,8 +260,84 @@ fn test_purge_with_paths() {
     
     let mut cmd = Command::cargo_bin("mo").unwrap();
     cmd.args(["purge", "--dry-run", "--paths", temp.path().to_str().unwrap()]);
+    // Nothing to purge in an empty directory has its own exit code
     cmd.assert()
-        .success();
+        .code(4)
+        .stdout(predicate::str::contains("No development artifacts found."));
+}
+
+/// Test purge writes a dry-run report
+#[test]
+fn test_purge_report() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let project = temp.path().join("app");
+    std::fs::create_dir_all(project.join("node_modules/lodash")).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(project.join("node_modules/lodash/index.js"), "x").unwrap();
+    let report = temp.path().join("plan.toml");
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["purge", "--dry-run", "--paths", temp.path().to_str().unwrap()])
+        .arg("--report")
+        .arg(&report);
+    cmd.assert().success();
+
+    let content = std::fs::read_to_string(&report).unwrap();
+    assert!(content.contains("command = \"purge\""));
+    assert!(content.contains("Node.js"));
+}
+
+/// Test restore moves a trashed file back to its original path
+#[test]
+fn test_restore_from_trash() {
+    let home = tempfile::TempDir::new().unwrap();
+    let trash = home.path().join(".local/share/Trash");
+    let original = home.path().join("docs/report.txt");
+    std::fs::create_dir_all(trash.join("files")).unwrap();
+    std::fs::create_dir_all(trash.join("info")).unwrap();
+    std::fs::write(trash.join("files/report.txt"), "data").unwrap();
+    std::fs::write(
+        trash.join("info/report.txt.trashinfo"),
+        format!(
+            "[Trash Info]\nPath={}\nDeletionDate=2024-01-01T12:00:00\n",
+            original.display()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.arg("restore").env("HOME", home.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("report.txt"));
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["restore", "1"]).env("HOME", home.path());
+    cmd.assert().success();
+
+    assert_eq!(std::fs::read_to_string(&original).unwrap(), "data");
+}
+
+/// Test quiet mode prints nothing on success
+#[test]
+fn test_clean_quiet() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--dry-run", "--quiet"]);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
+
+/// Test clean previews unless --apply is given
+#[test]
+fn test_clean_previews_by_default() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.arg("clean");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Cleaning...").not());
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--apply", "--dry-run"]);
+    cmd.assert().failure();
 }
 
 /// Test optimize command with dry-run
@@ -101,6 +350,253 @@ fn test_optimize_dry_run() {
         .stdout(predicate::str::contains("DRY RUN"));
 }
 
+/// Test --profile rejects names that could leave the profiles directory
+#[test]
+fn test_profile_name_validation() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--categories", "--profile", "../config"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid profile name"));
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--categories", "--profile", "missing-profile"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("using defaults"));
+}
+
+/// Test clean --parallel needs at least one worker
+#[test]
+fn test_clean_parallel_values() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--parallel", "0"]);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--parallel", "2"]);
+    cmd.assert().success();
+}
+
+/// Test a destructive command refuses to run while another holds the lock
+#[test]
+fn test_single_instance_lock() {
+    use std::os::unix::io::AsRawFd;
+
+    let home = tempfile::TempDir::new().unwrap();
+    let lock = home.path().join(".local/share/mole-rs/lock");
+    std::fs::create_dir_all(lock.parent().unwrap()).unwrap();
+    let file = std::fs::File::create(&lock).unwrap();
+    assert_eq!(unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) }, 0);
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["restore", "1"])
+        .env("HOME", home.path())
+        .env_remove("XDG_DATA_HOME");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Another mole-rs operation is running"));
+}
+
+/// Test the --no-recent-protection warning survives --quiet
+#[test]
+fn test_no_recent_protection_warns_when_quiet() {
+    let temp = tempfile::TempDir::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["--quiet", "purge", "--no-recent-protection", "--paths"])
+        .arg(temp.path());
+    cmd.assert()
+        .code(4)
+        .stdout(predicate::str::contains("--no-recent-protection:"));
+}
+
+/// Test analyze --group-by-fs prints per-filesystem totals
+#[test]
+fn test_analyze_group_by_fs() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp.path().join("file"), "data").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["analyze", "--group-by-fs"]).arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("By filesystem:"));
+}
+
+/// Test clean --preset values and the plan it prints
+#[test]
+fn test_clean_preset() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--preset", "reckless"]);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--preset", "safe", "--no-recent-protection"]);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--preset", "safe"]);
+    cmd.assert().code(predicate::in_iter([0, 4]));
+}
+
+/// Test scan reports a grand total without deleting anything
+#[test]
+fn test_scan_json() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["scan", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\":"))
+        .stdout(predicate::str::contains("\"name\": \"Purge\""));
+}
+
+/// Test analyze --format csv prints a header and one row per entry
+#[test]
+fn test_analyze_format_csv() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("a,b.txt"), "hello").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["analyze", "--format", "csv"]).arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("path,size_bytes,is_dir,percent\n"))
+        .stdout(predicate::str::contains("a,b.txt\",5,false,100.00"));
+}
+
+/// Test a broken config is reported on stderr, keeping JSON and CSV output clean
+#[test]
+fn test_broken_config_keeps_stdout_parseable() {
+    let home = tempfile::TempDir::new().unwrap();
+    let config_dir = home.path().join(".config/mole-rs");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "whitelist = [\n").unwrap();
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "hello").unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::cargo_bin("mo")
+            .unwrap()
+            .args(args)
+            .env("HOME", home.path())
+            .env("XDG_CONFIG_HOME", home.path().join(".config"))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Ignoring"));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let path = dir.path().to_str().unwrap();
+    let csv = run(&["analyze", "--format", "csv", path]);
+    assert!(csv.starts_with("path,size_bytes,is_dir,percent\n"));
+    assert_eq!(csv.lines().count(), 2);
+
+    for json in [run(&["analyze", "--format", "json", path]), run(&["scan", "--json"])] {
+        let json = json.trim();
+        assert!(json.starts_with(['[', '{']), "{}", json);
+        assert!(json.ends_with([']', '}']), "{}", json);
+        assert!(!json.contains("Ignoring"));
+    }
+}
+
+/// Test --stale-downloads only moves files after asking on a terminal
+#[test]
+fn test_stale_downloads_apply_needs_terminal() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--stale-downloads", "--apply"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--stale-downloads needs a terminal"));
+}
+
+/// Test optimize rejects task names it does not know
+#[test]
+fn test_optimize_only_unknown_task() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["optimize", "--dry-run", "--only", "no such task"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown optimize task \"no such task\""));
+}
+
+/// Test config check points at unknown keys and fails on them
+#[test]
+fn test_config_check_unknown_key() {
+    let home = tempfile::TempDir::new().unwrap();
+    let config_dir = home.path().join(".config/mole-rs");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "whitelsit = [\"/data\"]\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .args(["config", "check"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("unknown key `whitelsit`"));
+}
+
+/// Test --confirm-each refuses to run without a terminal to ask on
+#[test]
+fn test_confirm_each_needs_terminal() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--apply", "--confirm-each"])
+        .env("HOME", temp.path())
+        .write_stdin("a\n");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("--confirm-each needs a terminal"));
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["purge", "--apply", "--confirm-each", "--paths"])
+        .arg(temp.path())
+        .write_stdin("a\n");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("--confirm-each needs a terminal"));
+}
+
+/// Test --list-files only works with the preview
+#[test]
+fn test_clean_list_files() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--apply", "--list-files"]);
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["clean", "--list-files"]);
+    cmd.assert().code(predicate::in_iter([0, 4]));
+}
+
+/// Test uninstall without a name prints usage when there is no terminal to pick in
+#[test]
+fn test_uninstall_without_name_non_interactive() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.arg("uninstall");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Usage:"));
+}
+
+/// Test uninstall --type only accepts known package types
+#[test]
+fn test_uninstall_type_values() {
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["uninstall", "firefox", "--type", "rpm"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("appimage"));
+
+    let mut cmd = Command::cargo_bin("mo").unwrap();
+    cmd.args(["uninstall", "--help"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--type"));
+}
+
 /// Test unknown command returns error
 #[test]
 fn test_unknown_command() {